@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use alloy::primitives::U256;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::{
+    error::AppError,
+    jup::{
+        self,
+        quote::{QuoteRequest, SwapMode},
+        swap::SwapRequest,
+        transaction_config::TransactionConfig,
+        RateLimitedError,
+    },
+};
+
+use super::{AggQuote, AggQuoteData, AggRateLimits, AggSwapReq, Aggregator, AggregatorKind};
+
+/// Instructions of a solana swap
+#[derive(Debug, Clone)]
+pub struct SolSwap {
+    /// setup, swap and cleanup instructions in order
+    pub instructions: Vec<Instruction>,
+    pub address_lookup_table_addresses: Vec<Pubkey>,
+}
+
+pub type SolAggregator = dyn Aggregator<Address = Pubkey, Swap = SolSwap>;
+
+/// Routing options of jupiter quotes and swaps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JupiterRouteConfig {
+    pub only_direct_routes: bool,
+    pub use_shared_accounts: bool,
+    /// only route through these dexes, labels like `Raydium` or `Orca V2`
+    pub dexes: Option<Vec<String>>,
+    pub excluded_dexes: Option<Vec<String>>,
+    /// max accounts of the route, an estimation used by jupiter
+    pub max_accounts: Option<usize>,
+    /// only `ExactIn` is supported, the trade amounts of tasks are input amounts
+    pub swap_mode: SwapMode,
+}
+
+impl Default for JupiterRouteConfig {
+    fn default() -> Self {
+        Self {
+            only_direct_routes: true,
+            use_shared_accounts: false,
+            dexes: None,
+            excluded_dexes: None,
+            max_accounts: None,
+            swap_mode: SwapMode::ExactIn,
+        }
+    }
+}
+
+impl JupiterRouteConfig {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.swap_mode != SwapMode::ExactIn {
+            return Err(AppError::new(
+                "ExactOut swap mode is not supported, trade amounts are input amounts",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct JupiterAggregator {
+    http_client: reqwest::Client,
+    base_url: String,
+    /// sent in the `x-api-key` header when set
+    api_key: Option<String>,
+    route_config: JupiterRouteConfig,
+    rate_limits: Arc<AggRateLimits>,
+}
+
+impl JupiterAggregator {
+    pub fn new(
+        http_client: reqwest::Client,
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+        route_config: JupiterRouteConfig,
+        rate_limits: Arc<AggRateLimits>,
+    ) -> Self {
+        Self {
+            http_client,
+            base_url: base_url.into(),
+            api_key,
+            route_config,
+            rate_limits,
+        }
+    }
+
+    /// Rate limited responses are recorded so workers back off
+    fn map_err(&self, err: anyhow::Error) -> AppError {
+        if let Some(rate_limited) = err.downcast_ref::<RateLimitedError>() {
+            self.rate_limits
+                .limited(self.kind(), rate_limited.retry_after);
+        }
+        AppError::new(err.to_string())
+    }
+}
+
+#[async_trait]
+impl Aggregator for JupiterAggregator {
+    type Address = Pubkey;
+    type Swap = SolSwap;
+
+    fn kind(&self) -> AggregatorKind {
+        AggregatorKind::Jupiter
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Pubkey>) -> Result<AggQuote, AppError> {
+        let amount = u64::try_from(req.amount)
+            .map_err(|_| AppError::new(format!("amount {} overflows u64", req.amount)))?;
+        let quote_req = QuoteRequest {
+            amount,
+            input_mint: req.input_token,
+            output_mint: req.output_token,
+            slippage_bps: req.slippage,
+            swap_mode: Some(self.route_config.swap_mode.clone()),
+            dexes: self.route_config.dexes.clone(),
+            excluded_dexes: self.route_config.excluded_dexes.clone(),
+            only_direct_routes: Some(self.route_config.only_direct_routes),
+            max_accounts: self.route_config.max_accounts,
+            ..Default::default()
+        };
+        let api_key = self.api_key.as_deref();
+        let quote_response = jup::quote(&self.http_client, &self.base_url, api_key, &quote_req)
+            .await
+            .map_err(|err| self.map_err(err))?;
+
+        Ok(AggQuote {
+            aggregator: self.kind(),
+            in_amount: U256::from(quote_response.in_amount),
+            out_amount: U256::from(quote_response.out_amount),
+            data: AggQuoteData::Jupiter(Box::new(quote_response)),
+        })
+    }
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Pubkey>,
+        quote: &AggQuote,
+    ) -> Result<SolSwap, AppError> {
+        let AggQuoteData::Jupiter(quote_response) = &quote.data else {
+            return Err(AppError::new(format!(
+                "quote of {} can't build a jupiter swap",
+                quote.aggregator
+            )));
+        };
+
+        let swap_req = SwapRequest {
+            quote_response: *quote_response.clone(),
+            user_public_key: req.user,
+            config: TransactionConfig {
+                use_shared_accounts: self.route_config.use_shared_accounts,
+                ..Default::default()
+            },
+        };
+        let api_key = self.api_key.as_deref();
+        let swap_ixs_resp =
+            jup::swap_instructions(&self.http_client, &self.base_url, api_key, &swap_req)
+                .await
+                .map_err(|err| self.map_err(err))?;
+
+        let mut instructions = swap_ixs_resp.setup_instructions;
+        instructions.push(swap_ixs_resp.swap_instruction);
+        instructions.extend(swap_ixs_resp.cleanup_instruction);
+
+        Ok(SolSwap {
+            instructions,
+            address_lookup_table_addresses: swap_ixs_resp.address_lookup_table_addresses,
+        })
+    }
+}
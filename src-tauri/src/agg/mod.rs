@@ -0,0 +1,306 @@
+use std::sync::Arc;
+
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use strum::Display;
+
+use crate::{
+    chain::Chain, error::AppError, jup::quote::QuoteResponse, project::Project,
+    state::EvmRpcProvider,
+};
+
+mod jupiter;
+mod one_inch;
+mod rate_limit;
+mod raydium;
+mod router;
+
+pub use jupiter::*;
+pub use one_inch::*;
+pub use rate_limit::*;
+pub use raydium::*;
+pub use router::*;
+
+/// Swap aggregators a task can route its trades through
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AggregatorKind {
+    Jupiter,
+    OneInch,
+    /// swaps built from on-chain pool state, no api needed
+    Raydium,
+    UniswapV2,
+    UniswapV3,
+    PancakeSwapV2,
+    PancakeSwapV3,
+}
+
+impl AggregatorKind {
+    /// Aggregator of the chain `agg_api_url` of the project points to
+    pub fn default_for(chain: Chain) -> Self {
+        match chain {
+            Chain::Solana => Self::Jupiter,
+            Chain::Base | Chain::Bsc => Self::OneInch,
+        }
+    }
+
+    pub fn supports(&self, chain: Chain) -> bool {
+        match self {
+            Self::Jupiter | Self::Raydium => matches!(chain, Chain::Solana),
+            Self::OneInch => matches!(chain, Chain::Base | Chain::Bsc),
+            Self::UniswapV2 | Self::UniswapV3 => matches!(chain, Chain::Base),
+            Self::PancakeSwapV2 | Self::PancakeSwapV3 => matches!(chain, Chain::Bsc),
+        }
+    }
+
+    fn default_api_url(&self) -> &'static str {
+        match self {
+            Self::Jupiter => "https://quote-api.jup.ag/v6",
+            Self::OneInch => "https://api.1inch.dev",
+            _ => "",
+        }
+    }
+}
+
+/// Where an aggregator api is reached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggApiConfig {
+    pub kind: AggregatorKind,
+    /// base path of the api, for jupiter the path `/quote` and `/swap-instructions`
+    /// are under, which is the root of a self-hosted api
+    pub api_url: String,
+    /// bearer token of 1inch, `x-api-key` header of jupiter
+    pub api_key: Option<String>,
+}
+
+impl AggApiConfig {
+    /// Api of the aggregator configured in the project, the chain default aggregator
+    /// falls back to `agg_api_url` and the others to their public api
+    pub fn resolve(project: &Project, kind: AggregatorKind) -> Result<Self, AppError> {
+        if !kind.supports(project.chain) {
+            return Err(AppError::new(format!(
+                "aggregator {kind} not supports chain {}",
+                project.chain
+            )));
+        }
+
+        if let Some(api) = project.agg_apis.iter().find(|api| api.kind == kind) {
+            return Ok(api.clone());
+        }
+
+        let api = if kind == AggregatorKind::default_for(project.chain) {
+            Self {
+                kind,
+                api_url: project.agg_api_url.clone(),
+                api_key: project.agg_api_key.clone(),
+            }
+        } else {
+            Self {
+                kind,
+                api_url: kind.default_api_url().to_string(),
+                api_key: None,
+            }
+        };
+
+        Ok(api)
+    }
+}
+
+/// A swap to quote, amounts are in the smallest unit of the token
+#[derive(Debug, Clone)]
+pub struct AggSwapReq<T> {
+    pub input_token: T,
+    pub output_token: T,
+    pub amount: U256,
+    /// task slippage, passed as is to the aggregator
+    pub slippage: u16,
+    /// wallet which signs the swap
+    pub user: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct AggQuote {
+    pub aggregator: AggregatorKind,
+    pub in_amount: U256,
+    pub out_amount: U256,
+    data: AggQuoteData,
+}
+
+/// A hop of the route a quote goes through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggRouteStep {
+    pub label: String,
+    /// pool of the hop
+    pub amm_key: String,
+    pub input_token: String,
+    pub output_token: String,
+    /// percent of the input routed through the hop
+    pub percent: u8,
+}
+
+impl AggQuote {
+    /// `None` when the aggregator doesn't report its route
+    pub fn route_plan(&self) -> Option<Vec<AggRouteStep>> {
+        match &self.data {
+            AggQuoteData::Jupiter(quote_response) => {
+                let steps = quote_response
+                    .route_plan
+                    .iter()
+                    .map(|step| AggRouteStep {
+                        label: step.swap_info.label.clone(),
+                        amm_key: step.swap_info.amm_key.to_string(),
+                        input_token: step.swap_info.input_mint.to_string(),
+                        output_token: step.swap_info.output_mint.to_string(),
+                        percent: step.percent,
+                    })
+                    .collect();
+                Some(steps)
+            }
+            AggQuoteData::Raydium {
+                pool,
+                input_mint,
+                output_mint,
+            } => Some(vec![pool.route_step(input_mint, output_mint)]),
+            _ => None,
+        }
+    }
+}
+
+/// Aggregator specific quote, needed to build the swap
+#[derive(Debug, Clone)]
+enum AggQuoteData {
+    Jupiter(Box<QuoteResponse>),
+    OneInch {
+        /// only returned with `include_gas`
+        gas: Option<u128>,
+    },
+    Raydium {
+        pool: Box<RaydiumPool>,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+    },
+    V2Router {
+        path: Vec<Address>,
+    },
+    V3Router {
+        token_in: Address,
+        token_out: Address,
+        fee: u32,
+        /// quoter gas estimate
+        gas: u128,
+    },
+}
+
+/// Quote a swap and build its instructions or transaction
+#[async_trait]
+pub trait Aggregator: Send + Sync {
+    /// token and wallet address type of the chain
+    type Address: Send + Sync;
+    type Swap: Send;
+
+    fn kind(&self) -> AggregatorKind;
+
+    /// Contract the input token has to be approved to, `None` when no approval is needed
+    fn spender(&self) -> Option<Self::Address> {
+        None
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Self::Address>) -> Result<AggQuote, AppError>;
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Self::Address>,
+        quote: &AggQuote,
+    ) -> Result<Self::Swap, AppError>;
+
+    /// Quote of a trade with its swap, aggregators whose swap api returns the
+    /// out amount override it to skip the quote request
+    async fn quote_swap(
+        &self,
+        req: &AggSwapReq<Self::Address>,
+    ) -> Result<(AggQuote, Self::Swap), AppError> {
+        let quote = self.quote(req).await?;
+        let swap = self.build_swap(req, &quote).await?;
+        Ok((quote, swap))
+    }
+}
+
+impl AggApiConfig {
+    /// `rpc_client` is used by raydium to read pool accounts, so fixture
+    /// accounts served by a local validator are enough to try it
+    pub fn build_sol(
+        &self,
+        http_client: reqwest::Client,
+        rpc_client: Arc<RpcClient>,
+        jupiter_route_config: &JupiterRouteConfig,
+        rate_limits: Arc<AggRateLimits>,
+        raydium_pools: Arc<RaydiumPoolCache>,
+    ) -> Result<Box<SolAggregator>, AppError> {
+        match self.kind {
+            AggregatorKind::Jupiter => Ok(Box::new(JupiterAggregator::new(
+                http_client,
+                &self.api_url,
+                self.api_key.clone(),
+                jupiter_route_config.clone(),
+                rate_limits,
+            ))),
+            AggregatorKind::Raydium => {
+                Ok(Box::new(RaydiumAggregator::new(rpc_client, raydium_pools)))
+            }
+            kind => Err(AppError::new(format!(
+                "aggregator {kind} not supports solana"
+            ))),
+        }
+    }
+
+    /// `provider` is used by the router swaps to quote with `eth_call`,
+    /// the ignored fork tests of `router` run them against anvil forks
+    pub fn build_evm(
+        &self,
+        http_client: reqwest::Client,
+        provider: EvmRpcProvider,
+        chain: Chain,
+        one_inch_route_config: &OneInchRouteConfig,
+        rate_limits: Arc<AggRateLimits>,
+    ) -> Result<Box<EvmAggregator>, AppError> {
+        let chain_config = chain
+            .evm_chain_config()
+            .ok_or_else(|| AppError::new(format!("{chain} is not an evm chain")))?;
+        match self.kind {
+            AggregatorKind::OneInch => Ok(Box::new(OneInchAggregator::new(
+                http_client,
+                &self.api_url,
+                self.api_key.clone().unwrap_or_default(),
+                chain_config.named_chain.into(),
+                chain_config.one_inch_router_addr,
+                one_inch_route_config.clone(),
+                rate_limits,
+            ))),
+            AggregatorKind::UniswapV2 | AggregatorKind::PancakeSwapV2
+                if self.kind.supports(chain) =>
+            {
+                Ok(Box::new(V2RouterAggregator::new(
+                    self.kind,
+                    provider,
+                    chain_config.dex_v2,
+                    chain_config.wrapped_native_addr,
+                )))
+            }
+            AggregatorKind::UniswapV3 | AggregatorKind::PancakeSwapV3
+                if self.kind.supports(chain) =>
+            {
+                Ok(Box::new(V3RouterAggregator::new(
+                    self.kind,
+                    provider,
+                    chain_config.dex_v3,
+                    chain_config.wrapped_native_addr,
+                )))
+            }
+            kind => Err(AppError::new(format!(
+                "aggregator {kind} not supports {chain}"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use alloy::{
+    primitives::{Address, U256},
+    rpc::types::TransactionRequest,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    one_inch::{self, OneInchError, QuoteQueryParams, SwapQueryParams},
+};
+
+use super::{AggQuote, AggQuoteData, AggRateLimits, AggSwapReq, Aggregator, AggregatorKind};
+
+/// Transaction of an evm swap, fees are left unset
+#[derive(Debug, Clone)]
+pub struct EvmSwap {
+    pub tx: TransactionRequest,
+    /// gas limit estimated by the aggregator
+    pub gas: u128,
+}
+
+pub type EvmAggregator = dyn Aggregator<Address = Address, Swap = EvmSwap>;
+
+/// Parameters of 1inch quotes and swaps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OneInchRouteConfig {
+    /// only route through these liquidity sources, like `BASE_UNISWAP_V3`
+    pub protocols: Option<Vec<String>>,
+    /// skip the balance and allowance checks of the api when building the swap
+    pub disable_estimate: bool,
+    /// return the gas of the swap with quotes
+    pub include_gas: bool,
+}
+
+impl Default for OneInchRouteConfig {
+    fn default() -> Self {
+        Self {
+            protocols: None,
+            disable_estimate: false,
+            include_gas: true,
+        }
+    }
+}
+
+pub struct OneInchAggregator {
+    http_client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    chain_id: u64,
+    router_addr: Address,
+    route_config: OneInchRouteConfig,
+    rate_limits: Arc<AggRateLimits>,
+}
+
+impl OneInchAggregator {
+    pub fn new(
+        http_client: reqwest::Client,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+        chain_id: u64,
+        router_addr: Address,
+        route_config: OneInchRouteConfig,
+        rate_limits: Arc<AggRateLimits>,
+    ) -> Self {
+        Self {
+            http_client,
+            base_url: base_url.into(),
+            api_key: api_key.into(),
+            chain_id,
+            router_addr,
+            route_config,
+            rate_limits,
+        }
+    }
+
+    fn protocols(&self) -> Option<String> {
+        self.route_config
+            .protocols
+            .as_ref()
+            .filter(|protocols| !protocols.is_empty())
+            .map(|protocols| protocols.join(","))
+    }
+
+    /// Rate limited responses are recorded so workers back off
+    fn map_err(&self, err: OneInchError) -> AppError {
+        if let OneInchError::RateLimited { retry_after } = err {
+            self.rate_limits.limited(self.kind(), retry_after);
+        }
+        AppError::new(err.to_string())
+    }
+
+    /// Swap with the out amount of the `/swap` response, `quote_gas` is used
+    /// when the api skipped its gas estimate
+    async fn swap(
+        &self,
+        req: &AggSwapReq<Address>,
+        quote_gas: Option<u128>,
+    ) -> Result<(U256, EvmSwap), AppError> {
+        let swap_query_params = SwapQueryParams {
+            src: req.input_token,
+            dst: req.output_token,
+            amount: req.amount,
+            from: req.user,
+            origin: req.user,
+            slippage: req.slippage,
+            protocols: self.protocols(),
+            disable_estimate: self.route_config.disable_estimate,
+            include_gas: self.route_config.include_gas,
+        };
+        let resp = one_inch::get_swap_data(
+            &self.http_client,
+            &self.base_url,
+            &self.api_key,
+            self.chain_id,
+            swap_query_params,
+        )
+        .await
+        .map_err(|err| self.map_err(err))?;
+
+        // NOTE: gas of the swap is 0 when the api skipped its estimate
+        let gas = match resp.tx.gas {
+            0 => quote_gas.unwrap_or_default(),
+            gas => gas,
+        };
+        let tx = TransactionRequest::try_from(resp.tx)?;
+
+        Ok((resp.dst_amount, EvmSwap { tx, gas }))
+    }
+}
+
+#[async_trait]
+impl Aggregator for OneInchAggregator {
+    type Address = Address;
+    type Swap = EvmSwap;
+
+    fn kind(&self) -> AggregatorKind {
+        AggregatorKind::OneInch
+    }
+
+    fn spender(&self) -> Option<Address> {
+        Some(self.router_addr)
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Address>) -> Result<AggQuote, AppError> {
+        let quote_query_params = QuoteQueryParams {
+            src: req.input_token,
+            dst: req.output_token,
+            amount: req.amount,
+            protocols: self.protocols(),
+            include_gas: self.route_config.include_gas,
+        };
+        let resp = one_inch::get_quote(
+            &self.http_client,
+            &self.base_url,
+            &self.api_key,
+            self.chain_id,
+            quote_query_params,
+        )
+        .await
+        .map_err(|err| self.map_err(err))?;
+
+        Ok(AggQuote {
+            aggregator: self.kind(),
+            in_amount: req.amount,
+            out_amount: resp.dst_amount,
+            data: AggQuoteData::OneInch { gas: resp.gas },
+        })
+    }
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Address>,
+        quote: &AggQuote,
+    ) -> Result<EvmSwap, AppError> {
+        let AggQuoteData::OneInch { gas: quote_gas } = quote.data else {
+            return Err(AppError::new(format!(
+                "quote of {} can't build a 1inch swap",
+                quote.aggregator
+            )));
+        };
+
+        let (_, swap) = self.swap(req, quote_gas).await?;
+        Ok(swap)
+    }
+
+    async fn quote_swap(&self, req: &AggSwapReq<Address>) -> Result<(AggQuote, EvmSwap), AppError> {
+        let (out_amount, swap) = self.swap(req, None).await?;
+        let quote = AggQuote {
+            aggregator: self.kind(),
+            in_amount: req.amount,
+            out_amount,
+            data: AggQuoteData::OneInch { gas: None },
+        };
+        Ok((quote, swap))
+    }
+}
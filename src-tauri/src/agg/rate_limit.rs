@@ -0,0 +1,43 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use super::AggregatorKind;
+
+/// wait when a rate limited api gives no `Retry-After`
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(10);
+
+/// Rate limits reported by aggregator apis, shared by the workers of a task
+#[derive(Debug, Default)]
+pub struct AggRateLimits(Mutex<HashMap<AggregatorKind, Instant>>);
+
+impl AggRateLimits {
+    pub fn limited(&self, kind: AggregatorKind, retry_after: Option<Duration>) {
+        let until = Instant::now() + retry_after.unwrap_or(DEFAULT_RETRY_AFTER);
+        let mut limits = self.0.lock().unwrap();
+        let entry = limits.entry(kind).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+
+    /// Time left until the aggregator can be used again, `None` when it isn't limited
+    pub fn remaining(&self, kind: AggregatorKind) -> Option<Duration> {
+        let limits = self.0.lock().unwrap();
+        limits
+            .get(&kind)
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Time until any of the aggregators can be used again,
+    /// `None` when one of them isn't limited
+    pub fn backoff(&self, kinds: impl IntoIterator<Item = AggregatorKind>) -> Option<Duration> {
+        let mut backoff: Option<Duration> = None;
+        for kind in kinds {
+            let remaining = self.remaining(kind)?;
+            backoff = Some(backoff.map_or(remaining, |backoff| backoff.min(remaining)));
+        }
+        backoff
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use alloy::primitives::U256;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::debug;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, instruction::Instruction, pubkey::Pubkey, system_instruction};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{
+    consts::{RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID, WSOL_MINT},
+    error::AppError,
+    raydium::{
+        amm_v4::{self, AmmInfo, MarketInfo},
+        cpmm::{self, PoolState},
+        token_account_amount,
+    },
+};
+
+use super::{
+    AggQuote, AggQuoteData, AggRouteStep, AggSwapReq, Aggregator, AggregatorKind, SolSwap,
+};
+
+/// pools of a pair are searched again after this, to pick up new pools
+const POOL_IDS_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy)]
+enum RaydiumPoolKind {
+    AmmV4,
+    Cpmm,
+}
+
+type PoolIds = Vec<(RaydiumPoolKind, Pubkey)>;
+
+/// Pools found for each pair, shared by the workers of a task so the
+/// `getProgramAccounts` scans run once per pair instead of on every quote
+#[derive(Debug, Default)]
+pub struct RaydiumPoolCache(Mutex<HashMap<(Pubkey, Pubkey), (Instant, PoolIds)>>);
+
+impl RaydiumPoolCache {
+    fn key(mints: (Pubkey, Pubkey)) -> (Pubkey, Pubkey) {
+        if mints.0 <= mints.1 {
+            mints
+        } else {
+            (mints.1, mints.0)
+        }
+    }
+
+    fn get(&self, mints: (Pubkey, Pubkey)) -> Option<PoolIds> {
+        let pools = self.0.lock().unwrap();
+        pools
+            .get(&Self::key(mints))
+            .filter(|(found_at, _)| found_at.elapsed() < POOL_IDS_TTL)
+            .map(|(_, ids)| ids.clone())
+    }
+
+    fn insert(&self, mints: (Pubkey, Pubkey), ids: PoolIds) {
+        let mut pools = self.0.lock().unwrap();
+        pools.insert(Self::key(mints), (Instant::now(), ids));
+    }
+}
+
+/// Pool a raydium quote was computed on
+#[derive(Debug, Clone)]
+pub enum RaydiumPool {
+    AmmV4 { id: Pubkey, info: AmmInfo },
+    Cpmm { id: Pubkey, state: PoolState },
+}
+
+impl RaydiumPool {
+    pub fn route_step(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> AggRouteStep {
+        let (label, id) = match self {
+            Self::AmmV4 { id, .. } => ("Raydium", id),
+            Self::Cpmm { id, .. } => ("Raydium CP", id),
+        };
+        AggRouteStep {
+            label: label.to_string(),
+            amm_key: id.to_string(),
+            input_token: input_mint.to_string(),
+            output_token: output_mint.to_string(),
+            percent: 100,
+        }
+    }
+}
+
+/// Swap through raydium AMM v4 and CPMM pools of the pair, pools are found with
+/// `getProgramAccounts` once per pair and the best quote of them is used
+pub struct RaydiumAggregator {
+    rpc_client: Arc<RpcClient>,
+    pool_cache: Arc<RaydiumPoolCache>,
+}
+
+impl RaydiumAggregator {
+    pub fn new(rpc_client: Arc<RpcClient>, pool_cache: Arc<RaydiumPoolCache>) -> Self {
+        Self {
+            rpc_client,
+            pool_cache,
+        }
+    }
+
+    /// Ids of the pools of the pair, cached for [`POOL_IDS_TTL`] once any is found
+    async fn pool_ids(&self, mints: (Pubkey, Pubkey)) -> Result<PoolIds, AppError> {
+        if let Some(ids) = self.pool_cache.get(mints) {
+            return Ok(ids);
+        }
+
+        let (amm_pools, cpmm_pools) = futures::try_join!(
+            self.find_pools(
+                &RAYDIUM_AMM_V4_PROGRAM_ID,
+                amm_v4::AMM_INFO_LEN,
+                (amm_v4::COIN_MINT_OFFSET, amm_v4::PC_MINT_OFFSET),
+                mints,
+            ),
+            self.find_pools(
+                &RAYDIUM_CPMM_PROGRAM_ID,
+                cpmm::POOL_STATE_LEN,
+                (cpmm::TOKEN_0_MINT_OFFSET, cpmm::TOKEN_1_MINT_OFFSET),
+                mints,
+            ),
+        )?;
+        let ids: PoolIds = amm_pools
+            .into_iter()
+            .map(|id| (RaydiumPoolKind::AmmV4, id))
+            .chain(cpmm_pools.into_iter().map(|id| (RaydiumPoolKind::Cpmm, id)))
+            .collect();
+        // NOTE: a pool may be created soon for a new token, keep searching until then
+        if !ids.is_empty() {
+            self.pool_cache.insert(mints, ids.clone());
+        }
+
+        Ok(ids)
+    }
+
+    /// Pool accounts of the program with the mints at the offsets, in both orders,
+    /// only the ids are fetched
+    async fn find_pools(
+        &self,
+        program_id: &Pubkey,
+        data_len: u64,
+        mint_offsets: (usize, usize),
+        mints: (Pubkey, Pubkey),
+    ) -> Result<Vec<Pubkey>, AppError> {
+        let mut pools = vec![];
+        for (mint_a, mint_b) in [(mints.0, mints.1), (mints.1, mints.0)] {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(vec![
+                    RpcFilterType::DataSize(data_len),
+                    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                        mint_offsets.0,
+                        mint_a.as_ref(),
+                    )),
+                    RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                        mint_offsets.1,
+                        mint_b.as_ref(),
+                    )),
+                ]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: 0,
+                        length: 0,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let accounts = self
+                .rpc_client
+                .get_program_accounts_with_config(program_id, config)
+                .await?;
+            pools.extend(accounts.into_iter().map(|(id, _)| id));
+        }
+
+        Ok(pools)
+    }
+
+    async fn get_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>, AppError> {
+        let mut accounts = Vec::with_capacity(pubkeys.len());
+        // NOTE: `getMultipleAccounts` takes at most 100 accounts
+        for chunk in pubkeys.chunks(100) {
+            accounts.extend(self.rpc_client.get_multiple_accounts(chunk).await?);
+        }
+        Ok(accounts)
+    }
+}
+
+#[async_trait]
+impl Aggregator for RaydiumAggregator {
+    type Address = Pubkey;
+    type Swap = SolSwap;
+
+    fn kind(&self) -> AggregatorKind {
+        AggregatorKind::Raydium
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Pubkey>) -> Result<AggQuote, AppError> {
+        let amount = u64::try_from(req.amount)
+            .map_err(|_| AppError::new(format!("amount {} overflows u64", req.amount)))?;
+        let mints = (req.input_token, req.output_token);
+        let now = Utc::now().timestamp() as u64;
+
+        let pool_ids = self.pool_ids(mints).await?;
+        let ids: Vec<Pubkey> = pool_ids.iter().map(|(_, id)| *id).collect();
+        let pool_accounts = self.get_accounts(&ids).await?;
+
+        let mut pools = vec![];
+        for ((kind, id), account) in pool_ids.into_iter().zip(pool_accounts) {
+            let Some(account) = account else {
+                continue;
+            };
+            let pool = match kind {
+                RaydiumPoolKind::AmmV4 => AmmInfo::unpack(&account.data).map(|info| {
+                    info.is_swappable(now)
+                        .then_some(RaydiumPool::AmmV4 { id, info })
+                }),
+                RaydiumPoolKind::Cpmm => PoolState::unpack(&account.data).map(|state| {
+                    state
+                        .is_swappable(now)
+                        .then_some(RaydiumPool::Cpmm { id, state })
+                }),
+            };
+            match pool {
+                Ok(Some(pool)) => pools.push(pool),
+                Ok(None) => {}
+                Err(err) => debug!("skip raydium pool {id}: {}", err.err_msg),
+            }
+        }
+        if pools.is_empty() {
+            return Err(AppError::new(format!(
+                "raydium pool of {} and {} not found",
+                req.input_token, req.output_token
+            )));
+        }
+
+        // vaults of every pool, followed by the amm config of a cpmm pool
+        let mut pubkeys = vec![];
+        for pool in &pools {
+            match pool {
+                RaydiumPool::AmmV4 { info, .. } => {
+                    pubkeys.extend([info.coin_vault, info.pc_vault]);
+                }
+                RaydiumPool::Cpmm { state, .. } => {
+                    pubkeys.extend([state.token_0_vault, state.token_1_vault, state.amm_config]);
+                }
+            }
+        }
+        let mut accounts = self.get_accounts(&pubkeys).await?.into_iter();
+
+        let mut best: Option<(u64, RaydiumPool)> = None;
+        for pool in pools {
+            let account_cnt = match pool {
+                RaydiumPool::AmmV4 { .. } => 2,
+                RaydiumPool::Cpmm { .. } => 3,
+            };
+            let accounts: Vec<Option<Account>> = accounts.by_ref().take(account_cnt).collect();
+            let [Some(vault_a), Some(vault_b), rest @ ..] = accounts.as_slice() else {
+                continue;
+            };
+            let (Ok(vault_a_amount), Ok(vault_b_amount)) = (
+                token_account_amount(&vault_a.data),
+                token_account_amount(&vault_b.data),
+            ) else {
+                continue;
+            };
+
+            let out_amount = match &pool {
+                RaydiumPool::AmmV4 { info, .. } => {
+                    let coin_to_pc = info.coin_mint == req.input_token;
+                    info.quote(amount, coin_to_pc, vault_a_amount, vault_b_amount)
+                }
+                RaydiumPool::Cpmm { state, .. } => {
+                    let Some(Some(amm_config)) = rest.first() else {
+                        continue;
+                    };
+                    let Ok(trade_fee_rate) = cpmm::unpack_trade_fee_rate(&amm_config.data) else {
+                        continue;
+                    };
+                    let zero_to_one = state.token_0_mint == req.input_token;
+                    state.quote(
+                        amount,
+                        zero_to_one,
+                        trade_fee_rate,
+                        vault_a_amount,
+                        vault_b_amount,
+                    )
+                }
+            };
+
+            if best
+                .as_ref()
+                .map_or(true, |(best_out, _)| out_amount > *best_out)
+            {
+                best = Some((out_amount, pool));
+            }
+        }
+
+        let Some((out_amount, pool)) = best.filter(|(out_amount, _)| *out_amount > 0) else {
+            return Err(AppError::new("raydium quote out amount is zero"));
+        };
+
+        Ok(AggQuote {
+            aggregator: self.kind(),
+            in_amount: req.amount,
+            out_amount: U256::from(out_amount),
+            data: AggQuoteData::Raydium {
+                pool: Box::new(pool),
+                input_mint: req.input_token,
+                output_mint: req.output_token,
+            },
+        })
+    }
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Pubkey>,
+        quote: &AggQuote,
+    ) -> Result<SolSwap, AppError> {
+        let AggQuoteData::Raydium { pool, .. } = &quote.data else {
+            return Err(AppError::new(format!(
+                "quote of {} can't build a raydium swap",
+                quote.aggregator
+            )));
+        };
+
+        let amount_in = u64::try_from(quote.in_amount)
+            .map_err(|_| AppError::new(format!("amount {} overflows u64", quote.in_amount)))?;
+        let out_amount = u64::try_from(quote.out_amount)
+            .map_err(|_| AppError::new(format!("amount {} overflows u64", quote.out_amount)))?;
+        // NOTE: slippage of solana tasks is in basis points
+        let minimum_amount_out =
+            (out_amount as u128 * 10_000u128.saturating_sub(req.slippage as u128) / 10_000) as u64;
+
+        let (input_program, output_program) = match pool.as_ref() {
+            RaydiumPool::AmmV4 { .. } => (spl_token::id(), spl_token::id()),
+            RaydiumPool::Cpmm { state, .. } => {
+                if state.token_0_mint == req.input_token {
+                    (state.token_0_program, state.token_1_program)
+                } else {
+                    (state.token_1_program, state.token_0_program)
+                }
+            }
+        };
+        let user = req.user;
+        let input_account =
+            get_associated_token_address_with_program_id(&user, &req.input_token, &input_program);
+        let output_account =
+            get_associated_token_address_with_program_id(&user, &req.output_token, &output_program);
+
+        let mut instructions = vec![];
+        if req.input_token == WSOL_MINT {
+            instructions.extend(wrap_sol_ixs(&user, &input_account, amount_in)?);
+        }
+        instructions.push(create_associated_token_account_idempotent(
+            &user,
+            &user,
+            &req.output_token,
+            &output_program,
+        ));
+
+        let swap_ix = match pool.as_ref() {
+            RaydiumPool::AmmV4 { id, info } => {
+                let market_account = self.rpc_client.get_account(&info.market).await?;
+                let market =
+                    MarketInfo::unpack(&market_account.data, &info.market, &info.market_program)?;
+                amm_v4::swap_base_in_ix(
+                    id,
+                    info,
+                    &market,
+                    &input_account,
+                    &output_account,
+                    &user,
+                    amount_in,
+                    minimum_amount_out,
+                )
+            }
+            RaydiumPool::Cpmm { id, state } => cpmm::swap_base_input_ix(
+                id,
+                state,
+                state.token_0_mint == req.input_token,
+                &user,
+                &input_account,
+                &output_account,
+                amount_in,
+                minimum_amount_out,
+            ),
+        };
+        instructions.push(swap_ix);
+
+        // NOTE: unwrap the whole wsol account back to sol like jupiter does
+        let wsol_account = if req.input_token == WSOL_MINT {
+            Some(input_account)
+        } else if req.output_token == WSOL_MINT {
+            Some(output_account)
+        } else {
+            None
+        };
+        if let Some(wsol_account) = wsol_account {
+            instructions.push(spl_token::instruction::close_account(
+                &spl_token::id(),
+                &wsol_account,
+                &user,
+                &user,
+                &[],
+            )?);
+        }
+
+        Ok(SolSwap {
+            instructions,
+            address_lookup_table_addresses: vec![],
+        })
+    }
+}
+
+fn wrap_sol_ixs(
+    user: &Pubkey,
+    wsol_account: &Pubkey,
+    lamports: u64,
+) -> Result<Vec<Instruction>, AppError> {
+    Ok(vec![
+        create_associated_token_account_idempotent(user, user, &WSOL_MINT, &spl_token::id()),
+        system_instruction::transfer(user, wsol_account, lamports),
+        spl_token::instruction::sync_native(&spl_token::id(), wsol_account)?,
+    ])
+}
@@ -0,0 +1,487 @@
+use alloy::{
+    primitives::{aliases::U24, Address, Bytes, U160, U256},
+    sol_types::SolCall,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+
+use crate::{
+    chain::{DexV2Config, DexV3Config},
+    consts::ONE_INCH_NATIVE_COIN_ADDR,
+    contracts::{
+        IQuoterV2::QuoteExactInputSingleParams,
+        IV3SwapRouter::ExactInputSingleParams,
+        UniswapV2FactoryContract, UniswapV2RouterContract, UniswapV3FactoryContract,
+        UniswapV3QuoterContract,
+        UniswapV3RouterContract::{self, exactInputSingleCall, unwrapWETH9Call},
+    },
+    error::AppError,
+    state::EvmRpcProvider,
+};
+
+use super::{AggQuote, AggQuoteData, AggSwapReq, Aggregator, AggregatorKind, EvmSwap};
+
+/// router swaps expire after this
+const SWAP_DEADLINE_SECS: i64 = 20 * 60;
+/// gas of a v2 swap over one pair
+const V2_SWAP_GAS: u128 = 250_000;
+/// gas of the router on top of the quoter estimate of a v3 swap
+const V3_ROUTER_GAS: u128 = 100_000;
+/// `ADDRESS_THIS` of the v3 router, keeps the output to unwrap it
+const V3_ROUTER_SELF_ADDR: Address = Address::with_last_byte(2);
+
+/// Swap through a Uniswap V2 style router, the pair of the token and
+/// wrapped native coin is quoted with `getAmountsOut`
+///
+/// Tokens taxing transfers are not supported, `getAmountsOut` quotes the output
+/// before the tax, so their swaps revert when the tax is above the slippage.
+pub struct V2RouterAggregator {
+    kind: AggregatorKind,
+    provider: EvmRpcProvider,
+    dex: DexV2Config,
+    wrapped_native_addr: Address,
+}
+
+impl V2RouterAggregator {
+    pub fn new(
+        kind: AggregatorKind,
+        provider: EvmRpcProvider,
+        dex: DexV2Config,
+        wrapped_native_addr: Address,
+    ) -> Self {
+        Self {
+            kind,
+            provider,
+            dex,
+            wrapped_native_addr,
+        }
+    }
+
+    fn path(&self, req: &AggSwapReq<Address>) -> Vec<Address> {
+        vec![
+            wrapped_if_native(req.input_token, self.wrapped_native_addr),
+            wrapped_if_native(req.output_token, self.wrapped_native_addr),
+        ]
+    }
+}
+
+#[async_trait]
+impl Aggregator for V2RouterAggregator {
+    type Address = Address;
+    type Swap = EvmSwap;
+
+    fn kind(&self) -> AggregatorKind {
+        self.kind
+    }
+
+    fn spender(&self) -> Option<Address> {
+        Some(self.dex.router_addr)
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Address>) -> Result<AggQuote, AppError> {
+        let path = self.path(req);
+        let factory = UniswapV2FactoryContract::new(self.dex.factory_addr, self.provider.clone());
+        let pair = factory.getPair(path[0], path[1]).call().await?.pair;
+        if pair.is_zero() {
+            return Err(AppError::new(format!(
+                "{} pair of {} and {} not found",
+                self.kind, path[0], path[1]
+            )));
+        }
+
+        let router = UniswapV2RouterContract::new(self.dex.router_addr, self.provider.clone());
+        let amounts = router
+            .getAmountsOut(req.amount, path.clone())
+            .call()
+            .await?
+            .amounts;
+        let out_amount = amounts.last().copied().unwrap_or_default();
+        if out_amount.is_zero() {
+            return Err(AppError::new(format!(
+                "{} quote out amount is zero",
+                self.kind
+            )));
+        }
+
+        Ok(AggQuote {
+            aggregator: self.kind,
+            in_amount: req.amount,
+            out_amount,
+            data: AggQuoteData::V2Router { path },
+        })
+    }
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Address>,
+        quote: &AggQuote,
+    ) -> Result<EvmSwap, AppError> {
+        let AggQuoteData::V2Router { path } = &quote.data else {
+            return Err(AppError::new(format!(
+                "quote of {} can't build a {} swap",
+                quote.aggregator, self.kind
+            )));
+        };
+
+        let router = UniswapV2RouterContract::new(self.dex.router_addr, self.provider.clone());
+        let amount_out_min = amount_out_min(quote.out_amount, req.slippage);
+        let path = path.clone();
+        let deadline = swap_deadline();
+        // NOTE: fee on transfer variants also work for plain tokens, a taxed token
+        // still needs a slippage above its tax as the quote is before the tax
+        let tx = if req.input_token == ONE_INCH_NATIVE_COIN_ADDR {
+            router
+                .swapExactETHForTokensSupportingFeeOnTransferTokens(
+                    amount_out_min,
+                    path,
+                    req.user,
+                    deadline,
+                )
+                .value(req.amount)
+                .from(req.user)
+                .into_transaction_request()
+        } else if req.output_token == ONE_INCH_NATIVE_COIN_ADDR {
+            router
+                .swapExactTokensForETHSupportingFeeOnTransferTokens(
+                    req.amount,
+                    amount_out_min,
+                    path,
+                    req.user,
+                    deadline,
+                )
+                .from(req.user)
+                .into_transaction_request()
+        } else {
+            router
+                .swapExactTokensForTokensSupportingFeeOnTransferTokens(
+                    req.amount,
+                    amount_out_min,
+                    path,
+                    req.user,
+                    deadline,
+                )
+                .from(req.user)
+                .into_transaction_request()
+        };
+
+        Ok(EvmSwap {
+            tx,
+            gas: V2_SWAP_GAS,
+        })
+    }
+}
+
+/// Swap through a Uniswap V3 style `SwapRouter02`, every fee tier pool of the
+/// token and wrapped native coin is quoted with the quoter and the best one is used
+pub struct V3RouterAggregator {
+    kind: AggregatorKind,
+    provider: EvmRpcProvider,
+    dex: DexV3Config,
+    wrapped_native_addr: Address,
+}
+
+impl V3RouterAggregator {
+    pub fn new(
+        kind: AggregatorKind,
+        provider: EvmRpcProvider,
+        dex: DexV3Config,
+        wrapped_native_addr: Address,
+    ) -> Self {
+        Self {
+            kind,
+            provider,
+            dex,
+            wrapped_native_addr,
+        }
+    }
+}
+
+#[async_trait]
+impl Aggregator for V3RouterAggregator {
+    type Address = Address;
+    type Swap = EvmSwap;
+
+    fn kind(&self) -> AggregatorKind {
+        self.kind
+    }
+
+    fn spender(&self) -> Option<Address> {
+        Some(self.dex.router_addr)
+    }
+
+    async fn quote(&self, req: &AggSwapReq<Address>) -> Result<AggQuote, AppError> {
+        let token_in = wrapped_if_native(req.input_token, self.wrapped_native_addr);
+        let token_out = wrapped_if_native(req.output_token, self.wrapped_native_addr);
+        let factory = UniswapV3FactoryContract::new(self.dex.factory_addr, self.provider.clone());
+        let quoter = UniswapV3QuoterContract::new(self.dex.quoter_addr, self.provider.clone());
+
+        // (fee, out amount, gas estimate)
+        let mut best: Option<(u32, U256, U256)> = None;
+        for &fee in self.dex.fee_tiers {
+            let pool = factory
+                .getPool(token_in, token_out, U24::from(fee))
+                .call()
+                .await?
+                .pool;
+            if pool.is_zero() {
+                continue;
+            }
+
+            let params = QuoteExactInputSingleParams {
+                tokenIn: token_in,
+                tokenOut: token_out,
+                amountIn: req.amount,
+                fee: U24::from(fee),
+                sqrtPriceLimitX96: U160::ZERO,
+            };
+            // NOTE: quoter reverts when the pool has no liquidity for the amount
+            let Ok(resp) = quoter.quoteExactInputSingle(params).call().await else {
+                continue;
+            };
+            if best.map_or(true, |(_, out_amount, _)| resp.amountOut > out_amount) {
+                best = Some((fee, resp.amountOut, resp.gasEstimate));
+            }
+        }
+
+        let Some((fee, out_amount, gas_estimate)) = best.filter(|best| !best.1.is_zero()) else {
+            return Err(AppError::new(format!(
+                "{} pool of {token_in} and {token_out} not found",
+                self.kind
+            )));
+        };
+
+        Ok(AggQuote {
+            aggregator: self.kind,
+            in_amount: req.amount,
+            out_amount,
+            data: AggQuoteData::V3Router {
+                token_in,
+                token_out,
+                fee,
+                gas: gas_estimate.saturating_to(),
+            },
+        })
+    }
+
+    async fn build_swap(
+        &self,
+        req: &AggSwapReq<Address>,
+        quote: &AggQuote,
+    ) -> Result<EvmSwap, AppError> {
+        let AggQuoteData::V3Router {
+            token_in,
+            token_out,
+            fee,
+            gas,
+        } = quote.data
+        else {
+            return Err(AppError::new(format!(
+                "quote of {} can't build a {} swap",
+                quote.aggregator, self.kind
+            )));
+        };
+
+        let amount_out_min = amount_out_min(quote.out_amount, req.slippage);
+        let unwrap_output = req.output_token == ONE_INCH_NATIVE_COIN_ADDR;
+        let recipient = if unwrap_output {
+            V3_ROUTER_SELF_ADDR
+        } else {
+            req.user
+        };
+
+        let params = ExactInputSingleParams {
+            tokenIn: token_in,
+            tokenOut: token_out,
+            fee: U24::from(fee),
+            recipient,
+            amountIn: req.amount,
+            amountOutMinimum: amount_out_min,
+            sqrtPriceLimitX96: U160::ZERO,
+        };
+        let mut calls = vec![Bytes::from(exactInputSingleCall { params }.abi_encode())];
+        if unwrap_output {
+            let unwrap_call = unwrapWETH9Call {
+                amountMinimum: amount_out_min,
+                recipient: req.user,
+            };
+            calls.push(Bytes::from(unwrap_call.abi_encode()));
+        }
+
+        let value = if req.input_token == ONE_INCH_NATIVE_COIN_ADDR {
+            req.amount
+        } else {
+            U256::ZERO
+        };
+        let router = UniswapV3RouterContract::new(self.dex.router_addr, self.provider.clone());
+        let tx = router
+            .multicall(swap_deadline(), calls)
+            .value(value)
+            .from(req.user)
+            .into_transaction_request();
+
+        Ok(EvmSwap {
+            tx,
+            gas: gas + V3_ROUTER_GAS,
+        })
+    }
+}
+
+/// routers only swap the wrapped native coin
+fn wrapped_if_native(token: Address, wrapped_native_addr: Address) -> Address {
+    if token == ONE_INCH_NATIVE_COIN_ADDR {
+        wrapped_native_addr
+    } else {
+        token
+    }
+}
+
+/// `slippage` is in percent like the 1inch api
+fn amount_out_min(out_amount: U256, slippage: u16) -> U256 {
+    let slippage = U256::from(slippage.min(100));
+    out_amount * (U256::from(100) - slippage) / U256::from(100)
+}
+
+fn swap_deadline() -> U256 {
+    U256::from(Utc::now().timestamp() + SWAP_DEADLINE_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_out_min_takes_slippage_percent() {
+        let out_amount = U256::from(2_500_000u64);
+
+        assert_eq!(amount_out_min(out_amount, 0), out_amount);
+        assert_eq!(amount_out_min(out_amount, 1), U256::from(2_475_000u64));
+        assert_eq!(amount_out_min(out_amount, 15), U256::from(2_125_000u64));
+    }
+
+    #[test]
+    fn amount_out_min_rounds_down_and_caps_slippage() {
+        assert_eq!(amount_out_min(U256::from(999u64), 1), U256::from(989u64));
+        assert_eq!(amount_out_min(U256::from(1_000u64), 100), U256::ZERO);
+        assert_eq!(amount_out_min(U256::from(1_000u64), 250), U256::ZERO);
+    }
+
+    #[test]
+    fn routers_swap_the_wrapped_native_coin() {
+        let wrapped_native_addr = Address::with_last_byte(1);
+        let token = Address::with_last_byte(9);
+
+        assert_eq!(
+            wrapped_if_native(ONE_INCH_NATIVE_COIN_ADDR, wrapped_native_addr),
+            wrapped_native_addr
+        );
+        assert_eq!(wrapped_if_native(token, wrapped_native_addr), token);
+    }
+}
+
+/// Buys against anvil forks of the chains, started with e.g.
+/// `anvil --fork-url <base rpc>` and `anvil --fork-url <bsc rpc> --port 8546`, run with
+/// `BASE_FORK_URL=http://127.0.0.1:8545 BSC_FORK_URL=http://127.0.0.1:8546 cargo test -- --ignored`
+#[cfg(test)]
+mod fork_tests {
+    use std::{env, str::FromStr};
+
+    use alloy::{
+        network::EthereumWallet,
+        primitives::address,
+        providers::{Provider, ProviderBuilder},
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+    use crate::{agg::EvmAggregator, chain::Chain, contracts::Erc20Contract};
+
+    /// first prefunded account of anvil
+    const ANVIL_PK: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const BASE_USDC_ADDR: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+    const BSC_USDT_ADDR: Address = address!("55d398326f99059fF775485246999027B3197955");
+
+    /// quotes 0.01 native coin to `token` and sends the swap built from the quote
+    async fn buy_on_fork(chain: Chain, kind: AggregatorKind, token: Address) {
+        let env_key = match chain {
+            Chain::Bsc => "BSC_FORK_URL",
+            _ => "BASE_FORK_URL",
+        };
+        let url = env::var(env_key)
+            .unwrap_or_else(|_| panic!("{env_key} is not set"))
+            .parse()
+            .unwrap();
+        let chain_config = chain.evm_chain_config().unwrap();
+        let signer = PrivateKeySigner::from_str(ANVIL_PK).unwrap();
+        let user = signer.address();
+        let provider: EvmRpcProvider = ProviderBuilder::new().on_http(url);
+        let wallet_provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_provider(provider.clone());
+
+        let aggregator: Box<EvmAggregator> = match kind {
+            AggregatorKind::UniswapV2 | AggregatorKind::PancakeSwapV2 => {
+                Box::new(V2RouterAggregator::new(
+                    kind,
+                    provider.clone(),
+                    chain_config.dex_v2,
+                    chain_config.wrapped_native_addr,
+                ))
+            }
+            _ => Box::new(V3RouterAggregator::new(
+                kind,
+                provider.clone(),
+                chain_config.dex_v3,
+                chain_config.wrapped_native_addr,
+            )),
+        };
+        let req = AggSwapReq {
+            input_token: ONE_INCH_NATIVE_COIN_ADDR,
+            output_token: token,
+            amount: U256::from(10_000_000_000_000_000u64),
+            slippage: 1,
+            user,
+        };
+        let quote = aggregator.quote(&req).await.unwrap();
+        assert!(!quote.out_amount.is_zero());
+        let swap = aggregator.build_swap(&req, &quote).await.unwrap();
+
+        let token_contract = Erc20Contract::new(token, provider.clone());
+        let balance_before = token_contract.balanceOf(user).call().await.unwrap().balance;
+        let receipt = wallet_provider
+            .send_transaction(swap.tx)
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        assert!(receipt.status(), "{kind} swap reverted");
+        let balance_after = token_contract.balanceOf(user).call().await.unwrap().balance;
+
+        assert!(balance_after - balance_before >= amount_out_min(quote.out_amount, req.slippage));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs an anvil fork of base at BASE_FORK_URL"]
+    async fn uniswap_v2_buys_on_base_fork() {
+        buy_on_fork(Chain::Base, AggregatorKind::UniswapV2, BASE_USDC_ADDR).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs an anvil fork of base at BASE_FORK_URL"]
+    async fn uniswap_v3_buys_on_base_fork() {
+        buy_on_fork(Chain::Base, AggregatorKind::UniswapV3, BASE_USDC_ADDR).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs an anvil fork of bsc at BSC_FORK_URL"]
+    async fn pancakeswap_v2_buys_on_bsc_fork() {
+        buy_on_fork(Chain::Bsc, AggregatorKind::PancakeSwapV2, BSC_USDT_ADDR).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs an anvil fork of bsc at BSC_FORK_URL"]
+    async fn pancakeswap_v3_buys_on_bsc_fork() {
+        buy_on_fork(Chain::Bsc, AggregatorKind::PancakeSwapV3, BSC_USDT_ADDR).await;
+    }
+}
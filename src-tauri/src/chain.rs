@@ -1,16 +1,15 @@
-use std::time::{Duration, Instant};
-
 use alloy::primitives::Address;
 use async_trait::async_trait;
-
-use log::debug;
 use serde::{Deserialize, Serialize};
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_config::RpcSimulateTransactionConfig,
+    rpc_response::RpcSimulateTransactionResult,
+};
 use solana_sdk::{
     address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount},
     message::{v0::Message, VersionedMessage},
     pubkey::Pubkey,
-    signature::{Keypair, Signature},
+    signature::Keypair,
     signer::Signer,
     system_instruction,
     transaction::VersionedTransaction,
@@ -19,18 +18,46 @@ use strum::Display;
 
 use crate::{
     consts::{
-        BASE_MOO_TOKEN_HUB_ADDR, BASE_ONE_INCH_V6_ROUTER_ADDR, BASE_WETH_ADDR,
-        BSC_MOO_TOKEN_HUB_ADDR, BSC_ONE_INCH_V6_ROUTER_ADDR, BSC_WBNB_ADDR,
+        BASE_MOO_TOKEN_HUB_ADDR, BASE_ONE_INCH_V6_ROUTER_ADDR, BASE_UNISWAP_V2_FACTORY_ADDR,
+        BASE_UNISWAP_V2_ROUTER_ADDR, BASE_UNISWAP_V3_FACTORY_ADDR, BASE_UNISWAP_V3_QUOTER_ADDR,
+        BASE_UNISWAP_V3_ROUTER_ADDR, BASE_WETH_ADDR, BSC_MOO_TOKEN_HUB_ADDR,
+        BSC_ONE_INCH_V6_ROUTER_ADDR, BSC_PANCAKE_V2_FACTORY_ADDR, BSC_PANCAKE_V2_ROUTER_ADDR,
+        BSC_PANCAKE_V3_FACTORY_ADDR, BSC_PANCAKE_V3_QUOTER_ADDR, BSC_PANCAKE_V3_ROUTER_ADDR,
+        BSC_WBNB_ADDR, PANCAKE_V3_FEE_TIERS, UNISWAP_V3_FEE_TIERS,
     },
     error::AppError,
+    sol::{SolChainCache, SolTxOutcome, SolTxSender},
 };
 
+/// Uniswap V2 style dex deployment
+#[derive(Debug, Clone, Copy)]
+pub struct DexV2Config {
+    pub factory_addr: Address,
+    pub router_addr: Address,
+}
+
+/// Uniswap V3 style dex deployment, the router is a `SwapRouter02`
+#[derive(Debug, Clone, Copy)]
+pub struct DexV3Config {
+    pub factory_addr: Address,
+    pub quoter_addr: Address,
+    pub router_addr: Address,
+    pub fee_tiers: &'static [u32],
+}
+
 pub struct EvmChainConfig {
     pub named_chain: alloy_chains::NamedChain,
     pub moo_hub_addr: Address,
     pub native_symbol: &'static str,
     pub wrapped_native_addr: Address,
     pub one_inch_router_addr: Address,
+    /// OP-stack chains charge an extra L1 data fee, read from the `GasPriceOracle` predeploy
+    pub is_op_stack: bool,
+    /// blocks on top of a receipt's block before a transaction is final
+    pub confirmations: u64,
+    /// Uniswap on Base, PancakeSwap on BSC
+    pub dex_v2: DexV2Config,
+    pub dex_v3: DexV3Config,
 }
 
 #[derive(Debug, Display, Copy, Clone, Serialize, Deserialize, Default)]
@@ -42,6 +69,13 @@ pub enum Chain {
 }
 
 impl Chain {
+    pub fn native_decimals(&self) -> u8 {
+        match self {
+            Chain::Solana => 9,
+            Chain::Base | Chain::Bsc => 18,
+        }
+    }
+
     pub fn evm_chain_config(&self) -> Option<EvmChainConfig> {
         match self {
             Chain::Solana => None,
@@ -51,6 +85,18 @@ impl Chain {
                 native_symbol: "ETH",
                 wrapped_native_addr: BASE_WETH_ADDR,
                 one_inch_router_addr: BASE_ONE_INCH_V6_ROUTER_ADDR,
+                is_op_stack: true,
+                confirmations: 3,
+                dex_v2: DexV2Config {
+                    factory_addr: BASE_UNISWAP_V2_FACTORY_ADDR,
+                    router_addr: BASE_UNISWAP_V2_ROUTER_ADDR,
+                },
+                dex_v3: DexV3Config {
+                    factory_addr: BASE_UNISWAP_V3_FACTORY_ADDR,
+                    quoter_addr: BASE_UNISWAP_V3_QUOTER_ADDR,
+                    router_addr: BASE_UNISWAP_V3_ROUTER_ADDR,
+                    fee_tiers: UNISWAP_V3_FEE_TIERS,
+                },
             }),
             Chain::Bsc => Some(EvmChainConfig {
                 named_chain: alloy_chains::NamedChain::BinanceSmartChain,
@@ -58,6 +104,18 @@ impl Chain {
                 native_symbol: "BNB",
                 wrapped_native_addr: BSC_WBNB_ADDR,
                 one_inch_router_addr: BSC_ONE_INCH_V6_ROUTER_ADDR,
+                is_op_stack: false,
+                confirmations: 3,
+                dex_v2: DexV2Config {
+                    factory_addr: BSC_PANCAKE_V2_FACTORY_ADDR,
+                    router_addr: BSC_PANCAKE_V2_ROUTER_ADDR,
+                },
+                dex_v3: DexV3Config {
+                    factory_addr: BSC_PANCAKE_V3_FACTORY_ADDR,
+                    quoter_addr: BSC_PANCAKE_V3_QUOTER_ADDR,
+                    router_addr: BSC_PANCAKE_V3_ROUTER_ADDR,
+                    fee_tiers: PANCAKE_V3_FEE_TIERS,
+                },
             }),
         }
     }
@@ -70,6 +128,8 @@ pub trait SolRpcClientExt {
         from: Keypair,
         to: &[Pubkey],
         per_amount: u64,
+        cache: &SolChainCache,
+        sender: &SolTxSender,
     ) -> Result<String, AppError>;
 
     async fn get_address_lookup_tables(
@@ -77,7 +137,11 @@ pub trait SolRpcClientExt {
         pubkeys: &[Pubkey],
     ) -> Result<Vec<AddressLookupTableAccount>, AppError>;
 
-    async fn confirm_tx(&self, txid: Signature) -> Result<(), AppError>;
+    /// Simulate without signature verification against the latest blockhash
+    async fn simulate_tx(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> Result<RpcSimulateTransactionResult, AppError>;
 }
 
 #[async_trait]
@@ -87,6 +151,8 @@ impl SolRpcClientExt for RpcClient {
         from: Keypair,
         to: &[Pubkey],
         per_amount: u64,
+        cache: &SolChainCache,
+        sender: &SolTxSender,
     ) -> Result<String, AppError> {
         let total_need = per_amount * to.len() as u64;
 
@@ -107,16 +173,25 @@ impl SolRpcClientExt for RpcClient {
 
         let transfer_ixs = system_instruction::transfer_many(&from_pubkey, &to_lamports);
 
-        let recent_blockhash = self.get_latest_blockhash().await?;
+        let (recent_blockhash, last_valid_block_height) = cache.get_latest_blockhash().await?;
         let tx_msg = Message::try_compile(&from_pubkey, &transfer_ixs, &[], recent_blockhash)?;
         let tx_msg = VersionedMessage::V0(tx_msg);
         let tx = VersionedTransaction::try_new(tx_msg, &[&from])?;
-
-        let sign = self.send_transaction(&tx).await?;
-
-        self.confirm_tx(sign).await?;
-
-        Ok(sign.to_string())
+        let sign = tx.signatures[0];
+
+        match sender
+            .send_and_confirm(self, &tx, last_valid_block_height)
+            .await?
+        {
+            SolTxOutcome::Landed => Ok(sign.to_string()),
+            SolTxOutcome::Failed(err) => Err(AppError::new(err.to_string())),
+            SolTxOutcome::Expired => Err(AppError::new(format!(
+                "transaction {sign} expired before confirmed"
+            ))),
+            SolTxOutcome::Dropped => Err(AppError::new(format!(
+                "transaction {sign} dropped before confirmed"
+            ))),
+        }
     }
 
     async fn get_address_lookup_tables(
@@ -144,28 +219,18 @@ impl SolRpcClientExt for RpcClient {
         Ok(result)
     }
 
-    async fn confirm_tx(&self, txid: Signature) -> Result<(), AppError> {
-        let start_time = Instant::now();
-        loop {
-            tokio::time::sleep(Duration::from_secs(2)).await;
-            debug!("confirming tranaction {} ...", txid);
-            let statuses = self.get_signature_statuses(&[txid]).await?.value;
-
-            if let Some(status) = statuses[0].clone() {
-                if let Some(err) = status.err {
-                    return Err(AppError::new(err.to_string()));
-                } else {
-                    break;
-                }
-            }
-
-            // NOTE: max tx expire time 120s in solana_sdk
-            //
-            if start_time.elapsed().as_secs() > 120 {
-                return Err(AppError::new("transaction not confirmed after 120 seconds"));
-            }
-        }
-
-        Ok(())
+    async fn simulate_tx(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> Result<RpcSimulateTransactionResult, AppError> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.commitment()),
+            ..Default::default()
+        };
+        let result = self.simulate_transaction_with_config(tx, config).await?;
+
+        Ok(result.value)
     }
 }
@@ -17,14 +17,17 @@ use solana_sdk::{
     signature::Keypair,
     signer::Signer,
 };
-use tauri::{command, AppHandle};
+use tauri::{command, AppHandle, Manager};
 
 use crate::{
     chain::{Chain, SolRpcClientExt},
     consts::SOL_TX_BASE_FEE,
     contracts::MooTokenHubContract,
     error::AppError,
-    state::AppHandleStateExt,
+    evm::{get_op_l1_fee, EvmFeeMode, EvmPendingTx, EvmReplaceAction, EvmTxConfig},
+    jito::JitoRegionStats,
+    sol::SolEndpointStats,
+    state::{AppHandleStateExt, EvmNonceManagerState, JitoRegionsState, SolEndpointStatsState},
     token::TokenInfo,
 };
 
@@ -42,6 +45,18 @@ pub struct AirdropReq {
     pub addrs: Vec<String>,
     pub per_amount: f64,
     pub per_w_amount: Option<f64>,
+    /// only used by evm chains
+    #[serde(default)]
+    pub fee_mode: EvmFeeMode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceEvmTxReq {
+    pub from_pk: String,
+    /// nonce of the pending transaction to replace
+    pub nonce: u64,
+    #[serde(default)]
+    pub fee_mode: EvmFeeMode,
 }
 
 #[derive(Debug, Deserialize)]
@@ -51,6 +66,9 @@ pub struct TransferNativeReq {
     pub addr: String,
     /// 'max' or float number
     pub amount: String,
+    /// only used by evm chains
+    #[serde(default)]
+    pub fee_mode: EvmFeeMode,
 }
 
 #[command(async)]
@@ -113,6 +131,70 @@ pub async fn get_addr_balance(
     Ok(result)
 }
 
+#[command(async)]
+pub async fn get_sol_endpoint_stats(
+    app_handle: AppHandle,
+) -> Result<Vec<SolEndpointStats>, AppError> {
+    let stats = app_handle.state::<SolEndpointStatsState>();
+
+    Ok(stats.snapshot())
+}
+
+#[command(async)]
+pub async fn get_jito_regions(app_handle: AppHandle) -> Result<Vec<JitoRegionStats>, AppError> {
+    let regions = app_handle.state::<JitoRegionsState>();
+
+    Ok(regions.snapshot())
+}
+
+#[command(async)]
+pub async fn get_evm_pending_txs(app_handle: AppHandle) -> Result<Vec<EvmPendingTx>, AppError> {
+    let nonce_manager = app_handle.state::<EvmNonceManagerState>();
+
+    Ok(nonce_manager.pending_txs().await)
+}
+
+#[command(async, rename_all = "snake_case")]
+pub async fn speed_up_evm_tx(
+    req: ReplaceEvmTxReq,
+    app_handle: AppHandle,
+) -> Result<String, AppError> {
+    replace_evm_tx(req, EvmReplaceAction::SpeedUp, app_handle).await
+}
+
+#[command(async, rename_all = "snake_case")]
+pub async fn cancel_evm_tx(
+    req: ReplaceEvmTxReq,
+    app_handle: AppHandle,
+) -> Result<String, AppError> {
+    replace_evm_tx(req, EvmReplaceAction::Cancel, app_handle).await
+}
+
+async fn replace_evm_tx(
+    req: ReplaceEvmTxReq,
+    action: EvmReplaceAction,
+    app_handle: AppHandle,
+) -> Result<String, AppError> {
+    let pk_bytes = alloy::hex::decode(&req.from_pk)?;
+    let wallet_signer = PrivateKeySigner::from_slice(&pk_bytes)?;
+    let wallet_addr = wallet_signer.address();
+
+    let rpc_client = app_handle.read_evm_rpc_client().await?;
+    let rpc_provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::from(wallet_signer))
+        .on_client(rpc_client);
+
+    let fees = req.fee_mode.estimate(&rpc_provider).await?;
+    let tx_hash = app_handle
+        .read_evm_tx_sender(EvmTxConfig::default())
+        .await?
+        .replace(&rpc_provider, wallet_addr, req.nonce, action, Some(fees))
+        .await?;
+
+    Ok(tx_hash.to_string())
+}
+
 #[command(async)]
 pub async fn get_token_info(
     chain: Chain,
@@ -141,11 +223,14 @@ pub async fn airdrop(req: AirdropReq, app_handle: AppHandle) -> Result<String, A
         addrs,
         per_amount,
         per_w_amount,
+        fee_mode,
     } = req;
 
     let tx_id = match chain {
         Chain::Solana => {
             let rpc_client = app_handle.read_sol_rpc_client().await?;
+            let cache = app_handle.read_sol_chain_cache().await?;
+            let sender = app_handle.read_sol_tx_sender().await?;
             let pk_bytes = bs58::decode(&from_pk).into_vec()?;
             let from = Keypair::from_bytes(&pk_bytes)?;
             let per_amount = per_amount * LAMPORTS_PER_SOL as f64;
@@ -154,7 +239,7 @@ pub async fn airdrop(req: AirdropReq, app_handle: AppHandle) -> Result<String, A
                 pubkeys.push(Pubkey::from_str(&addr)?);
             }
             let sign = rpc_client
-                .batch_transfer_sol(from, &pubkeys, per_amount as u64)
+                .batch_transfer_sol(from, &pubkeys, per_amount as u64, &cache, &sender)
                 .await?;
             sign.to_string()
         }
@@ -188,13 +273,18 @@ pub async fn airdrop(req: AirdropReq, app_handle: AppHandle) -> Result<String, A
             let token_hub_addr = chain_config.moo_hub_addr;
 
             let token_hub_contract = MooTokenHubContract::new(token_hub_addr, rpc_provider.clone());
-            let receipt = token_hub_contract
+            let deposit_tx = token_hub_contract
                 .deposit(addresses, per_amount, per_w_amount)
                 .value(value)
-                .send()
+                .from(wallet_address)
+                .into_transaction_request();
+            let fees = fee_mode.estimate(&rpc_provider).await?;
+            let receipt = app_handle
+                .read_evm_tx_sender(EvmTxConfig::default())
                 .await?
-                .get_receipt()
-                .await?;
+                .send_and_wait(&rpc_provider, deposit_tx, fees)
+                .await?
+                .receipt;
 
             if !receipt.status() {
                 return Err(AppError::new("transaction failed ...."));
@@ -215,6 +305,8 @@ pub async fn transfer_native(
     let txid = match req.chain {
         Chain::Solana => {
             let rpc_client = app_handle.read_sol_rpc_client().await?;
+            let cache = app_handle.read_sol_chain_cache().await?;
+            let sender = app_handle.read_sol_tx_sender().await?;
             let pk_bytes = bs58::decode(&req.from_pk).into_vec()?;
             let from = Keypair::from_bytes(&pk_bytes)?;
             let balance = rpc_client.get_balance(&from.pubkey()).await?;
@@ -231,7 +323,7 @@ pub async fn transfer_native(
 
             let pubkey = Pubkey::from_str(&req.addr)?;
             let sign = rpc_client
-                .batch_transfer_sol(from, &[pubkey], amount)
+                .batch_transfer_sol(from, &[pubkey], amount, &cache, &sender)
                 .await?;
             sign.to_string()
         }
@@ -243,26 +335,38 @@ pub async fn transfer_native(
             let address = Address::from_str(&req.addr)?;
 
             let rpc_client = app_handle.read_evm_rpc_client().await?;
+            let wallet = EthereumWallet::from(wallet_signer);
             let rpc_provider = ProviderBuilder::new()
                 .with_recommended_fillers()
-                .wallet(EthereumWallet::from(wallet_signer))
+                .wallet(wallet.clone())
                 .on_client(rpc_client);
 
+            let chain_config = req.chain.evm_chain_config().unwrap();
             let balance = rpc_provider.get_balance(wallet_addr).await?;
-            let mut gas_price = rpc_provider.get_gas_price().await?;
-            // higher price
-            gas_price += 100_000;
+            let fees = req.fee_mode.estimate(&rpc_provider).await?;
             let gas_limit = 21000u128;
+            let build_tx = |value: U256| {
+                let mut tx = TransactionRequest::default()
+                    .from(wallet_addr)
+                    .to(address)
+                    .value(value);
+                tx.set_gas_limit(gas_limit);
+                fees.apply(&mut tx);
+                tx
+            };
 
-            let value = if req.amount.to_lowercase() == "max" {
-                let l1_gas = 1600u128;
-
-                // OPTIM: use l1 rpc to get gas price
-                // suppose 10gwei for now
-                let l1_gas_price = 10_000_000_000u128;
-                let l1_fee_need = l1_gas * l1_gas_price;
-
-                let total_fee_needed = U256::from((gas_price * gas_limit) + l1_fee_need);
+            let is_max = req.amount.to_lowercase() == "max";
+            let value = if is_max {
+                let l1_fee_need = if chain_config.is_op_stack {
+                    // NOTE: full balance as value, serialized size is never smaller than the real one
+                    get_op_l1_fee(&rpc_provider, &wallet, build_tx(balance)).await?
+                } else {
+                    U256::ZERO
+                };
+
+                // NOTE: the max fee is reserved so a rising base fee can't strand the transaction,
+                // the unused part of it is refunded and left as dust
+                let total_fee_needed = U256::from(fees.max_gas_price() * gas_limit) + l1_fee_need;
                 if total_fee_needed > balance {
                     return Err(AppError::new("insufficient balance"));
                 }
@@ -275,19 +379,18 @@ pub async fn transfer_native(
                 return Err(AppError::new("insufficient balance"));
             }
 
-            let mut tx = TransactionRequest::default()
-                .from(wallet_addr)
-                .to(address)
-                .value(value);
-
-            tx.set_gas_limit(gas_limit);
-            tx.set_gas_price(gas_price);
-
-            let receipt = rpc_provider
-                .send_transaction(tx)
+            let tx = build_tx(value);
+            let tx_config = if is_max {
+                EvmTxConfig::without_replacement()
+            } else {
+                EvmTxConfig::default()
+            };
+            let receipt = app_handle
+                .read_evm_tx_sender(tx_config)
                 .await?
-                .get_receipt()
-                .await?;
+                .send_and_wait(&rpc_provider, tx, fees)
+                .await?
+                .receipt;
 
             if !receipt.status() {
                 return Err(AppError::new("transaction failed ...."));
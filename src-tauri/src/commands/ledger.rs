@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use tauri::{command, AppHandle, Manager};
+
+use crate::error::AppError;
+use crate::ledger::{TradeLedger, TradeRecord, TradeRecordFilter, TradeRecordPage};
+use crate::state::TradeLedgerState;
+
+async fn read_trade_ledger(app_handle: &AppHandle) -> Result<Arc<TradeLedger>, AppError> {
+    let ledger_state = app_handle.state::<TradeLedgerState>();
+    let guard = ledger_state.read().await;
+    guard
+        .clone()
+        .ok_or_else(|| AppError::new("No Project Open"))
+}
+
+/// Trade attempts of the open project matching the filters, latest first
+#[command(async)]
+pub async fn query_trade_records(
+    filter: TradeRecordFilter,
+    app_handle: AppHandle,
+) -> Result<TradeRecordPage, AppError> {
+    let ledger = read_trade_ledger(&app_handle).await?;
+    tokio::task::spawn_blocking(move || ledger.query(&filter)).await?
+}
+
+#[command(async)]
+pub async fn get_trade_record(
+    id: i64,
+    app_handle: AppHandle,
+) -> Result<Option<TradeRecord>, AppError> {
+    let ledger = read_trade_ledger(&app_handle).await?;
+    tokio::task::spawn_blocking(move || ledger.get(id)).await?
+}
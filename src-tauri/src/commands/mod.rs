@@ -1,6 +1,7 @@
 use tauri::{generate_handler, ipc::Invoke};
 
 pub mod chain;
+pub mod ledger;
 pub mod project;
 pub mod tasks;
 pub mod wallet;
@@ -20,9 +21,19 @@ pub fn invoke_hanlders() -> impl Fn(Invoke) -> bool + Send + Sync + 'static {
         chain::transfer_native,
         chain::get_addr_balance,
         chain::get_token_info,
+        chain::get_sol_endpoint_stats,
+        chain::get_jito_regions,
+        chain::get_evm_pending_txs,
+        chain::speed_up_evm_tx,
+        chain::cancel_evm_tx,
         tasks::create_trade_task,
         tasks::start_trade_task,
         tasks::stop_trade_task,
         tasks::remove_trade_task,
+        tasks::get_task_schedules,
+        tasks::set_task_schedule,
+        tasks::remove_task_schedule,
+        ledger::query_trade_records,
+        ledger::get_trade_record,
     ]
 }
@@ -11,13 +11,20 @@ use solana_sdk::commitment_config::CommitmentConfig;
 use tauri::{command, AppHandle, Manager, State, Url};
 
 use crate::{
+    agg::AggApiConfig,
     chain::Chain,
     error::AppError,
+    ledger::TradeLedger,
     project::Project,
+    sol::{SolChainCache, SolTxConfirmer},
     state::{
-        EvmProviderState, EvmRpcClientState, ProjectState, ProjectStateValue,
-        ProxiedHttpClientState, ProxiedHttpClientValue, SolRpcClientState, TradeTaskState,
+        EvmNonceManagerState, EvmProviderState, EvmRpcClientState, JitoRegionsState,
+        JitoTipCacheState, ProjectState, ProjectStateValue, ProxiedHttpClientState,
+        ProxiedHttpClientValue, SolChainCacheState, SolEndpointStatsState, SolRpcClientState,
+        SolSendRpcClientsState, SolTxConfirmerState, TaskSchedulerState, TradeLedgerState,
+        TradeTaskState,
     },
+    tls::{LoadedTlsConfig, TlsConfig},
     utils,
 };
 
@@ -29,10 +36,20 @@ pub struct CreateProjectReq {
     pub name: String,
     pub chain: Chain,
     pub rpc: String,
-    pub jito_url: Option<String>,
+    pub rpc_ws_url: Option<String>,
+    #[serde(default)]
+    pub send_rpc_urls: Vec<String>,
+    /// jito block engine regions
+    #[serde(default)]
+    pub jito_urls: Vec<String>,
     pub agg_api_url: String,
     pub agg_api_key: Option<String>,
+    #[serde(default)]
+    pub agg_apis: Vec<AggApiConfig>,
     pub proxy_urls: Vec<String>,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    pub evm_confirmations: Option<u64>,
     pub save_path: String,
 }
 
@@ -44,10 +61,15 @@ pub struct ProjectResp {
     pub main_wallet: String,
     pub main_wallet_pk: String,
     pub rpc: String,
-    pub jito_url: Option<String>,
+    pub rpc_ws_url: Option<String>,
+    pub send_rpc_urls: Vec<String>,
+    pub jito_urls: Vec<String>,
     pub agg_api_url: String,
     pub agg_api_key: Option<String>,
+    pub agg_apis: Vec<AggApiConfig>,
     pub proxy_urls: Vec<String>,
+    pub tls: TlsConfig,
+    pub evm_confirmations: Option<u64>,
     pub wallet_grps: Vec<WalletGrpResp>,
 }
 
@@ -70,10 +92,15 @@ impl TryFrom<&Project> for ProjectResp {
             main_wallet,
             main_wallet_pk,
             rpc: value.rpc.clone(),
-            jito_url: value.jito_url.clone(),
+            rpc_ws_url: value.rpc_ws_url.clone(),
+            send_rpc_urls: value.send_rpc_urls.clone(),
+            jito_urls: value.jito_urls.clone(),
             agg_api_url: value.agg_api_url.clone(),
             agg_api_key: value.agg_api_key.clone(),
+            agg_apis: value.agg_apis.clone(),
             proxy_urls: value.proxy_urls.clone(),
+            tls: value.tls.clone(),
+            evm_confirmations: value.evm_confirmations,
             wallet_grps,
         })
     }
@@ -84,8 +111,58 @@ pub struct UpdateProjectReq {
     pub main_wallet_pk: String,
     pub proxy_urls: Vec<String>,
     pub rpc_url: String,
-    pub jito_url: Option<String>,
+    /// saved websocket url is kept when not set, an empty one removes it
+    pub rpc_ws_url: Option<String>,
+    /// saved send rpc urls are kept when not set
+    pub send_rpc_urls: Option<Vec<String>>,
+    /// jito block engine regions, saved ones are kept when not set
+    pub jito_urls: Option<Vec<String>>,
     pub agg_api_url: String,
+    /// saved aggregator apis are kept when not set
+    pub agg_apis: Option<Vec<AggApiConfig>>,
+    /// saved tls config is kept when not set
+    pub tls: Option<TlsConfig>,
+    /// saved confirmations are kept when not set, a `null` removes them
+    /// so the chain default is used
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub evm_confirmations: Option<Option<u64>>,
+}
+
+impl UpdateProjectReq {
+    /// Update the settings of a project, the ones not set in the request keep their saved values
+    fn apply_to(self, project: &mut Project) -> Result<(), AppError> {
+        let main_wallet = match project.chain {
+            Chain::Solana => bs58::decode(&self.main_wallet_pk).into_vec()?,
+            Chain::Base | Chain::Bsc => PrivateKeySigner::from_str(&self.main_wallet_pk)?
+                .to_bytes()
+                .to_vec(),
+        };
+        project.main_wallet = main_wallet;
+
+        project.rpc = self.rpc_url;
+        if let Some(rpc_ws_url) = self.rpc_ws_url {
+            project.rpc_ws_url = Some(rpc_ws_url).filter(|url| !url.is_empty());
+        }
+        if let Some(send_rpc_urls) = self.send_rpc_urls {
+            project.send_rpc_urls = send_rpc_urls;
+        }
+        if let Some(jito_urls) = self.jito_urls {
+            project.jito_urls = jito_urls;
+        }
+        project.agg_api_url = self.agg_api_url;
+        if let Some(agg_apis) = self.agg_apis {
+            project.agg_apis = agg_apis;
+        }
+        project.proxy_urls = self.proxy_urls;
+        if let Some(tls) = self.tls {
+            project.tls = tls;
+        }
+        if let Some(evm_confirmations) = self.evm_confirmations {
+            project.evm_confirmations = evm_confirmations;
+        }
+
+        Ok(())
+    }
 }
 
 #[command(async)]
@@ -96,8 +173,28 @@ pub async fn create_project(req: CreateProjectReq) -> Result<(), AppError> {
     }
 
     Url::parse(&req.rpc).map_err(|_| AppError::new("Rpc Url is not a valid url"))?;
+    for send_rpc_url in &req.send_rpc_urls {
+        Url::parse(send_rpc_url).map_err(|_| {
+            AppError::new(format!("Send Rpc Url {send_rpc_url} is not a valid url"))
+        })?;
+    }
+    for jito_url in &req.jito_urls {
+        Url::parse(jito_url)
+            .map_err(|_| AppError::new(format!("Jito Url {jito_url} is not a valid url")))?;
+    }
+    if let Some(rpc_ws_url) = &req.rpc_ws_url {
+        Url::parse(rpc_ws_url).map_err(|_| AppError::new("Rpc Ws Url is not a valid url"))?;
+    }
     Url::parse(&req.agg_api_url)
         .map_err(|_| AppError::new("Aggregation Api Url is not a valid url"))?;
+    for agg_api in &req.agg_apis {
+        Url::parse(&agg_api.api_url).map_err(|_| {
+            AppError::new(format!(
+                "{} Api Url {} is not a valid url",
+                agg_api.kind, agg_api.api_url
+            ))
+        })?;
+    }
 
     let proj = Project::from(req);
     proj.save(save_path).await?;
@@ -105,28 +202,65 @@ pub async fn create_project(req: CreateProjectReq) -> Result<(), AppError> {
     Ok(())
 }
 
+fn build_sol_rpc_client(
+    rpc_url: &str,
+    proxy_url: &str,
+    tls_config: &LoadedTlsConfig,
+) -> Result<RpcClient, AppError> {
+    let http_client_builder = reqwest_11::Client::builder()
+        .proxy(reqwest_11::Proxy::all(proxy_url)?)
+        .timeout(Duration::from_secs(10));
+    let http_client = tls_config.apply_11(http_client_builder)?.build()?;
+    let http_sender = HttpSender::new_with_client(rpc_url.to_string(), http_client);
+    let client = RpcClient::new_sender(
+        http_sender,
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+    );
+
+    Ok(client)
+}
+
 #[command(async)]
 pub async fn open_project(path: String, app_handle: AppHandle) -> Result<ProjectResp, AppError> {
     let proj = Project::read_from(path.clone()).await?;
     let resp = ProjectResp::try_from(&proj)?;
     let rpc_proxy_url = proj.proxy_urls[0].clone();
+    let tls_config = proj.tls.load().await?;
 
     match &proj.chain {
         Chain::Solana => {
             let client_state = app_handle.state::<SolRpcClientState>();
-            let http_client = reqwest_11::Client::builder()
-                .proxy(reqwest_11::Proxy::all(rpc_proxy_url)?)
-                .timeout(Duration::from_secs(10))
-                .danger_accept_invalid_certs(true) 
-                .build()?;
-            let http_sender = HttpSender::new_with_client(proj.rpc.clone(), http_client);
-            let client = RpcClient::new_sender(
-                http_sender,
-                RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
-            );
+            let client = build_sol_rpc_client(&proj.rpc, &rpc_proxy_url, &tls_config)?;
+            let client = Arc::new(client);
+
+            let mut send_rpc_clients = vec![];
+            for send_rpc_url in &proj.send_rpc_urls {
+                let send_client = build_sol_rpc_client(send_rpc_url, &rpc_proxy_url, &tls_config)?;
+                send_rpc_clients.push((send_rpc_url.clone(), Arc::new(send_client)));
+            }
+            let send_rpc_clients_state = app_handle.state::<SolSendRpcClientsState>();
+            let mut guard = send_rpc_clients_state.write().await;
+            *guard = send_rpc_clients;
+            drop(guard);
+            app_handle.state::<SolEndpointStatsState>().clear();
+            app_handle.state::<JitoTipCacheState>().clear();
+            app_handle.state::<JitoRegionsState>().clear();
+
+            let confirmer =
+                SolTxConfirmer::connect(client.clone(), proj.rpc_ws_url.as_deref()).await;
+            let confirmer_state = app_handle.state::<SolTxConfirmerState>();
+            let mut guard = confirmer_state.write().await;
+            *guard = Some(Arc::new(confirmer));
+            drop(guard);
+
+            let cache = SolChainCache::start(client.clone());
+            let cache_state = app_handle.state::<SolChainCacheState>();
+            let mut guard = cache_state.write().await;
+            *guard = Some(cache);
+            drop(guard);
 
             let mut guard = client_state.write().await;
-            *guard = Some(Arc::new(client));
+            *guard = Some(client);
             drop(guard)
         }
         Chain::Base | Chain::Bsc => {
@@ -134,11 +268,10 @@ pub async fn open_project(path: String, app_handle: AppHandle) -> Result<Project
             let provider_state = app_handle.state::<EvmProviderState>();
             let chain_config = proj.chain.evm_chain_config().unwrap();
 
-            let http_client = reqwest::ClientBuilder::new()
+            let http_client_builder = reqwest::ClientBuilder::new()
                 .proxy(reqwest::Proxy::all(rpc_proxy_url)?)
-                .timeout(Duration::from_secs(60))
-                .danger_accept_invalid_certs(true) 
-                .build()?;
+                .timeout(Duration::from_secs(60));
+            let http_client = tls_config.apply(http_client_builder)?.build()?;
             let rpc_url = proj.rpc.parse()?;
             let transport: Http<reqwest::Client> = Http::with_client(http_client, rpc_url);
             let client = ClientBuilder::default().transport(transport, false);
@@ -153,13 +286,14 @@ pub async fn open_project(path: String, app_handle: AppHandle) -> Result<Project
 
             let mut guard = provider_state.write().await;
             *guard = Some(provider);
-            drop(guard)
+            drop(guard);
         }
     };
 
     let mut proxied_http_clients: Vec<ProxiedHttpClientValue> = vec![];
     for proxy_url in &proj.proxy_urls {
         let proxy = reqwest::Proxy::all(proxy_url)?;
+        // NOTE: aggregator and jito clients, the tls config is only for rpc endpoints
         let client = reqwest::ClientBuilder::default()
             .proxy(proxy)
             .connect_timeout(Duration::from_secs(2))
@@ -175,6 +309,13 @@ pub async fn open_project(path: String, app_handle: AppHandle) -> Result<Project
     *guard = proxied_http_clients;
     drop(guard);
 
+    let ledger_path = path.clone();
+    let ledger = tokio::task::spawn_blocking(move || TradeLedger::open(ledger_path)).await??;
+    let ledger_state = app_handle.state::<TradeLedgerState>();
+    let mut guard = ledger_state.write().await;
+    *guard = Some(Arc::new(ledger));
+    drop(guard);
+
     let state_val = ProjectStateValue {
         path,
         project: proj,
@@ -205,6 +346,30 @@ pub async fn close_project(app_handle: AppHandle) -> Result<(), AppError> {
     *guard = None;
     drop(guard);
 
+    let sol_tx_confirmer_state = app_handle.state::<SolTxConfirmerState>();
+    let mut guard = sol_tx_confirmer_state.write().await;
+    *guard = None;
+    drop(guard);
+
+    let sol_chain_cache_state = app_handle.state::<SolChainCacheState>();
+    let mut guard = sol_chain_cache_state.write().await;
+    *guard = None;
+    drop(guard);
+
+    let sol_send_rpc_clients_state = app_handle.state::<SolSendRpcClientsState>();
+    let mut guard = sol_send_rpc_clients_state.write().await;
+    *guard = vec![];
+    drop(guard);
+    app_handle.state::<SolEndpointStatsState>().clear();
+    app_handle.state::<JitoTipCacheState>().clear();
+    app_handle.state::<JitoRegionsState>().clear();
+    app_handle.state::<TaskSchedulerState>().clear();
+
+    let ledger_state = app_handle.state::<TradeLedgerState>();
+    let mut guard = ledger_state.write().await;
+    *guard = None;
+    drop(guard);
+
     let evm_rpc_client_state = app_handle.state::<EvmRpcClientState>();
     let mut guard = evm_rpc_client_state.write().await;
     *guard = None;
@@ -214,6 +379,7 @@ pub async fn close_project(app_handle: AppHandle) -> Result<(), AppError> {
     let mut guard = evm_provider_state.write().await;
     *guard = None;
     drop(guard);
+    app_handle.state::<EvmNonceManagerState>().clear().await;
 
     let project_state = app_handle.state::<ProjectState>();
     let mut guard = project_state.lock().await;
@@ -231,32 +397,165 @@ pub async fn update_project(
     let state: State<'_, ProjectState> = app_handle.state();
 
     Url::parse(&req.rpc_url).map_err(|_| AppError::new("Rpc Url is not a valid url"))?;
+    for send_rpc_url in req.send_rpc_urls.iter().flatten() {
+        Url::parse(send_rpc_url).map_err(|_| {
+            AppError::new(format!("Send Rpc Url {send_rpc_url} is not a valid url"))
+        })?;
+    }
+    for jito_url in req.jito_urls.iter().flatten() {
+        Url::parse(jito_url)
+            .map_err(|_| AppError::new(format!("Jito Url {jito_url} is not a valid url")))?;
+    }
+    if let Some(rpc_ws_url) = req.rpc_ws_url.as_ref().filter(|url| !url.is_empty()) {
+        Url::parse(rpc_ws_url).map_err(|_| AppError::new("Rpc Ws Url is not a valid url"))?;
+    }
     Url::parse(&req.agg_api_url)
         .map_err(|_| AppError::new("Aggregation Api Url is not a valid url"))?;
+    for agg_api in req.agg_apis.iter().flatten() {
+        Url::parse(&agg_api.api_url).map_err(|_| {
+            AppError::new(format!(
+                "{} Api Url {} is not a valid url",
+                agg_api.kind, agg_api.api_url
+            ))
+        })?;
+    }
 
     let mut guard = state.lock().await;
     if let Some(proj) = guard.as_mut() {
-        let chain = proj.project.chain;
-        let main_wallet = match chain {
-            Chain::Solana => bs58::decode(&req.main_wallet_pk).into_vec()?,
-            Chain::Base | Chain::Bsc => PrivateKeySigner::from_str(&req.main_wallet_pk)?
-                .to_bytes()
-                .to_vec(),
-        };
-        proj.project.main_wallet = main_wallet;
-
-        proj.project.rpc = req.rpc_url;
-        proj.project.jito_url = req.jito_url;
-        proj.project.agg_api_url = req.agg_api_url;
-        proj.project.proxy_urls = req.proxy_urls;
+        let rpc_changed = proj.project.rpc != req.rpc_url;
+        req.apply_to(&mut proj.project)?;
 
         proj.project.save(&proj.path).await?;
 
         let project_path = proj.path.clone();
         drop(guard);
 
+        // NOTE: running tasks keep waiting for their pending transactions on the same rpc
+        if rpc_changed {
+            app_handle.state::<EvmNonceManagerState>().clear().await;
+        }
+
         return open_project(project_path, app_handle.clone()).await;
     }
 
     Err(AppError::new("project not found"))
 }
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signer::keypair::Keypair;
+
+    use crate::agg::AggregatorKind;
+
+    use super::*;
+
+    fn update_req(rpc_url: &str) -> UpdateProjectReq {
+        UpdateProjectReq {
+            main_wallet_pk: Keypair::new().to_base58_string(),
+            proxy_urls: vec!["http://127.0.0.1:8080".to_string()],
+            rpc_url: rpc_url.to_string(),
+            rpc_ws_url: None,
+            send_rpc_urls: None,
+            jito_urls: None,
+            agg_api_url: "https://quote-api.jup.ag/v6".to_string(),
+            agg_apis: None,
+            tls: None,
+            evm_confirmations: None,
+        }
+    }
+
+    #[test]
+    fn update_keeps_settings_not_in_request() {
+        let tls = TlsConfig {
+            ca_cert_paths: vec!["/etc/ssl/private-ca.pem".to_string()],
+            exclusive_ca_cert_paths: vec![],
+            accept_invalid_certs: false,
+        };
+        let mut project = Project {
+            chain: Chain::Solana,
+            rpc: "https://old-rpc.example.com".to_string(),
+            rpc_ws_url: Some("wss://rpc.example.com".to_string()),
+            send_rpc_urls: vec!["https://send-rpc.example.com".to_string()],
+            jito_urls: vec!["https://mainnet.block-engine.jito.wtf".to_string()],
+            agg_apis: vec![AggApiConfig {
+                kind: AggregatorKind::Jupiter,
+                api_url: "https://jupiter.example.com".to_string(),
+                api_key: Some("key".to_string()),
+            }],
+            tls: tls.clone(),
+            evm_confirmations: Some(3),
+            ..Default::default()
+        };
+
+        update_req("https://new-rpc.example.com")
+            .apply_to(&mut project)
+            .unwrap();
+
+        assert_eq!(project.rpc, "https://new-rpc.example.com");
+        assert_eq!(project.rpc_ws_url.as_deref(), Some("wss://rpc.example.com"));
+        assert_eq!(project.send_rpc_urls, vec!["https://send-rpc.example.com"]);
+        assert_eq!(
+            project.jito_urls,
+            vec!["https://mainnet.block-engine.jito.wtf"]
+        );
+        assert_eq!(project.tls.ca_cert_paths, tls.ca_cert_paths);
+        assert_eq!(project.evm_confirmations, Some(3));
+        assert_eq!(project.agg_apis.len(), 1);
+        assert_eq!(project.agg_apis[0].api_url, "https://jupiter.example.com");
+    }
+
+    #[test]
+    fn update_replaces_settings_in_request() {
+        let mut project = Project {
+            chain: Chain::Solana,
+            rpc_ws_url: Some("wss://rpc.example.com".to_string()),
+            send_rpc_urls: vec!["https://send-rpc.example.com".to_string()],
+            jito_urls: vec!["https://mainnet.block-engine.jito.wtf".to_string()],
+            ..Default::default()
+        };
+        let mut req = update_req("https://rpc.example.com");
+        req.rpc_ws_url = Some(String::new());
+        req.send_rpc_urls = Some(vec![]);
+        req.jito_urls = Some(vec!["https://ny.mainnet.block-engine.jito.wtf".to_string()]);
+        req.evm_confirmations = Some(Some(5));
+        req.tls = Some(TlsConfig {
+            accept_invalid_certs: true,
+            ..Default::default()
+        });
+
+        req.apply_to(&mut project).unwrap();
+
+        assert!(project.rpc_ws_url.is_none());
+        assert!(project.send_rpc_urls.is_empty());
+        assert_eq!(
+            project.jito_urls,
+            vec!["https://ny.mainnet.block-engine.jito.wtf"]
+        );
+        assert!(project.tls.accept_invalid_certs);
+        assert_eq!(project.evm_confirmations, Some(5));
+    }
+
+    #[test]
+    fn update_removes_confirmations_sent_as_null() {
+        let mut req_json = serde_json::json!({
+            "main_wallet_pk": Keypair::new().to_base58_string(),
+            "proxy_urls": ["http://127.0.0.1:8080"],
+            "rpc_url": "https://rpc.example.com",
+            "agg_api_url": "https://quote-api.jup.ag/v6",
+        });
+        let mut project = Project {
+            chain: Chain::Solana,
+            evm_confirmations: Some(3),
+            ..Default::default()
+        };
+
+        let req: UpdateProjectReq = serde_json::from_value(req_json.clone()).unwrap();
+        req.apply_to(&mut project).unwrap();
+        assert_eq!(project.evm_confirmations, Some(3));
+
+        req_json["evm_confirmations"] = serde_json::Value::Null;
+        let req: UpdateProjectReq = serde_json::from_value(req_json).unwrap();
+        req.apply_to(&mut project).unwrap();
+        assert!(project.evm_confirmations.is_none());
+    }
+}
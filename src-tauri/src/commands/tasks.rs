@@ -1,13 +1,19 @@
 use log::debug;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tauri::{command, AppHandle, Manager};
 
+use crate::agg::{AggregatorKind, JupiterRouteConfig, OneInchRouteConfig};
 use crate::error::AppError;
-use crate::state::{ProjectState, TradeTaskState};
-use crate::task::{Task, TaskState, TradeMode};
+use crate::evm::{EvmFeeMode, EvmTxConfig};
+use crate::jito::JitoTipStrategy;
+use crate::sol::SolPriorityFeeStrategy;
+use crate::state::{ProjectState, TaskSchedulerState, TradeTaskState};
+use crate::task::{Task, TaskSchedule, TaskState, TaskStopConditions, TradeMode};
 use crate::token::TokenInfo;
 
-#[derive(Debug, Deserialize)]
+/// NOTE: saved in the project file with the task schedule, changing its fields
+/// needs a new project version
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateTaskReq {
     pub workers_cnt: u32,
     pub wallet_grp_id: String,
@@ -16,8 +22,43 @@ pub struct CreateTaskReq {
     pub percetage: (u32, u32),
     pub slippage: u16,
     pub use_jito: bool,
+    /// compute unit price in micro lamports, or the jito tip in lamports with jito
     pub gas_price: u32,
+    /// only used with jito
+    #[serde(default)]
+    pub jito_tip_strategy: JitoTipStrategy,
+    /// lamports, caps the jito tip of every strategy
+    pub jito_max_tip: Option<u64>,
+    /// compute unit price in micro lamports, send through rpc with it when every
+    /// jito region failed, no fallback when not set
+    pub jito_fallback_unit_price: Option<u32>,
+    /// swaps of up to this many wallets of the task are sent in one bundle and
+    /// only the last one tips, a bundle per swap when not set
+    pub jito_bundle_size: Option<u8>,
+    #[serde(default)]
+    pub priority_fee_strategy: SolPriorityFeeStrategy,
+    /// extra compute units on top of the simulated units in percent, 10 if not set
+    pub compute_unit_margin_pct: Option<u32>,
+    /// only used by evm chains
+    #[serde(default)]
+    pub evm_fee_mode: EvmFeeMode,
+    /// only used by evm chains
+    #[serde(default)]
+    pub evm_tx_config: EvmTxConfig,
+    /// primary aggregator first, then the fallbacks tried in order when it errors,
+    /// the chain default aggregator when empty
+    #[serde(default)]
+    pub aggregators: Vec<AggregatorKind>,
+    /// only used by jupiter
+    #[serde(default)]
+    pub jupiter_route_config: JupiterRouteConfig,
+    /// only used by 1inch
+    #[serde(default)]
+    pub one_inch_route_config: OneInchRouteConfig,
     pub interval_secs: u64,
+    /// the task runs until it is stopped when none is set
+    #[serde(default)]
+    pub stop_conditions: TaskStopConditions,
 }
 
 #[command(async)]
@@ -58,6 +99,7 @@ pub async fn start_trade_task(id: String, app_handle: AppHandle) -> Result<(), A
         if task.task_state == TaskState::Created || task.task_state == TaskState::Stopped {
             debug!("starting task: {id}");
             task.task_state = TaskState::Running;
+            task.stop_reason = None;
             let workers = task.create_workers(app_handle.clone());
             for worker in workers {
                 task.running_workers += 1;
@@ -89,6 +131,66 @@ pub async fn stop_trade_task(id: String, app_handle: AppHandle) -> Result<(), Ap
     Ok(())
 }
 
+#[command(async)]
+pub async fn get_task_schedules(app_handle: AppHandle) -> Result<Vec<TaskSchedule>, AppError> {
+    let project_state = app_handle.state::<ProjectState>();
+    let guard = project_state.lock().await;
+    let proj = guard
+        .as_ref()
+        .ok_or_else(|| AppError::new("No Project Open"))?;
+
+    Ok(proj.project.task_schedules.clone())
+}
+
+/// Replace the schedule of the task with the same id
+#[command(async)]
+pub async fn set_task_schedule(
+    schedule: TaskSchedule,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    schedule.kind.validate()?;
+    if schedule.task.wallet_grp_id != schedule.task_id {
+        return Err(AppError::new(
+            "task of the schedule is of another wallet group",
+        ));
+    }
+
+    let project_state = app_handle.state::<ProjectState>();
+    let mut guard = project_state.lock().await;
+    let proj = guard
+        .as_mut()
+        .ok_or_else(|| AppError::new("No Project Open"))?;
+    // NOTE: the task is created when the schedule starts it, check the request now
+    Task::create_from_req(&schedule.task, &proj.project)?;
+
+    debug!("set schedule of task: {}", schedule.task_id);
+    let task_id = schedule.task_id.clone();
+    let schedules = &mut proj.project.task_schedules;
+    schedules.retain(|it| it.task_id != task_id);
+    schedules.push(schedule);
+    proj.project.save(&proj.path).await?;
+    drop(guard);
+
+    app_handle.state::<TaskSchedulerState>().reset(&task_id);
+    Ok(())
+}
+
+#[command(async)]
+pub async fn remove_task_schedule(id: String, app_handle: AppHandle) -> Result<(), AppError> {
+    debug!("remove schedule of task: {id}");
+    let project_state = app_handle.state::<ProjectState>();
+    let mut guard = project_state.lock().await;
+    let proj = guard
+        .as_mut()
+        .ok_or_else(|| AppError::new("No Project Open"))?;
+    proj.project.task_schedules.retain(|it| it.task_id != id);
+    proj.project.save(&proj.path).await?;
+    drop(guard);
+
+    app_handle.state::<TaskSchedulerState>().reset(&id);
+    Ok(())
+}
+
 #[command(async)]
 pub async fn remove_trade_task(id: String, app_handle: AppHandle) -> Result<(), AppError> {
     debug!("remove task: {id}");
@@ -5,6 +5,7 @@ use crate::{
     consts::SOL_TX_BASE_FEE,
     contracts::WEthContract,
     error::AppError,
+    evm::{get_op_l1_fee, EvmFeeMode, EvmTxConfig},
     state::{AppHandleStateExt, ProjectState},
     utils,
     wallet::WalletGrp,
@@ -75,6 +76,9 @@ pub struct WalletGrpWithdrawReq {
     pub chain: Chain,
     pub from_pk: String,
     pub addr: String,
+    /// only used by evm chains
+    #[serde(default)]
+    pub fee_mode: EvmFeeMode,
 }
 
 #[command(async)]
@@ -169,6 +173,8 @@ pub async fn wallet_grp_withdraw(
     let txid = match req.chain {
         Chain::Solana => {
             let rpc_client = app_handle.read_sol_rpc_client().await?;
+            let cache = app_handle.read_sol_chain_cache().await?;
+            let sender = app_handle.read_sol_tx_sender().await?;
             let pk_bytes = bs58::decode(&req.from_pk).into_vec()?;
             let from = Keypair::from_bytes(&pk_bytes)?;
             let balance = rpc_client.get_balance(&from.pubkey()).await?;
@@ -180,7 +186,7 @@ pub async fn wallet_grp_withdraw(
 
             let pubkey = Pubkey::from_str(&req.addr)?;
             let sign = rpc_client
-                .batch_transfer_sol(from, &[pubkey], amount)
+                .batch_transfer_sol(from, &[pubkey], amount, &cache, &sender)
                 .await?;
             sign.to_string()
         }
@@ -192,22 +198,29 @@ pub async fn wallet_grp_withdraw(
             let address = Address::from_str(&req.addr)?;
 
             let rpc_client = app_handle.read_evm_rpc_client().await?;
+            let wallet = EthereumWallet::from(wallet_signer);
             let rpc_provider = ProviderBuilder::new()
                 .with_recommended_fillers()
-                .wallet(EthereumWallet::from(wallet_signer))
+                .wallet(wallet.clone())
                 .on_client(rpc_client);
 
             let chain_config = req.chain.evm_chain_config().unwrap();
+            let evm_tx_sender = app_handle
+                .read_evm_tx_sender(EvmTxConfig::default())
+                .await?;
             let wrapped_native_addr = chain_config.wrapped_native_addr;
             let weth_contract = WEthContract::new(wrapped_native_addr, rpc_provider.clone());
             let weth_balance = weth_contract.balanceOf(wallet_addr).call().await?._0;
             if weth_balance > U256::ZERO {
-                let withdraw_receipt = weth_contract
+                let withdraw_tx = weth_contract
                     .withdraw(weth_balance)
-                    .send()
+                    .from(wallet_addr)
+                    .into_transaction_request();
+                let fees = req.fee_mode.estimate(&rpc_provider).await?;
+                let withdraw_receipt = evm_tx_sender
+                    .send_and_wait(&rpc_provider, withdraw_tx, fees)
                     .await?
-                    .get_receipt()
-                    .await?;
+                    .receipt;
 
                 if !withdraw_receipt.status() {
                     return Err(AppError::new("convert weth to eth error"));
@@ -215,41 +228,40 @@ pub async fn wallet_grp_withdraw(
             }
 
             let balance = rpc_provider.get_balance(wallet_addr).await?;
-            let mut gas_price = rpc_provider.get_gas_price().await?;
-            // higher price
-            gas_price += 100_000;
+            let fees = req.fee_mode.estimate(&rpc_provider).await?;
             let gas_limit = 21000u128;
-            let l1_fee_need = match req.chain {
-                Chain::Base => {
-                    let l1_gas = 1600u128;
-                    // OPTIM: use l1 rpc to get gas price
-                    // suppose 1gwei for now
-                    let l1_gas_price = 1_000_000_000u128;
-                    l1_gas * l1_gas_price
-                }
-                _ => 0,
+            let build_tx = |value: U256| {
+                let mut tx = TransactionRequest::default()
+                    .from(wallet_addr)
+                    .to(address)
+                    .value(value);
+                tx.set_gas_limit(gas_limit);
+                fees.apply(&mut tx);
+                tx
             };
 
-            let total_fee_needed = U256::from((gas_price * gas_limit) + l1_fee_need);
+            let l1_fee_need = if chain_config.is_op_stack {
+                // NOTE: full balance as value, serialized size is never smaller than the real one
+                get_op_l1_fee(&rpc_provider, &wallet, build_tx(balance)).await?
+            } else {
+                U256::ZERO
+            };
+
+            // NOTE: the max fee is reserved so a rising base fee can't strand the transaction,
+            // the unused part of it is refunded and left as dust
+            let total_fee_needed = U256::from(fees.max_gas_price() * gas_limit) + l1_fee_need;
             if total_fee_needed > balance {
                 return Err(AppError::new("insufficient balance"));
             }
 
             let value = balance - total_fee_needed;
-
-            let mut tx = TransactionRequest::default()
-                .from(wallet_addr)
-                .to(address)
-                .value(value);
-
-            tx.set_gas_limit(gas_limit);
-            tx.set_gas_price(gas_price);
-
-            let receipt = rpc_provider
-                .send_transaction(tx)
+            let tx = build_tx(value);
+            let receipt = app_handle
+                .read_evm_tx_sender(EvmTxConfig::without_replacement())
                 .await?
-                .get_receipt()
-                .await?;
+                .send_and_wait(&rpc_provider, tx, fees)
+                .await?
+                .receipt;
 
             if !receipt.status() {
                 return Err(AppError::new("transaction failed ...."));
@@ -4,15 +4,45 @@ use solana_sdk::{pubkey, pubkey::Pubkey};
 #[allow(unused)]
 pub const SOL_TX_BASE_FEE: u64 = 5_000;
 pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey =
+    pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
 
 pub const BASE_MOO_TOKEN_HUB_ADDR: Address = address!("2592342f0c6c54ac61876d30358d4c944bb4ce54");
 pub const BASE_WETH_ADDR: Address = address!("4200000000000000000000000000000000000006");
 pub const BASE_ONE_INCH_V6_ROUTER_ADDR: Address =
     address!("111111125421cA6dc452d289314280a0f8842A65");
 
+pub const BASE_UNISWAP_V2_FACTORY_ADDR: Address =
+    address!("8909Dc15e40173Ff4699343b6eB8132c65e18eC6");
+pub const BASE_UNISWAP_V2_ROUTER_ADDR: Address =
+    address!("4752ba5DBc23f44D87826276BF6Fd6b1C372aD24");
+pub const BASE_UNISWAP_V3_FACTORY_ADDR: Address =
+    address!("33128a8fC17869897dcE68Ed026d694621f6FDfD");
+pub const BASE_UNISWAP_V3_QUOTER_ADDR: Address =
+    address!("3d4e44Eb1374240CE5F1B871ab261CD16335B76a");
+pub const BASE_UNISWAP_V3_ROUTER_ADDR: Address =
+    address!("2626664c2603336E57B271c5C0b26F421741e481");
+pub const UNISWAP_V3_FEE_TIERS: &[u32] = &[100, 500, 3000, 10000];
+
 pub const BSC_MOO_TOKEN_HUB_ADDR: Address = address!("26cf96267bd73E98aF0e360c3B6157573f40001D");
 pub const BSC_WBNB_ADDR: Address = address!("bb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c");
 pub const BSC_ONE_INCH_V6_ROUTER_ADDR: Address =
     address!("111111125421cA6dc452d289314280a0f8842A65");
 
+pub const BSC_PANCAKE_V2_FACTORY_ADDR: Address =
+    address!("cA143Ce32Fe78f1f7019d7d551a6402fC5350c73");
+pub const BSC_PANCAKE_V2_ROUTER_ADDR: Address =
+    address!("10ED43C718714eb63d5aA57B78B54704E256024E");
+pub const BSC_PANCAKE_V3_FACTORY_ADDR: Address =
+    address!("0BFbCF9fa4f9C56B0F40a671Ad40E0805A091865");
+pub const BSC_PANCAKE_V3_QUOTER_ADDR: Address =
+    address!("B048Bbc1Ee6b733FFfCFb9e9CeF7375518e25997");
+pub const BSC_PANCAKE_V3_ROUTER_ADDR: Address =
+    address!("13f4EA83D0bd40E75C8222255bc855a974568Dd4");
+pub const PANCAKE_V3_FEE_TIERS: &[u32] = &[100, 500, 2500, 10000];
+
+/// `GasPriceOracle` predeploy of OP-stack chains
+pub const OP_GAS_PRICE_ORACLE_ADDR: Address = address!("420000000000000000000000000000000000000F");
+
 pub const ONE_INCH_NATIVE_COIN_ADDR: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
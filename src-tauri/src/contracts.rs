@@ -17,3 +17,39 @@ sol!(
     MooTokenHubContract,
     "abis/moo_token_hub.json"
 );
+
+sol!(
+    #[sol(rpc)]
+    GasPriceOracleContract,
+    "abis/gas_price_oracle.json"
+);
+
+sol!(
+    #[sol(rpc)]
+    UniswapV2FactoryContract,
+    "abis/uniswap_v2_factory.json"
+);
+
+sol!(
+    #[sol(rpc)]
+    UniswapV2RouterContract,
+    "abis/uniswap_v2_router.json"
+);
+
+sol!(
+    #[sol(rpc)]
+    UniswapV3FactoryContract,
+    "abis/uniswap_v3_factory.json"
+);
+
+sol!(
+    #[sol(rpc)]
+    UniswapV3QuoterContract,
+    "abis/uniswap_v3_quoter.json"
+);
+
+sol!(
+    #[sol(rpc)]
+    UniswapV3RouterContract,
+    "abis/uniswap_v3_router.json"
+);
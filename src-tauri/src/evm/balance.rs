@@ -0,0 +1,260 @@
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, I256, U256},
+    providers::Provider,
+    rpc::types::TransactionReceipt,
+    transports::Transport,
+};
+
+use crate::{contracts::Erc20Contract, error::AppError};
+
+/// Balance changes of a wallet made by a swap
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvmBalanceChanges {
+    /// native coin the wallet got from the swap without the fee, plus `Transfer`s of the
+    /// wrapped native coin, negative when it was sent
+    pub native: I256,
+    /// `Transfer`s of the token to and from the wallet
+    pub token: I256,
+    /// gas and the L1 data fee of OP-stack chains
+    pub fee: U256,
+}
+
+impl EvmBalanceChanges {
+    /// Changes of a landed swap, the native side is the balance change of the wallet over
+    /// the block of the receipt with the fee added back
+    ///
+    /// NOTE: routers unwrap the output to themselves and send it on, so the balance is the
+    /// only place the native coin the wallet got shows up. Other transactions of the wallet
+    /// in the same block are counted as well, a worker has one swap in flight.
+    pub async fn fetch<P, T>(
+        provider: &P,
+        receipt: &TransactionReceipt,
+        wallet: Address,
+        token: Address,
+        wrapped_native: Address,
+        is_op_stack: bool,
+    ) -> Result<Self, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let block = receipt
+            .block_number
+            .ok_or(AppError::new("swap receipt has no block number"))?;
+        let balance_after = provider
+            .get_balance(wallet)
+            .block_id(BlockId::number(block))
+            .await?;
+        let balance_before = provider
+            .get_balance(wallet)
+            .block_id(BlockId::number(block.saturating_sub(1)))
+            .await?;
+        let l1_fee = if is_op_stack {
+            op_l1_fee(provider, receipt.transaction_hash).await?
+        } else {
+            U256::ZERO
+        };
+        let balance_change = I256::from_raw(balance_after) - I256::from_raw(balance_before);
+
+        Ok(Self::from_receipt(
+            receipt,
+            wallet,
+            token,
+            wrapped_native,
+            balance_change,
+            l1_fee,
+        ))
+    }
+
+    /// `balance_change` is the native balance change of the wallet over the block of the
+    /// receipt, `l1_fee` the L1 data fee the transaction was charged
+    pub fn from_receipt(
+        receipt: &TransactionReceipt,
+        wallet: Address,
+        token: Address,
+        wrapped_native: Address,
+        balance_change: I256,
+        l1_fee: U256,
+    ) -> Self {
+        let fee = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price) + l1_fee;
+        let mut native = balance_change + I256::from_raw(fee);
+        let mut token_change = I256::ZERO;
+        for log in receipt.inner.logs() {
+            let Ok(transfer) = log.log_decode::<Erc20Contract::Transfer>() else {
+                continue;
+            };
+            let address = log.address();
+            let transfer = transfer.inner.data;
+            let amount = I256::from_raw(transfer.value);
+            let change = match (transfer.from == wallet, transfer.to == wallet) {
+                (true, false) => -amount,
+                (false, true) => amount,
+                _ => continue,
+            };
+            if address == token {
+                token_change += change;
+            } else if address == wrapped_native {
+                native += change;
+            }
+        }
+
+        Self {
+            native,
+            token: token_change,
+            fee,
+        }
+    }
+}
+
+/// The L1 data fee an OP-stack chain charged, which only its receipts report
+async fn op_l1_fee<P, T>(provider: &P, tx_hash: B256) -> Result<U256, AppError>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let receipt: serde_json::Value = provider
+        .raw_request("eth_getTransactionReceipt".into(), (tx_hash,))
+        .await?;
+    match receipt.get("l1Fee").and_then(|fee| fee.as_str()) {
+        Some(l1_fee) => Ok(U256::from_str_radix(l1_fee.trim_start_matches("0x"), 16)?),
+        None => Ok(U256::ZERO),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{address, b256, Bloom};
+    use serde_json::json;
+
+    use super::*;
+
+    const WALLET: Address = address!("00000000000000000000000000000000000000aa");
+    const POOL: Address = address!("00000000000000000000000000000000000000bb");
+    const ROUTER: Address = address!("2626664c2603336E57B271c5C0b26F421741e481");
+    const TOKEN: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+    const WETH: Address = address!("4200000000000000000000000000000000000006");
+    const TRANSFER_TOPIC: B256 =
+        b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+    const WITHDRAWAL_TOPIC: B256 =
+        b256!("7fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65");
+    /// 200_000 gas at 1 gwei, as in the receipt
+    const GAS_FEE: i64 = 200_000_000_000_000;
+
+    fn int(value: i64) -> I256 {
+        I256::try_from(value).unwrap()
+    }
+
+    fn log(address: Address, topics: &[B256], amount: i64) -> serde_json::Value {
+        json!({
+            "address": address,
+            "topics": topics,
+            "data": B256::from(U256::from(amount)),
+            "blockHash": B256::ZERO,
+            "blockNumber": "0x10",
+            "transactionHash": B256::ZERO,
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false,
+        })
+    }
+
+    fn transfer(address: Address, from: Address, to: Address, amount: i64) -> serde_json::Value {
+        log(
+            address,
+            &[TRANSFER_TOPIC, from.into_word(), to.into_word()],
+            amount,
+        )
+    }
+
+    /// a receipt as `eth_getTransactionReceipt` returns it
+    fn receipt(logs: Vec<serde_json::Value>) -> TransactionReceipt {
+        serde_json::from_value(json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x30d40",
+            "logs": logs,
+            "logsBloom": Bloom::ZERO,
+            "transactionHash": B256::ZERO,
+            "transactionIndex": "0x0",
+            "blockHash": B256::ZERO,
+            "blockNumber": "0x10",
+            "gasUsed": "0x30d40",
+            "effectiveGasPrice": "0x3b9aca00",
+            "from": WALLET,
+            "to": ROUTER,
+            "contractAddress": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn sell_counts_the_balance_change_not_the_router_withdrawal() {
+        let receipt = receipt(vec![
+            transfer(TOKEN, WALLET, POOL, 1_000_000),
+            transfer(WETH, POOL, ROUTER, 5_000_000_000_000_000),
+            log(
+                WETH,
+                &[WITHDRAWAL_TOPIC, ROUTER.into_word()],
+                5_000_000_000_000_000,
+            ),
+        ]);
+        let l1_fee = 1_000_000_000_000;
+        let balance_change = int(5_000_000_000_000_000 - GAS_FEE - l1_fee);
+
+        let changes = EvmBalanceChanges::from_receipt(
+            &receipt,
+            WALLET,
+            TOKEN,
+            WETH,
+            balance_change,
+            U256::from(l1_fee),
+        );
+
+        assert_eq!(changes.native, int(5_000_000_000_000_000));
+        assert_eq!(changes.token, int(-1_000_000));
+        assert_eq!(changes.fee, U256::from(GAS_FEE + l1_fee));
+    }
+
+    #[test]
+    fn buy_counts_the_value_sent_and_the_token_received() {
+        let receipt = receipt(vec![
+            transfer(WETH, ROUTER, POOL, 10_000_000_000_000_000),
+            transfer(TOKEN, POOL, WALLET, 25_000_000),
+        ]);
+        let balance_change = int(-10_000_000_000_000_000 - GAS_FEE);
+
+        let changes = EvmBalanceChanges::from_receipt(
+            &receipt,
+            WALLET,
+            TOKEN,
+            WETH,
+            balance_change,
+            U256::ZERO,
+        );
+
+        assert_eq!(changes.native, int(-10_000_000_000_000_000));
+        assert_eq!(changes.token, int(25_000_000));
+        assert_eq!(changes.fee, U256::from(GAS_FEE));
+    }
+
+    #[test]
+    fn wrapped_native_sent_to_the_wallet_counts_as_native() {
+        let receipt = receipt(vec![
+            transfer(TOKEN, WALLET, POOL, 1_000_000),
+            transfer(WETH, POOL, WALLET, 5_000_000_000_000_000),
+        ]);
+
+        let changes = EvmBalanceChanges::from_receipt(
+            &receipt,
+            WALLET,
+            TOKEN,
+            WETH,
+            int(-GAS_FEE),
+            U256::ZERO,
+        );
+
+        assert_eq!(changes.native, int(5_000_000_000_000_000));
+        assert_eq!(changes.token, int(-1_000_000));
+    }
+}
@@ -0,0 +1,165 @@
+use alloy::{
+    eips::BlockNumberOrTag, network::TransactionBuilder, providers::Provider,
+    rpc::types::TransactionRequest, transports::Transport,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// recent blocks `eth_feeHistory` looks at
+const FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+const DEFAULT_REWARD_PERCENTILE: f64 = 50.0;
+/// wei added on top of `eth_gasPrice` in legacy mode
+const LEGACY_GAS_PRICE_BUMP: u128 = 100_000;
+const MIN_REPLACEMENT_BUMP_PCT: u32 = 10;
+
+/// How the fees of an evm transaction are chosen
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum EvmFeeMode {
+    /// type-0 transaction paying `eth_gasPrice`, for chains without EIP-1559
+    Legacy,
+    /// type-2 transaction, the priority fee is the `reward_percentile` of the
+    /// priority fees paid in recent blocks, max fee allows the base fee to double
+    Eip1559 { reward_percentile: f64 },
+}
+
+impl Default for EvmFeeMode {
+    fn default() -> Self {
+        Self::Eip1559 {
+            reward_percentile: DEFAULT_REWARD_PERCENTILE,
+        }
+    }
+}
+
+/// Fees estimated by [`EvmFeeMode`], in wei per gas
+#[derive(Debug, Clone, Copy, Serialize)]
+pub enum EvmFees {
+    Legacy {
+        gas_price: u128,
+    },
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+}
+
+impl EvmFeeMode {
+    pub async fn estimate<P, T>(&self, provider: &P) -> Result<EvmFees, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let fees = match *self {
+            Self::Legacy => {
+                let gas_price = provider.get_gas_price().await?;
+                EvmFees::Legacy {
+                    gas_price: gas_price + LEGACY_GAS_PRICE_BUMP,
+                }
+            }
+            Self::Eip1559 { reward_percentile } => {
+                let fee_history = provider
+                    .get_fee_history(
+                        FEE_HISTORY_BLOCK_COUNT,
+                        BlockNumberOrTag::Latest,
+                        &[reward_percentile.clamp(0.0, 100.0)],
+                    )
+                    .await?;
+
+                let base_fee = fee_history
+                    .next_block_base_fee()
+                    .ok_or(AppError::new("no base fee in fee history"))?;
+
+                let rewards: Vec<u128> = fee_history
+                    .reward
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|block_rewards| block_rewards.first().copied())
+                    .filter(|reward| *reward > 0)
+                    .collect();
+                let mut priority_fee = median(rewards);
+                if priority_fee == 0 {
+                    // NOTE: empty blocks report no rewards, ask the node instead
+                    priority_fee = provider.get_max_priority_fee_per_gas().await?;
+                }
+
+                EvmFees::Eip1559 {
+                    max_fee_per_gas: base_fee * 2 + priority_fee,
+                    max_priority_fee_per_gas: priority_fee,
+                }
+            }
+        };
+
+        Ok(fees)
+    }
+}
+
+impl EvmFees {
+    /// The most wei per gas the transaction may pay, used for balance checks
+    pub fn max_gas_price(&self) -> u128 {
+        match *self {
+            Self::Legacy { gas_price } => gas_price,
+            Self::Eip1559 {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas,
+        }
+    }
+
+    /// Fees of a replacement transaction, nodes only accept a replacement
+    /// paying at least 10% more than the transaction it replaces
+    pub fn bumped(&self, bump_pct: u32, current: &EvmFees) -> EvmFees {
+        let bump_pct = bump_pct.max(MIN_REPLACEMENT_BUMP_PCT) as u128;
+        let bump = |fee: u128| fee * (100 + bump_pct) / 100;
+        match (*self, *current) {
+            (Self::Legacy { gas_price }, Self::Legacy { gas_price: current }) => Self::Legacy {
+                gas_price: bump(gas_price).max(current),
+            },
+            (
+                Self::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                },
+                Self::Eip1559 {
+                    max_fee_per_gas: current_max_fee,
+                    max_priority_fee_per_gas: current_priority_fee,
+                },
+            ) => Self::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas).max(current_max_fee),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas).max(current_priority_fee),
+            },
+            (Self::Legacy { gas_price }, _) => Self::Legacy {
+                gas_price: bump(gas_price),
+            },
+            (
+                Self::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                },
+                _,
+            ) => Self::Eip1559 {
+                max_fee_per_gas: bump(max_fee_per_gas),
+                max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+            },
+        }
+    }
+
+    pub fn apply(&self, tx: &mut TransactionRequest) {
+        match *self {
+            Self::Legacy { gas_price } => tx.set_gas_price(gas_price),
+            Self::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                tx.set_max_fee_per_gas(max_fee_per_gas);
+                tx.set_max_priority_fee_per_gas(max_priority_fee_per_gas);
+            }
+        }
+    }
+}
+
+fn median(mut values: Vec<u128>) -> u128 {
+    if values.is_empty() {
+        return 0;
+    }
+    values.sort_unstable();
+    values[values.len() / 2]
+}
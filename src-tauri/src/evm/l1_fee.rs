@@ -0,0 +1,42 @@
+use alloy::{
+    eips::eip2718::Encodable2718,
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::U256,
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    transports::Transport,
+};
+
+use crate::{consts::OP_GAS_PRICE_ORACLE_ADDR, contracts::GasPriceOracleContract, error::AppError};
+
+/// L1 data fee an OP-stack chain charges for `tx` on top of the L2 gas.
+///
+/// `tx` needs `from`, `to`, value, gas limit and fees set, it is signed so the
+/// fee is computed over its exact serialized size, but never sent
+pub async fn get_op_l1_fee<P, T>(
+    provider: &P,
+    wallet: &EthereumWallet,
+    tx: TransactionRequest,
+) -> Result<U256, AppError>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    let from = tx
+        .from
+        .ok_or(AppError::new("transaction sender is required for l1 fee"))?;
+    let nonce = provider.get_transaction_count(from).await?;
+    let chain_id = provider.get_chain_id().await?;
+
+    let tx_envelope = tx
+        .with_nonce(nonce)
+        .with_chain_id(chain_id)
+        .build(wallet)
+        .await?;
+    let tx_data = tx_envelope.encoded_2718();
+
+    let oracle = GasPriceOracleContract::new(OP_GAS_PRICE_ORACLE_ADDR, provider);
+    let l1_fee = oracle.getL1Fee(tx_data.into()).call().await?._0;
+
+    Ok(l1_fee)
+}
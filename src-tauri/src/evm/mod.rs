@@ -0,0 +1,11 @@
+mod balance;
+mod fee;
+mod l1_fee;
+mod nonce;
+mod sender;
+
+pub use balance::*;
+pub use fee::*;
+pub use l1_fee::*;
+pub use nonce::*;
+pub use sender::*;
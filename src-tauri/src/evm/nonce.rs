@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy::{
+    primitives::{Address, TxHash},
+    providers::Provider,
+    rpc::types::TransactionRequest,
+    transports::Transport,
+};
+use chrono::Utc;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use crate::error::AppError;
+
+use super::EvmFees;
+
+/// A sent transaction no receipt was seen for yet
+#[derive(Debug, Clone, Serialize)]
+pub struct EvmPendingTx {
+    pub from: Address,
+    pub nonce: u64,
+    /// the original transaction and its replacements, the last one is the latest
+    pub tx_hashes: Vec<TxHash>,
+    pub fees: EvmFees,
+    /// replacements which are 0-value self transfers
+    pub cancel_tx_hashes: Vec<TxHash>,
+    /// unix timestamp in seconds the latest transaction was sent at
+    pub sent_at: i64,
+    #[serde(skip)]
+    pub tx: TransactionRequest,
+}
+
+#[derive(Debug, Default)]
+struct WalletNonces {
+    /// fetched from the chain when unknown
+    next_nonce: Option<u64>,
+    pending: BTreeMap<u64, EvmPendingTx>,
+}
+
+/// Hands out nonces per wallet and tracks transactions until they are mined,
+/// kept for the opened project
+#[derive(Debug, Default)]
+pub struct EvmNonceManager(Mutex<HashMap<Address, WalletNonces>>);
+
+impl EvmPendingTx {
+    /// the latest transaction is a cancel
+    pub fn is_canceled(&self) -> bool {
+        self.tx_hashes
+            .last()
+            .is_some_and(|tx_hash| self.cancel_tx_hashes.contains(tx_hash))
+    }
+}
+
+impl EvmNonceManager {
+    pub async fn reserve_nonce<P, T>(&self, provider: &P, from: Address) -> Result<u64, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let mut fetched = None;
+        loop {
+            let mut guard = self.0.lock().await;
+            let wallet = guard.entry(from).or_default();
+            // NOTE: another sender of the wallet may have reserved one while this one fetched
+            if let Some(nonce) = wallet.next_nonce.or(fetched) {
+                wallet.next_nonce = Some(nonce + 1);
+                return Ok(nonce);
+            }
+            drop(guard);
+
+            // NOTE: fetched without the lock so the wallets don't wait on each other
+            fetched = Some(provider.get_transaction_count(from).pending().await?);
+        }
+    }
+
+    /// Forget the next nonce of the wallet, used after a send failed
+    /// since the reserved nonce may never be used
+    pub async fn reset(&self, from: Address) {
+        let mut guard = self.0.lock().await;
+        if let Some(wallet) = guard.get_mut(&from) {
+            wallet.next_nonce = None;
+        }
+    }
+
+    pub async fn track(&self, tx: EvmPendingTx) {
+        let mut guard = self.0.lock().await;
+        let wallet = guard.entry(tx.from).or_default();
+        wallet.pending.insert(tx.nonce, tx);
+    }
+
+    pub async fn get_pending(&self, from: Address, nonce: u64) -> Option<EvmPendingTx> {
+        let guard = self.0.lock().await;
+        guard
+            .get(&from)
+            .and_then(|wallet| wallet.pending.get(&nonce))
+            .cloned()
+    }
+
+    /// Record a replacement sent with the same nonce
+    pub async fn replaced(
+        &self,
+        from: Address,
+        nonce: u64,
+        tx_hash: TxHash,
+        tx: TransactionRequest,
+        fees: EvmFees,
+        canceled: bool,
+    ) {
+        let mut guard = self.0.lock().await;
+        let pending = guard
+            .get_mut(&from)
+            .and_then(|wallet| wallet.pending.get_mut(&nonce));
+        if let Some(pending) = pending {
+            pending.tx_hashes.push(tx_hash);
+            pending.tx = tx;
+            pending.fees = fees;
+            if canceled {
+                pending.cancel_tx_hashes.push(tx_hash);
+            }
+            pending.sent_at = Utc::now().timestamp();
+        }
+    }
+
+    pub async fn finish(&self, from: Address, nonce: u64) {
+        let mut guard = self.0.lock().await;
+        if let Some(wallet) = guard.get_mut(&from) {
+            wallet.pending.remove(&nonce);
+        }
+    }
+
+    pub async fn pending_txs(&self) -> Vec<EvmPendingTx> {
+        let guard = self.0.lock().await;
+        guard
+            .values()
+            .flat_map(|wallet| wallet.pending.values().cloned())
+            .collect()
+    }
+
+    pub async fn clear(&self) {
+        self.0.lock().await.clear();
+    }
+}
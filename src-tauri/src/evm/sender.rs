@@ -0,0 +1,344 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, TxHash, U256},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    transports::Transport,
+};
+use chrono::Utc;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+use super::{EvmFees, EvmNonceManager, EvmPendingTx};
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What to send in place of a transaction stuck in the mempool
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum EvmReplaceAction {
+    /// the same transaction with bumped fees
+    #[default]
+    SpeedUp,
+    /// a 0-value self transfer with bumped fees
+    Cancel,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvmTxConfig {
+    /// stop waiting for the receipt after this, the transaction stays pending
+    pub receipt_timeout_secs: u64,
+    /// replace the transaction when it is not mined after this, never when not set
+    pub replace_after_secs: Option<u64>,
+    pub replace_action: EvmReplaceAction,
+    /// fee bump of each replacement in percent, at least 10
+    pub fee_bump_pct: u32,
+}
+
+impl Default for EvmTxConfig {
+    fn default() -> Self {
+        Self {
+            receipt_timeout_secs: 300,
+            replace_after_secs: Some(60),
+            replace_action: EvmReplaceAction::SpeedUp,
+            fee_bump_pct: 15,
+        }
+    }
+}
+
+impl EvmTxConfig {
+    /// For transactions sending the whole balance, whose replacements would pay
+    /// more than the balance and be rejected
+    pub fn without_replacement() -> Self {
+        Self {
+            replace_after_secs: None,
+            ..Default::default()
+        }
+    }
+}
+
+/// Sends evm transactions with nonces from [`EvmNonceManager`] and waits for
+/// their receipts, replacing them when they get stuck
+pub struct EvmTxSender {
+    nonce_manager: Arc<EvmNonceManager>,
+    config: EvmTxConfig,
+    /// blocks on top of a receipt's block before the transaction is final
+    confirmations: u64,
+}
+
+/// Receipt of a transaction with enough confirmations
+#[derive(Debug, Clone)]
+pub struct EvmConfirmedTx {
+    pub receipt: TransactionReceipt,
+    pub confirmations: u64,
+    /// times the block of the receipt was reorged out before it was final
+    pub reorg_cnt: u32,
+}
+
+impl EvmTxSender {
+    pub fn new(
+        nonce_manager: Arc<EvmNonceManager>,
+        config: EvmTxConfig,
+        confirmations: u64,
+    ) -> Self {
+        Self {
+            nonce_manager,
+            config,
+            confirmations,
+        }
+    }
+
+    /// `tx` needs `from` set, nonce and fees are set here
+    pub async fn send<P, T>(
+        &self,
+        provider: &P,
+        tx: TransactionRequest,
+        fees: EvmFees,
+    ) -> Result<(Address, u64), AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let from = tx
+            .from
+            .ok_or(AppError::new("transaction sender is required"))?;
+        let nonce = self.nonce_manager.reserve_nonce(provider, from).await?;
+
+        let mut tx = tx.with_nonce(nonce);
+        fees.apply(&mut tx);
+
+        let pending = match provider.send_transaction(tx.clone()).await {
+            Ok(pending) => pending,
+            Err(err) => {
+                self.nonce_manager.reset(from).await;
+                return Err(err.into());
+            }
+        };
+        let tx_hash = *pending.tx_hash();
+        debug!("sent transaction {tx_hash} from {from} with nonce {nonce}");
+
+        self.nonce_manager
+            .track(EvmPendingTx {
+                from,
+                nonce,
+                tx_hashes: vec![tx_hash],
+                fees,
+                cancel_tx_hashes: vec![],
+                sent_at: Utc::now().timestamp(),
+                tx,
+            })
+            .await;
+
+        Ok((from, nonce))
+    }
+
+    pub async fn send_and_wait<P, T>(
+        &self,
+        provider: &P,
+        tx: TransactionRequest,
+        fees: EvmFees,
+    ) -> Result<EvmConfirmedTx, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let (from, nonce) = self.send(provider, tx, fees).await?;
+        self.wait_for_receipt(provider, from, nonce).await
+    }
+
+    /// Wait until a transaction with the nonce is mined and confirmed, the configured
+    /// replacement is sent whenever it stays pending for too long
+    pub async fn wait_for_receipt<P, T>(
+        &self,
+        provider: &P,
+        from: Address,
+        nonce: u64,
+    ) -> Result<EvmConfirmedTx, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let started_at = Instant::now();
+        let mut last_sent_at = Instant::now();
+        let receipt_timeout = Duration::from_secs(self.config.receipt_timeout_secs);
+        let replace_after = self.config.replace_after_secs.map(Duration::from_secs);
+        let mut reorg_cnt = 0;
+
+        loop {
+            let Some(pending) = self.nonce_manager.get_pending(from, nonce).await else {
+                return Err(AppError::new(format!(
+                    "no pending transaction from {from} with nonce {nonce}"
+                )));
+            };
+
+            let mut receipt = find_receipt(provider, &pending.tx_hashes).await?;
+            if receipt.is_none() {
+                let latest_nonce = provider.get_transaction_count(from).await?;
+                if latest_nonce > nonce {
+                    // NOTE: may have been mined right after the receipts were checked
+                    receipt = find_receipt(provider, &pending.tx_hashes).await?;
+                    if receipt.is_none() {
+                        self.nonce_manager.finish(from, nonce).await;
+                        return Err(AppError::new(format!(
+                            "nonce {nonce} of {from} was used by another transaction"
+                        )));
+                    }
+                }
+            }
+
+            if let Some(receipt) = receipt {
+                let tx_hash = receipt.transaction_hash;
+                if pending.cancel_tx_hashes.contains(&tx_hash) {
+                    self.nonce_manager.finish(from, nonce).await;
+                    return Err(AppError::new(format!(
+                        "transaction with nonce {nonce} was canceled by {tx_hash}"
+                    )));
+                }
+
+                match self.wait_for_confirmations(provider, &receipt).await? {
+                    Some(confirmations) => {
+                        self.nonce_manager.finish(from, nonce).await;
+                        return Ok(EvmConfirmedTx {
+                            receipt,
+                            confirmations,
+                            reorg_cnt,
+                        });
+                    }
+                    None => {
+                        reorg_cnt += 1;
+                        warn!("block of transaction {tx_hash} was reorged out, check it again");
+                        continue;
+                    }
+                }
+            }
+
+            if started_at.elapsed() > receipt_timeout {
+                return Err(AppError::new(format!(
+                    "no receipt for nonce {nonce} of {from} after {}s, transaction {} is still pending",
+                    receipt_timeout.as_secs(),
+                    pending.tx_hashes.last().unwrap()
+                )));
+            }
+
+            if replace_after.is_some_and(|replace_after| last_sent_at.elapsed() > replace_after) {
+                let action = self.config.replace_action;
+                match self.replace(provider, from, nonce, action, None).await {
+                    Ok(tx_hash) => debug!("replaced nonce {nonce} of {from} by {tx_hash}"),
+                    Err(err) => warn!("replace nonce {nonce} of {from} error: {}", err.err_msg),
+                }
+                last_sent_at = Instant::now();
+            }
+
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Confirmations once the receipt has enough of them,
+    /// `None` when its block was reorged out meanwhile
+    async fn wait_for_confirmations<P, T>(
+        &self,
+        provider: &P,
+        receipt: &TransactionReceipt,
+    ) -> Result<Option<u64>, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let (Some(block_number), Some(block_hash)) = (receipt.block_number, receipt.block_hash)
+        else {
+            return Ok(None);
+        };
+
+        loop {
+            let head_number = provider.get_block_number().await?;
+            let confirmations = (head_number + 1).saturating_sub(block_number);
+
+            // NOTE: receipt is gone or in another block when its block was reorged out
+            let current_receipt = provider
+                .get_transaction_receipt(receipt.transaction_hash)
+                .await?;
+            if current_receipt.and_then(|receipt| receipt.block_hash) != Some(block_hash) {
+                return Ok(None);
+            }
+
+            if confirmations >= self.confirmations {
+                return Ok(Some(confirmations));
+            }
+            debug!(
+                "transaction {} has {confirmations}/{} confirmations",
+                receipt.transaction_hash, self.confirmations
+            );
+
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Send a replacement of the pending transaction with the nonce, fees are
+    /// the pending fees bumped, and at least `current_fees` when given
+    pub async fn replace<P, T>(
+        &self,
+        provider: &P,
+        from: Address,
+        nonce: u64,
+        action: EvmReplaceAction,
+        current_fees: Option<EvmFees>,
+    ) -> Result<TxHash, AppError>
+    where
+        P: Provider<T>,
+        T: Transport + Clone,
+    {
+        let Some(pending) = self.nonce_manager.get_pending(from, nonce).await else {
+            return Err(AppError::new(format!(
+                "no pending transaction from {from} with nonce {nonce}"
+            )));
+        };
+
+        let current_fees = current_fees.unwrap_or(pending.fees);
+        let fees = pending.fees.bumped(self.config.fee_bump_pct, &current_fees);
+
+        let (mut tx, canceled) = match action {
+            EvmReplaceAction::SpeedUp => (pending.tx.clone(), pending.is_canceled()),
+            EvmReplaceAction::Cancel => {
+                let tx = TransactionRequest::default()
+                    .from(from)
+                    .to(from)
+                    .value(U256::ZERO)
+                    .with_gas_limit(21000)
+                    .with_nonce(nonce);
+                (tx, true)
+            }
+        };
+        fees.apply(&mut tx);
+
+        let tx_hash = *provider.send_transaction(tx.clone()).await?.tx_hash();
+        self.nonce_manager
+            .replaced(from, nonce, tx_hash, tx, fees, canceled)
+            .await;
+
+        Ok(tx_hash)
+    }
+}
+
+async fn find_receipt<P, T>(
+    provider: &P,
+    tx_hashes: &[TxHash],
+) -> Result<Option<TransactionReceipt>, AppError>
+where
+    P: Provider<T>,
+    T: Transport + Clone,
+{
+    for tx_hash in tx_hashes.iter().rev() {
+        if let Some(receipt) = provider.get_transaction_receipt(*tx_hash).await? {
+            return Ok(Some(receipt));
+        }
+    }
+
+    Ok(None)
+}
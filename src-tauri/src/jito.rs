@@ -1,14 +1,52 @@
-use log::debug;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{debug, warn};
 use rand::{thread_rng, Rng};
+use reqwest::{header::RETRY_AFTER, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::sync::watch;
 
 use crate::error::AppError;
+use crate::sol::SolTxSubmitter;
 
 #[allow(unused)]
 const MAX_RECENT_BLOCKHASHES: u64 = 300;
 
+/// a just sent bundle may not be known by the block engine yet
+const BUNDLE_INVALID_GRACE: Duration = Duration::from_secs(5);
+
+/// landed tip percentiles of the last minutes, the same for every region
+const JITO_TIP_FLOOR_URL: &str = "https://bundles.jito.wtf/api/v1/bundles/tip_floor";
+const TIP_ACCOUNTS_TTL: Duration = Duration::from_secs(600);
+const TIP_FLOOR_TTL: Duration = Duration::from_secs(10);
+
+/// max transactions of a bundle
+pub const MAX_BUNDLE_TXS: usize = 5;
+
+/// mainnet block engine regions
+pub const JITO_BLOCK_ENGINE_URLS: [&str; 5] = [
+    "https://amsterdam.mainnet.block-engine.jito.wtf",
+    "https://frankfurt.mainnet.block-engine.jito.wtf",
+    "https://ny.mainnet.block-engine.jito.wtf",
+    "https://slc.mainnet.block-engine.jito.wtf",
+    "https://tokyo.mainnet.block-engine.jito.wtf",
+];
+const REGION_PROBE_TTL: Duration = Duration::from_secs(60);
+const REGION_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// a failed region is tried last for this long when it gives no `Retry-After`
+const REGION_DEFAULT_COOLDOWN: Duration = Duration::from_secs(10);
+
 const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
     pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
     pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
@@ -20,32 +58,562 @@ const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
     pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
 ];
 
+/// How the jito tip (lamports) of a trade is chosen, capped by the task max tip
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum JitoTipStrategy {
+    /// always tip the task `gas_price`
+    #[default]
+    Fixed,
+    /// percentile of recently landed tips, one of 25, 50, 75, 95 and 99
+    TipFloor { percentile: u8 },
+    /// start from the task `gas_price`, raise it by `step_pct` after every bundle
+    /// which didn't land and go back after a bundle landed
+    Escalating { step_pct: u32 },
+}
+
+impl JitoTipStrategy {
+    /// `base_tip` is the task `gas_price`, `dropped_cnt` the number of
+    /// bundles dropped in a row by the worker
+    pub async fn tip(
+        &self,
+        tip_cache: &JitoTipCache,
+        http_client: &reqwest::Client,
+        base_tip: u64,
+        dropped_cnt: u32,
+        max_tip: Option<u64>,
+    ) -> Result<u64, AppError> {
+        let max_tip = max_tip.unwrap_or(u64::MAX);
+        let tip = match *self {
+            Self::Fixed => base_tip,
+            Self::TipFloor { percentile } => {
+                let tip_floor = tip_cache.tip_floor(http_client).await?;
+                tip_floor.percentile_lamports(percentile)
+            }
+            Self::Escalating { step_pct } => {
+                let mut tip = base_tip;
+                for _ in 0..dropped_cnt {
+                    tip = tip.saturating_mul(100 + step_pct as u64) / 100;
+                    if tip >= max_tip {
+                        break;
+                    }
+                }
+                tip
+            }
+        };
+
+        Ok(tip.min(max_tip))
+    }
+}
+
+/// Landed tips in SOL
+#[derive(Debug, Clone, Deserialize)]
+pub struct JitoTipFloor {
+    pub landed_tips_25th_percentile: f64,
+    pub landed_tips_50th_percentile: f64,
+    pub landed_tips_75th_percentile: f64,
+    pub landed_tips_95th_percentile: f64,
+    pub landed_tips_99th_percentile: f64,
+}
+
+impl JitoTipFloor {
+    /// the reported percentile at or above `percentile`
+    pub fn percentile_lamports(&self, percentile: u8) -> u64 {
+        let tip = match percentile {
+            0..=25 => self.landed_tips_25th_percentile,
+            26..=50 => self.landed_tips_50th_percentile,
+            51..=75 => self.landed_tips_75th_percentile,
+            76..=95 => self.landed_tips_95th_percentile,
+            _ => self.landed_tips_99th_percentile,
+        };
+        solana_sdk::native_token::sol_to_lamports(tip)
+    }
+}
+
+/// Tip accounts and tip floor of the block engine, shared by every worker
+#[derive(Debug, Default)]
+pub struct JitoTipCache {
+    tip_accounts: Mutex<Option<(Vec<Pubkey>, Instant)>>,
+    tip_floor: Mutex<Option<(JitoTipFloor, Instant)>>,
+}
+
+impl JitoTipCache {
+    /// A random tip account of the block engine, the well known accounts
+    /// are used when they can't be fetched
+    pub async fn tip_account(&self, jito_client: &JitoRpcClient) -> Pubkey {
+        let cached = self
+            .tip_accounts
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < TIP_ACCOUNTS_TTL)
+            .map(|(tip_accounts, _)| tip_accounts);
+
+        let tip_accounts = match cached {
+            Some(tip_accounts) => tip_accounts,
+            None => match jito_client.get_tip_accounts().await {
+                Ok(tip_accounts) if !tip_accounts.is_empty() => {
+                    let mut guard = self.tip_accounts.lock().unwrap();
+                    *guard = Some((tip_accounts.clone(), Instant::now()));
+                    tip_accounts
+                }
+                Ok(_) => JITO_TIP_ACCOUNTS.to_vec(),
+                Err(err) => {
+                    warn!("get jito tip accounts error: {}", err.err_msg);
+                    JITO_TIP_ACCOUNTS.to_vec()
+                }
+            },
+        };
+
+        let idx = thread_rng().gen_range(0..tip_accounts.len());
+        tip_accounts[idx]
+    }
+
+    pub async fn tip_floor(&self, http_client: &reqwest::Client) -> Result<JitoTipFloor, AppError> {
+        let cached = self
+            .tip_floor
+            .lock()
+            .unwrap()
+            .clone()
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < TIP_FLOOR_TTL);
+        if let Some((tip_floor, _)) = cached {
+            return Ok(tip_floor);
+        }
+
+        let resp = http_client.get(JITO_TIP_FLOOR_URL).send().await?;
+        if !resp.status().is_success() {
+            return Err(AppError::new(format!(
+                "get jito tip floor status not ok: {}",
+                resp.status()
+            )));
+        }
+        let tip_floor = resp
+            .json::<Vec<JitoTipFloor>>()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(AppError::new("no jito tip floor return"))?;
+
+        let mut guard = self.tip_floor.lock().unwrap();
+        *guard = Some((tip_floor.clone(), Instant::now()));
+        Ok(tip_floor)
+    }
+
+    pub fn clear(&self) {
+        *self.tip_accounts.lock().unwrap() = None;
+        *self.tip_floor.lock().unwrap() = None;
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JitoError {
+    #[error("jito block engine {url} rate limited")]
+    RateLimited {
+        url: String,
+        retry_after: Option<Duration>,
+    },
+    #[error("jito block engine {url} unreachable: {source}")]
+    Unreachable { url: String, source: reqwest::Error },
+    #[error("jito request status not ok: {status}, body: {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("jito request error: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+impl JitoError {
+    /// Errors of the region itself, the request is sent to the next region
+    pub fn is_failover(&self) -> bool {
+        matches!(self, Self::RateLimited { .. } | Self::Unreachable { .. })
+    }
+}
+
+/// Latency and availability of a block engine region
+#[derive(Debug, Clone, Serialize)]
+pub struct JitoRegionStats {
+    pub url: String,
+    /// round trip of the last probe in milliseconds, `None` when it failed
+    pub latency_ms: Option<u64>,
+    /// seconds left before the region is tried first again after a failure
+    pub cooldown_secs: Option<u64>,
+    #[serde(skip)]
+    probed_at: Option<Instant>,
+    #[serde(skip)]
+    unavailable_until: Option<Instant>,
+}
+
+impl JitoRegionStats {
+    fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            latency_ms: None,
+            cooldown_secs: None,
+            probed_at: None,
+            unavailable_until: None,
+        }
+    }
+
+    fn is_available(&self, now: Instant) -> bool {
+        self.unavailable_until.map_or(true, |until| until <= now)
+    }
+}
+
+/// Regions of the opened project, probed and ranked by latency
+#[derive(Debug, Default)]
+pub struct JitoRegionBook(Mutex<HashMap<String, JitoRegionStats>>);
+
+impl JitoRegionBook {
+    /// Regions fastest first, the ones which failed recently or can't be reached go last,
+    /// latencies older than the probe ttl are probed again
+    pub async fn ranked(&self, http_client: &reqwest::Client, urls: &[String]) -> Vec<String> {
+        let stale: Vec<String> = {
+            let guard = self.0.lock().unwrap();
+            urls.iter()
+                .filter(|url| {
+                    guard
+                        .get(*url)
+                        .and_then(|stats| stats.probed_at)
+                        .map_or(true, |probed_at| probed_at.elapsed() > REGION_PROBE_TTL)
+                })
+                .cloned()
+                .collect()
+        };
+        let latencies = join_all(stale.iter().map(|url| probe_region(http_client, url))).await;
+
+        let mut guard = self.0.lock().unwrap();
+        let now = Instant::now();
+        for (url, latency) in stale.iter().zip(latencies) {
+            let stats = guard
+                .entry(url.clone())
+                .or_insert_with(|| JitoRegionStats::new(url));
+            stats.latency_ms = latency.map(|latency| latency.as_millis() as u64);
+            stats.probed_at = Some(now);
+        }
+
+        let mut ranked = urls.to_vec();
+        ranked.sort_by_key(|url| match guard.get(url) {
+            Some(stats) => (
+                !stats.is_available(now),
+                stats.latency_ms.unwrap_or(u64::MAX),
+            ),
+            None => (false, u64::MAX),
+        });
+        ranked
+    }
+
+    /// Whether any of the regions hasn't failed recently
+    pub fn any_available(&self, urls: &[String]) -> bool {
+        let guard = self.0.lock().unwrap();
+        let now = Instant::now();
+        urls.iter()
+            .any(|url| guard.get(url).map_or(true, |stats| stats.is_available(now)))
+    }
+
+    pub fn unavailable(&self, url: &str, retry_after: Option<Duration>) {
+        let until = Instant::now() + retry_after.unwrap_or(REGION_DEFAULT_COOLDOWN);
+        let mut guard = self.0.lock().unwrap();
+        let stats = guard
+            .entry(url.to_string())
+            .or_insert_with(|| JitoRegionStats::new(url));
+        stats.unavailable_until = Some(stats.unavailable_until.map_or(until, |it| it.max(until)));
+    }
+
+    pub fn snapshot(&self) -> Vec<JitoRegionStats> {
+        let guard = self.0.lock().unwrap();
+        let now = Instant::now();
+        let mut result: Vec<_> = guard
+            .values()
+            .map(|stats| JitoRegionStats {
+                cooldown_secs: stats
+                    .unavailable_until
+                    .filter(|until| *until > now)
+                    .map(|until| (until - now).as_secs()),
+                ..stats.clone()
+            })
+            .collect();
+        result.sort_by(|a, b| a.url.cmp(&b.url));
+        result
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// Round trip of a `getTipAccounts` request, `None` when it failed
+async fn probe_region(http_client: &reqwest::Client, url: &str) -> Option<Duration> {
+    let req_body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getTipAccounts",
+        "params": []
+    });
+    let started_at = Instant::now();
+    let resp = http_client
+        .post(format!("{url}/api/v1/bundles"))
+        .body(req_body.to_string())
+        .header("Content-Type", "application/json")
+        .timeout(REGION_PROBE_TIMEOUT)
+        .send()
+        .await;
+    match resp {
+        Ok(resp) if resp.status().is_success() => Some(started_at.elapsed()),
+        Ok(resp) => {
+            debug!("probe jito region {url} status: {}", resp.status());
+            None
+        }
+        Err(err) => {
+            debug!("probe jito region {url} error: {err}");
+            None
+        }
+    }
+}
+
+/// State of a bundle reported by the block engine
+#[derive(Debug, strum::Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JitoBundleState {
+    /// not found in the last 5 minutes
+    Invalid,
+    Pending,
+    /// failed in every region which received it, it won't be forwarded
+    Failed,
+    Landed,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct JitoInflightBundleStatus {
+    pub bundle_id: String,
+    pub status: JitoBundleState,
+    pub landed_slot: Option<u64>,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct JitoBundleStatus {
+    pub bundle_id: String,
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    pub confirmation_status: Option<String>,
+    pub err: serde_json::Value,
+}
+
+/// Last bundle sent by a [`JitoRpcClient`]
+#[derive(Debug, Clone)]
+pub struct JitoBundle {
+    pub id: String,
+    pub state: JitoBundleState,
+    /// block engine region which accepted the bundle
+    pub region: String,
+    sent_at: Instant,
+}
+
+/// Client of the block engine regions of a project, requests go to the regions
+/// in order and fail over to the next one on rate limits and connection errors
 pub struct JitoRpcClient {
     pub http_client: reqwest::Client,
-    pub base_url: String,
+    /// fastest region first
+    pub base_urls: Vec<String>,
+    regions: Arc<JitoRegionBook>,
+    bundle: Mutex<Option<JitoBundle>>,
+    /// every region failed the last request with rate limits or connection errors
+    regions_rejected: AtomicBool,
 }
 
 impl JitoRpcClient {
-    async fn request(&self, req_body: serde_json::Value) -> Result<serde_json::Value, AppError> {
-        let api_url = format!("{}/api/v1/bundles", self.base_url);
+    pub fn new(
+        http_client: reqwest::Client,
+        base_urls: Vec<String>,
+        regions: Arc<JitoRegionBook>,
+    ) -> Self {
+        Self {
+            http_client,
+            base_urls,
+            regions,
+            bundle: Mutex::new(None),
+            regions_rejected: AtomicBool::new(false),
+        }
+    }
+
+    async fn request_result<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, AppError> {
+        let req_body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params
+        });
+        let region = self.bundle().map(|bundle| bundle.region);
+        let (_, resp_json) = self.request(req_body, region.as_deref()).await?;
+        if let Some(err) = resp_json.get("error") {
+            return Err(AppError::new(format!("jito {method} error: {err}")));
+        }
+        let result = resp_json
+            .get("result")
+            .cloned()
+            .ok_or_else(|| AppError::new(format!("jito {method} no result return")))?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Statuses of bundles sent in the last 5 minutes
+    pub async fn get_inflight_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> Result<Vec<JitoInflightBundleStatus>, AppError> {
+        #[derive(Deserialize)]
+        struct Resp {
+            value: Vec<JitoInflightBundleStatus>,
+        }
+        let resp: Resp = self
+            .request_result("getInflightBundleStatuses", json!([bundle_ids]))
+            .await?;
+        Ok(resp.value)
+    }
+
+    /// Statuses of landed bundles, `None` for the ones not landed
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> Result<Vec<Option<JitoBundleStatus>>, AppError> {
+        #[derive(Deserialize)]
+        struct Resp {
+            value: Vec<Option<JitoBundleStatus>>,
+        }
+        let resp: Resp = self
+            .request_result("getBundleStatuses", json!([bundle_ids]))
+            .await?;
+        Ok(resp.value)
+    }
+
+    pub fn bundle(&self) -> Option<JitoBundle> {
+        self.bundle.lock().unwrap().clone()
+    }
+
+    /// The block engine rejected the submission in every region and returned no bundle id,
+    /// so nothing was sent and the transaction can be sent another way
+    pub fn is_rejected(&self) -> bool {
+        self.bundle().is_none() && self.regions_rejected.load(Ordering::Relaxed)
+    }
+
+    /// Refresh the state of the last sent bundle, landed bundles older than
+    /// the inflight look back are found with `getBundleStatuses`
+    pub async fn refresh_bundle(&self) -> Result<Option<JitoBundle>, AppError> {
+        let Some(bundle) = self.bundle() else {
+            return Ok(None);
+        };
+        if bundle.state == JitoBundleState::Landed {
+            return Ok(Some(bundle));
+        }
+
+        let bundle_ids = [bundle.id.clone()];
+        let mut state = self
+            .get_inflight_bundle_statuses(&bundle_ids)
+            .await?
+            .first()
+            .map(|status| status.status)
+            .unwrap_or(JitoBundleState::Invalid);
+        if state == JitoBundleState::Invalid {
+            let landed = self.get_bundle_statuses(&bundle_ids).await?;
+            if landed.first().is_some_and(|status| status.is_some()) {
+                state = JitoBundleState::Landed;
+            }
+        }
+
+        let mut guard = self.bundle.lock().unwrap();
+        let bundle = guard.as_mut().filter(|it| it.id == bundle.id).map(|it| {
+            it.state = state;
+            it.clone()
+        });
+        Ok(bundle)
+    }
+
+    /// Send the request to `first_region` and then the other regions in order until one
+    /// of them isn't rate limited or unreachable, returns the region which answered
+    async fn request(
+        &self,
+        req_body: serde_json::Value,
+        first_region: Option<&str>,
+    ) -> Result<(String, serde_json::Value), AppError> {
+        let regions = first_region.into_iter().chain(
+            self.base_urls
+                .iter()
+                .map(|url| url.as_str())
+                .filter(|url| Some(*url) != first_region),
+        );
+
+        self.regions_rejected.store(false, Ordering::Relaxed);
+        let mut last_err = None;
+        for region in regions {
+            match self.request_region(region, &req_body).await {
+                Ok(resp_json) => return Ok((region.to_string(), resp_json)),
+                Err(err) if err.is_failover() => {
+                    warn!("{err}, try next jito region");
+                    let retry_after = match &err {
+                        JitoError::RateLimited { retry_after, .. } => *retry_after,
+                        _ => None,
+                    };
+                    self.regions.unavailable(region, retry_after);
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        let Some(last_err) = last_err else {
+            return Err(AppError::new("no jito block engine configured"));
+        };
+        self.regions_rejected.store(true, Ordering::Relaxed);
+        Err(last_err.into())
+    }
+
+    async fn request_region(
+        &self,
+        base_url: &str,
+        req_body: &serde_json::Value,
+    ) -> Result<serde_json::Value, JitoError> {
+        let api_url = format!("{base_url}/api/v1/bundles");
         let resp = self
             .http_client
             .post(api_url)
             .body(req_body.to_string())
             .header("Content-Type", "application/json")
             .send()
-            .await?;
+            .await
+            .map_err(|err| {
+                if err.is_connect() || err.is_timeout() {
+                    JitoError::Unreachable {
+                        url: base_url.to_string(),
+                        source: err,
+                    }
+                } else {
+                    JitoError::Request(err)
+                }
+            })?;
 
-        let resp_json: serde_json::Value = resp.json().await?;
-        Ok(resp_json)
-    }
-
-    pub fn get_tip_account() -> Pubkey {
-        let idx: usize = thread_rng().gen_range(0..8);
-        JITO_TIP_ACCOUNTS[idx]
+        let status = resp.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = resp
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(JitoError::RateLimited {
+                url: base_url.to_string(),
+                retry_after,
+            });
+        }
+        // NOTE: json-rpc errors like an invalid bundle come with a 400 and a json body
+        let body = resp.text().await?;
+        match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(resp_json) => Ok(resp_json),
+            Err(_) => Err(JitoError::Status { status, body }),
+        }
     }
 
-    pub async fn send_bundle(&self, bs58_txs: &[String]) -> Result<String, AppError> {
+    /// Returns the bundle id and the region which accepted it
+    pub async fn send_bundle(&self, bs58_txs: &[String]) -> Result<(String, String), AppError> {
         let req_body = json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -54,19 +622,52 @@ impl JitoRpcClient {
         });
 
         debug!("send request to jito: {req_body}");
-        let resp_json = self.request(req_body).await?;
-        debug!("jito response: {resp_json}");
+        let (region, resp_json) = self.request(req_body, None).await?;
+        debug!("jito {region} response: {resp_json}");
 
+        if let Some(err) = resp_json.get("error") {
+            return Err(AppError::new(format!("jito sendBundle error: {err}")));
+        }
         let result = resp_json
             .get("result")
             .and_then(|it| it.as_str())
             .map(|it| it.to_string())
             .ok_or_else(|| AppError::new("no bundle id return"))?;
-        Ok(result)
+        Ok((result, region))
+    }
+
+    /// Send the transactions as one bundle and track its state
+    pub async fn submit_bundle(
+        &self,
+        txs: &[VersionedTransaction],
+    ) -> Result<JitoBundle, AppError> {
+        let mut base58_txs = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let tx_bytes = bincode::serialize(tx)?;
+            base58_txs.push(bs58::encode(tx_bytes).into_string());
+        }
+        let (bundle_id, region) = self.send_bundle(&base58_txs).await?;
+
+        let bundle = JitoBundle {
+            id: bundle_id,
+            state: JitoBundleState::Pending,
+            region,
+            sent_at: Instant::now(),
+        };
+        Ok(self.track_bundle(bundle))
+    }
+
+    /// Track a bundle sent by another client, like a bundle of several wallets
+    pub fn track_bundle(&self, bundle: JitoBundle) -> JitoBundle {
+        // NOTE: bundle id is the hash of its signatures, rebroadcasts return the same id
+        let mut guard = self.bundle.lock().unwrap();
+        if guard.as_ref().map_or(true, |it| it.id != bundle.id) {
+            *guard = Some(bundle);
+        }
+        guard.clone().unwrap()
     }
 
-    #[allow(unused)]
-    pub async fn get_tip_accounts(&self) -> Result<Vec<String>, AppError> {
+    pub async fn get_tip_accounts(&self) -> Result<Vec<Pubkey>, AppError> {
         let req_body = json!({
             "jsonrpc": "2.0",
             "id": 1,
@@ -74,16 +675,245 @@ impl JitoRpcClient {
             "params": []
         });
 
-        let resp_json = self.request(req_body).await?;
+        let (_, resp_json) = self.request(req_body, None).await?;
         let result = resp_json
             .get("result")
             .and_then(|it| it.as_array())
             .map(|it| {
                 it.iter()
-                    .filter_map(|addr| addr.as_str().map(|item| item.to_string()))
+                    .filter_map(|addr| addr.as_str().and_then(|it| Pubkey::from_str(it).ok()))
                     .collect::<Vec<_>>()
             });
 
         Ok(result.unwrap_or_default())
     }
 }
+
+#[async_trait]
+impl SolTxSubmitter for JitoRpcClient {
+    async fn submit(
+        &self,
+        tx: &VersionedTransaction,
+        _is_rebroadcast: bool,
+    ) -> Result<(), AppError> {
+        self.submit_bundle(std::slice::from_ref(tx)).await?;
+        Ok(())
+    }
+
+    async fn is_dropped(&self, _txid: &Signature) -> bool {
+        match self.refresh_bundle().await {
+            Ok(Some(bundle)) => match bundle.state {
+                JitoBundleState::Failed => true,
+                JitoBundleState::Invalid => bundle.sent_at.elapsed() > BUNDLE_INVALID_GRACE,
+                JitoBundleState::Pending | JitoBundleState::Landed => false,
+            },
+            Ok(None) => false,
+            Err(err) => {
+                warn!("refresh jito bundle error: {}", err.err_msg);
+                false
+            }
+        }
+    }
+}
+
+/// Signed swap of a wallet waiting to be bundled, `tipped_tx` is the same swap
+/// with the tip transfer appended, used when it is the last of the bundle
+#[derive(Debug, Clone)]
+pub struct JitoBundleEntry {
+    pub tx: VersionedTransaction,
+    pub tipped_tx: VersionedTransaction,
+}
+
+/// Bundle of several wallets' swaps sent by a [`JitoBundleCollector`]
+#[derive(Debug, Clone)]
+pub struct JitoSentBundle {
+    pub bundle: JitoBundle,
+    pub txs: Vec<VersionedTransaction>,
+}
+
+impl JitoSentBundle {
+    /// The transaction of the entry which went into the bundle and whether it carries the tip
+    pub fn tx_of(&self, entry: &JitoBundleEntry) -> Option<(VersionedTransaction, bool)> {
+        let is_tipped = self.txs.last()?.signatures == entry.tipped_tx.signatures;
+        if is_tipped {
+            return Some((entry.tipped_tx.clone(), true));
+        }
+        self.txs
+            .iter()
+            .find(|tx| tx.signatures == entry.tx.signatures)
+            .map(|tx| (tx.clone(), false))
+    }
+}
+
+struct PendingBundle {
+    id: u64,
+    entries: Vec<JitoBundleEntry>,
+    sent: watch::Sender<Option<SentBundleResult>>,
+}
+
+/// Collects the swaps of a task's workers into one bundle of up to `size` transactions,
+/// only the last transaction tips. Bundles which aren't full after `max_wait` are sent
+/// with the collected swaps
+pub struct JitoBundleCollector {
+    size: usize,
+    max_wait: Duration,
+    pending: Mutex<Option<PendingBundle>>,
+    next_id: AtomicU64,
+}
+
+impl std::fmt::Debug for JitoBundleCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JitoBundleCollector")
+            .field("size", &self.size)
+            .field("max_wait", &self.max_wait)
+            .finish()
+    }
+}
+
+impl JitoBundleCollector {
+    pub fn new(size: usize, max_wait: Duration) -> Self {
+        Self {
+            size: size.clamp(1, MAX_BUNDLE_TXS),
+            max_wait,
+            pending: Mutex::new(None),
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Add the swap to the pending bundle and wait until the bundle is sent,
+    /// the worker which fills the bundle or times out sends it with its client
+    pub async fn join(
+        &self,
+        jito_client: &JitoRpcClient,
+        entry: JitoBundleEntry,
+    ) -> Result<JitoSentBundle, JitoBundleSendError> {
+        let (pending_id, mut sent_rx, full) = {
+            let mut guard = self.pending.lock().unwrap();
+            let pending = guard.get_or_insert_with(|| PendingBundle {
+                id: self.next_id.fetch_add(1, Ordering::Relaxed),
+                entries: vec![],
+                sent: watch::channel(None).0,
+            });
+            pending.entries.push(entry);
+            let pending_id = pending.id;
+            let sent_rx = pending.sent.subscribe();
+            let full = if pending.entries.len() >= self.size {
+                guard.take()
+            } else {
+                None
+            };
+            (pending_id, sent_rx, full)
+        };
+        if let Some(pending) = full {
+            return self.send(jito_client, pending).await;
+        }
+
+        let sent = match tokio::time::timeout(self.max_wait, wait_sent(&mut sent_rx)).await {
+            Ok(sent) => sent,
+            Err(_) => {
+                let timed_out = {
+                    let mut guard = self.pending.lock().unwrap();
+                    if guard
+                        .as_ref()
+                        .is_some_and(|pending| pending.id == pending_id)
+                    {
+                        guard.take()
+                    } else {
+                        None
+                    }
+                };
+                match timed_out {
+                    Some(pending) => return self.send(jito_client, pending).await,
+                    // NOTE: another worker is sending it right now
+                    None => wait_sent(&mut sent_rx).await,
+                }
+            }
+        };
+
+        sent
+    }
+
+    async fn send(
+        &self,
+        jito_client: &JitoRpcClient,
+        pending: PendingBundle,
+    ) -> Result<JitoSentBundle, JitoBundleSendError> {
+        let mut txs: Vec<VersionedTransaction> = vec![];
+        let last_idx = pending.entries.len() - 1;
+        for (idx, entry) in pending.entries.into_iter().enumerate() {
+            txs.push(if idx == last_idx {
+                entry.tipped_tx
+            } else {
+                entry.tx
+            });
+        }
+
+        let result = match jito_client.submit_bundle(&txs).await {
+            Ok(bundle) => Ok(JitoSentBundle { bundle, txs }),
+            Err(err) if jito_client.is_rejected() => {
+                Err(JitoBundleSendError::Rejected(err.err_msg))
+            }
+            Err(err) => Err(JitoBundleSendError::Failed(err.err_msg)),
+        };
+        pending
+            .sent
+            .send_modify(|sent| *sent = Some(result.clone()));
+        result
+    }
+}
+
+/// Why the bundle of a [`JitoBundleCollector`] wasn't sent, every worker in it gets the same
+#[derive(Debug, Clone)]
+pub enum JitoBundleSendError {
+    /// every region rejected the bundle before returning a bundle id, nothing was sent
+    Rejected(String),
+    Failed(String),
+}
+
+type SentBundleResult = Result<JitoSentBundle, JitoBundleSendError>;
+
+async fn wait_sent(sent_rx: &mut watch::Receiver<Option<SentBundleResult>>) -> SentBundleResult {
+    match sent_rx.wait_for(Option::is_some).await {
+        Ok(sent) => sent.clone().unwrap(),
+        Err(_) => Err(JitoBundleSendError::Failed(
+            "jito bundle was given up before sent".to_string(),
+        )),
+    }
+}
+
+/// Submitter of a transaction already sent with a multi-wallet bundle,
+/// rebroadcasts resend the whole bundle from the worker whose transaction tips
+pub struct JitoBundleSubmitter {
+    jito_client: Arc<JitoRpcClient>,
+    sent: JitoSentBundle,
+    is_tipped: bool,
+}
+
+impl JitoBundleSubmitter {
+    pub fn new(jito_client: Arc<JitoRpcClient>, sent: JitoSentBundle, is_tipped: bool) -> Self {
+        jito_client.track_bundle(sent.bundle.clone());
+        Self {
+            jito_client,
+            sent,
+            is_tipped,
+        }
+    }
+}
+
+#[async_trait]
+impl SolTxSubmitter for JitoBundleSubmitter {
+    async fn submit(
+        &self,
+        _tx: &VersionedTransaction,
+        is_rebroadcast: bool,
+    ) -> Result<(), AppError> {
+        if is_rebroadcast && self.is_tipped {
+            self.jito_client.submit_bundle(&self.sent.txs).await?;
+        }
+        Ok(())
+    }
+
+    async fn is_dropped(&self, txid: &Signature) -> bool {
+        self.jito_client.is_dropped(txid).await
+    }
+}
@@ -1,5 +1,7 @@
+use std::time::Duration;
+
 use anyhow::{anyhow, Result};
-use reqwest::Response;
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 
 use self::quote::{QuoteRequest, QuoteResponse};
@@ -14,7 +16,40 @@ mod serde_helpers;
 pub mod swap;
 pub mod transaction_config;
 
+/// header paid tiers and self-hosted apis read the api key from
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Api answered 429, `retry_after` is from its `Retry-After` header when given in seconds
+#[derive(Debug, thiserror::Error)]
+#[error("Jupiter api rate limited, retry after {retry_after:?}")]
+pub struct RateLimitedError {
+    pub retry_after: Option<Duration>,
+}
+
+/// `base_path` is the prefix of `/quote` and `/swap-instructions`, like
+/// `https://quote-api.jup.ag/v6`, `https://api.jup.ag/swap/v1` for keyed tiers
+/// or the root of a self-hosted api
+fn endpoint(base_path: impl Into<String>, path: &str) -> String {
+    format!("{}{path}", base_path.into().trim_end_matches('/'))
+}
+
+fn with_api_key(request: RequestBuilder, api_key: Option<&str>) -> RequestBuilder {
+    match api_key {
+        Some(api_key) if !api_key.is_empty() => request.header(API_KEY_HEADER, api_key),
+        _ => request,
+    }
+}
+
 async fn check_is_success(response: Response) -> Result<Response> {
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        return Err(RateLimitedError { retry_after }.into());
+    }
     if !response.status().is_success() {
         return Err(anyhow!(
             "Request status not ok: {}, body: {:?}",
@@ -36,23 +71,23 @@ async fn check_status_code_and_deserialize<T: DeserializeOwned>(response: Respon
 pub async fn quote(
     http_client: &reqwest::Client,
     base_path: impl Into<String>,
+    api_key: Option<&str>,
     quote_request: &QuoteRequest,
 ) -> Result<QuoteResponse> {
     let query = serde_qs::to_string(&quote_request)?;
-    let response = http_client
-        .get(format!("{}/quote?{query}", base_path.into()))
-        .send()
-        .await?;
+    let url = format!("{}?{query}", endpoint(base_path, "/quote"));
+    let response = with_api_key(http_client.get(url), api_key).send().await?;
     check_status_code_and_deserialize(response).await
 }
 
 pub async fn swap(
     http_client: &reqwest::Client,
     base_path: impl Into<String>,
+    api_key: Option<&str>,
     swap_request: &SwapRequest,
 ) -> Result<SwapResponse> {
-    let response = http_client
-        .post(format!("{}/swap", base_path.into()))
+    let request = http_client.post(endpoint(base_path, "/swap"));
+    let response = with_api_key(request, api_key)
         .json(swap_request)
         .send()
         .await?;
@@ -62,10 +97,11 @@ pub async fn swap(
 pub async fn swap_instructions(
     http_client: &reqwest::Client,
     base_path: impl Into<String>,
+    api_key: Option<&str>,
     swap_request: &SwapRequest,
 ) -> Result<SwapInstructionsResponse> {
-    let response = http_client
-        .post(format!("{}/swap-instructions", base_path.into()))
+    let request = http_client.post(endpoint(base_path, "/swap-instructions"));
+    let response = with_api_key(request, api_key)
         .json(swap_request)
         .send()
         .await?;
@@ -0,0 +1,394 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::Utc;
+use rusqlite::{
+    params_from_iter,
+    types::{Type, Value},
+    Connection, OptionalExtension, Row,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{agg::AggRouteStep, error::AppError, jito::JitoBundleState, task::TradeDirection};
+
+/// page size of a query which doesn't set one
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id TEXT NOT NULL,
+    worker_id INTEGER NOT NULL,
+    wallet TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    input_mint TEXT NOT NULL,
+    output_mint TEXT NOT NULL,
+    input_amount TEXT NOT NULL,
+    quoted_output TEXT,
+    realized_output TEXT,
+    fee TEXT,
+    tip INTEGER,
+    txid TEXT,
+    proxy TEXT,
+    aggregator TEXT,
+    route_plan TEXT,
+    bundle_id TEXT,
+    bundle_state TEXT,
+    confirmations INTEGER,
+    reorg_cnt INTEGER,
+    status TEXT NOT NULL,
+    error TEXT,
+    started_at INTEGER NOT NULL,
+    finished_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_task_id ON trades (task_id, started_at);
+CREATE INDEX IF NOT EXISTS trades_wallet ON trades (wallet, started_at);
+CREATE INDEX IF NOT EXISTS trades_started_at ON trades (started_at);
+";
+
+/// applied in order on top of `SCHEMA`, the `user_version` of the database is
+/// the number of migrations applied
+const MIGRATIONS: &[&str] = &["
+ALTER TABLE trades ADD COLUMN realized_input TEXT;
+ALTER TABLE trades ADD COLUMN effective_price REAL;
+ALTER TABLE trades ADD COLUMN slippage_bps REAL;
+ALTER TABLE trades ADD COLUMN total_fee TEXT;
+"];
+
+const INSERT_COLUMNS: &str = "task_id, worker_id, wallet, direction, input_mint, output_mint, \
+    input_amount, quoted_output, realized_output, fee, tip, txid, proxy, aggregator, \
+    route_plan, bundle_id, bundle_state, confirmations, reorg_cnt, status, error, \
+    started_at, finished_at, realized_input, effective_price, slippage_bps, total_fee";
+
+/// How a trade attempt ended
+#[derive(Debug, strum::Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeStatus {
+    /// the swap landed and succeeded
+    Success,
+    /// the swap landed but failed
+    Failed,
+    /// the swap never landed
+    Dropped,
+    /// the solana blockhash of the swap expired before it landed
+    Expired,
+    /// the attempt errored before the swap was sent, like a failed quote
+    Error,
+}
+
+/// A trade attempt of a worker, amounts are strings of integers in the smallest unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    /// assigned when it is written
+    pub id: Option<i64>,
+    pub task_id: String,
+    pub worker_id: u32,
+    pub wallet: String,
+    pub direction: TradeDirection,
+    pub input_mint: String,
+    pub output_mint: String,
+    pub input_amount: String,
+    pub quoted_output: Option<String>,
+    /// input spent by the wallet according to the chain, once the swap landed
+    pub realized_input: Option<String>,
+    /// output received by the wallet according to the chain, once the swap landed
+    pub realized_output: Option<String>,
+    /// native coin per token of the realized amounts
+    pub effective_price: Option<f64>,
+    /// basis points the realized output is below the quoted output
+    pub slippage_bps: Option<f64>,
+    /// native fee of the swap transaction, without the jito tip
+    pub fee: Option<String>,
+    /// fees and the jito tip charged according to the chain
+    pub total_fee: Option<String>,
+    /// lamports tipped to jito
+    pub tip: Option<u64>,
+    pub txid: Option<String>,
+    pub proxy: Option<String>,
+    pub aggregator: Option<String>,
+    pub route_plan: Option<Vec<AggRouteStep>>,
+    pub bundle_id: Option<String>,
+    pub bundle_state: Option<JitoBundleState>,
+    pub confirmations: Option<u64>,
+    pub reorg_cnt: Option<u32>,
+    pub status: TradeStatus,
+    pub error: Option<String>,
+    /// unix timestamps in milliseconds
+    pub started_at: i64,
+    pub finished_at: i64,
+}
+
+impl TradeRecord {
+    /// An attempt which errors unless its outcome is set
+    pub fn new(
+        task_id: impl Into<String>,
+        worker_id: u32,
+        wallet: impl Into<String>,
+        direction: TradeDirection,
+        input_mint: impl Into<String>,
+        output_mint: impl Into<String>,
+        input_amount: impl Into<String>,
+    ) -> Self {
+        let now = Utc::now().timestamp_millis();
+        Self {
+            id: None,
+            task_id: task_id.into(),
+            worker_id,
+            wallet: wallet.into(),
+            direction,
+            input_mint: input_mint.into(),
+            output_mint: output_mint.into(),
+            input_amount: input_amount.into(),
+            quoted_output: None,
+            realized_input: None,
+            realized_output: None,
+            effective_price: None,
+            slippage_bps: None,
+            fee: None,
+            total_fee: None,
+            tip: None,
+            txid: None,
+            proxy: None,
+            aggregator: None,
+            route_plan: None,
+            bundle_id: None,
+            bundle_state: None,
+            confirmations: None,
+            reorg_cnt: None,
+            status: TradeStatus::Error,
+            error: None,
+            started_at: now,
+            finished_at: now,
+        }
+    }
+}
+
+/// Filters of a trade records query, every filter set must match
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TradeRecordFilter {
+    pub task_id: Option<String>,
+    pub worker_id: Option<u32>,
+    pub wallet: Option<String>,
+    pub direction: Option<TradeDirection>,
+    pub status: Option<TradeStatus>,
+    pub txid: Option<String>,
+    /// unix timestamps in milliseconds of the start of the attempts, `to` exclusive
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+    /// starts from 0, latest attempts first
+    pub page: u32,
+    /// `DEFAULT_PAGE_SIZE` if not set, at most `MAX_PAGE_SIZE`
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeRecordPage {
+    /// records matching the filters on every page
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub records: Vec<TradeRecord>,
+}
+
+/// Trade attempts of a project, kept in a sqlite database next to the project file
+#[derive(Debug)]
+pub struct TradeLedger {
+    conn: Mutex<Connection>,
+}
+
+impl TradeLedger {
+    pub fn db_path(project_path: impl AsRef<Path>) -> PathBuf {
+        project_path.as_ref().with_extension("ledger.db")
+    }
+
+    pub fn open(project_path: impl AsRef<Path>) -> Result<Self, AppError> {
+        let mut conn = Connection::open(Self::db_path(project_path))?;
+        conn.execute_batch(SCHEMA)?;
+        let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            // NOTE: a migration and its version commit together, a failed one is rolled back
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", idx + 1)?;
+            tx.commit()?;
+        }
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Write the record and return its id
+    pub fn insert(&self, record: &TradeRecord) -> Result<i64, AppError> {
+        let route_plan = record
+            .route_plan
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        let bundle_state = record.bundle_state.map(|state| state.to_string());
+        let values = vec![
+            Value::from(record.task_id.clone()),
+            Value::from(record.worker_id),
+            Value::from(record.wallet.clone()),
+            Value::from(record.direction.to_string()),
+            Value::from(record.input_mint.clone()),
+            Value::from(record.output_mint.clone()),
+            Value::from(record.input_amount.clone()),
+            Value::from(record.quoted_output.clone()),
+            Value::from(record.realized_output.clone()),
+            Value::from(record.fee.clone()),
+            Value::from(record.tip.map(|tip| tip as i64)),
+            Value::from(record.txid.clone()),
+            Value::from(record.proxy.clone()),
+            Value::from(record.aggregator.clone()),
+            Value::from(route_plan),
+            Value::from(record.bundle_id.clone()),
+            Value::from(bundle_state),
+            Value::from(record.confirmations.map(|cnt| cnt as i64)),
+            Value::from(record.reorg_cnt),
+            Value::from(record.status.to_string()),
+            Value::from(record.error.clone()),
+            Value::from(record.started_at),
+            Value::from(record.finished_at),
+            Value::from(record.realized_input.clone()),
+            Value::from(record.effective_price),
+            Value::from(record.slippage_bps),
+            Value::from(record.total_fee.clone()),
+        ];
+        let placeholders = vec!["?"; values.len()].join(", ");
+        let sql = format!("INSERT INTO trades ({INSERT_COLUMNS}) VALUES ({placeholders})");
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(&sql, params_from_iter(values))?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<TradeRecord>, AppError> {
+        let conn = self.conn.lock().unwrap();
+        let sql = format!("SELECT id, {INSERT_COLUMNS} FROM trades WHERE id = ?");
+        let record = conn.query_row(&sql, [id], read_record).optional()?;
+        Ok(record)
+    }
+
+    pub fn query(&self, filter: &TradeRecordFilter) -> Result<TradeRecordPage, AppError> {
+        let mut conditions = vec![];
+        let mut values = vec![];
+        let mut add = |condition: &str, value: Value| {
+            conditions.push(condition.to_string());
+            values.push(value);
+        };
+        if let Some(task_id) = &filter.task_id {
+            add("task_id = ?", Value::from(task_id.clone()));
+        }
+        if let Some(worker_id) = filter.worker_id {
+            add("worker_id = ?", Value::from(worker_id));
+        }
+        if let Some(wallet) = &filter.wallet {
+            add("wallet = ?", Value::from(wallet.clone()));
+        }
+        if let Some(direction) = filter.direction {
+            add("direction = ?", Value::from(direction.to_string()));
+        }
+        if let Some(status) = filter.status {
+            add("status = ?", Value::from(status.to_string()));
+        }
+        if let Some(txid) = &filter.txid {
+            add("txid = ?", Value::from(txid.clone()));
+        }
+        if let Some(from) = filter.from {
+            add("started_at >= ?", Value::from(from));
+        }
+        if let Some(to) = filter.to {
+            add("started_at < ?", Value::from(to));
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let page_size = filter
+            .page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let offset = filter.page as i64 * page_size as i64;
+
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM trades {where_clause}"),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let sql = format!(
+            "SELECT id, {INSERT_COLUMNS} FROM trades {where_clause} \
+            ORDER BY started_at DESC, id DESC LIMIT ? OFFSET ?"
+        );
+        values.push(Value::from(page_size));
+        values.push(Value::from(offset));
+        let mut stmt = conn.prepare(&sql)?;
+        let records = stmt
+            .query_map(params_from_iter(values.iter()), read_record)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(TradeRecordPage {
+            total: total as u64,
+            page: filter.page,
+            page_size,
+            records,
+        })
+    }
+}
+
+fn read_record(row: &Row) -> rusqlite::Result<TradeRecord> {
+    let route_plan = row
+        .get::<_, Option<String>>(15)?
+        .map(|json| {
+            serde_json::from_str(&json).map_err(|err| {
+                rusqlite::Error::FromSqlConversionFailure(15, Type::Text, err.into())
+            })
+        })
+        .transpose()?;
+
+    Ok(TradeRecord {
+        id: row.get(0)?,
+        task_id: row.get(1)?,
+        worker_id: row.get(2)?,
+        wallet: row.get(3)?,
+        direction: read_enum(row, 4)?,
+        input_mint: row.get(5)?,
+        output_mint: row.get(6)?,
+        input_amount: row.get(7)?,
+        quoted_output: row.get(8)?,
+        realized_output: row.get(9)?,
+        fee: row.get(10)?,
+        tip: row.get::<_, Option<i64>>(11)?.map(|tip| tip as u64),
+        txid: row.get(12)?,
+        proxy: row.get(13)?,
+        aggregator: row.get(14)?,
+        route_plan,
+        bundle_id: row.get(16)?,
+        bundle_state: read_enum(row, 17)?,
+        confirmations: row.get::<_, Option<i64>>(18)?.map(|cnt| cnt as u64),
+        reorg_cnt: row.get(19)?,
+        status: read_enum(row, 20)?,
+        error: row.get(21)?,
+        started_at: row.get(22)?,
+        finished_at: row.get(23)?,
+        realized_input: row.get(24)?,
+        effective_price: row.get(25)?,
+        slippage_bps: row.get(26)?,
+        total_fee: row.get(27)?,
+    })
+}
+
+/// Unit variants are written by their names
+fn read_enum<T: DeserializeOwned>(row: &Row, idx: usize) -> rusqlite::Result<T> {
+    let value = match row.get::<_, Option<String>>(idx)? {
+        Some(name) => serde_json::Value::String(name),
+        None => serde_json::Value::Null,
+    };
+    serde_json::from_value(value)
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, err.into()))
+}
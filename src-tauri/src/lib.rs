@@ -1,10 +1,16 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use evm::EvmNonceManager;
+use jito::{JitoRegionBook, JitoTipCache};
 use log::{debug, LevelFilter};
+use sol::SolEndpointStatsBook;
 use state::{
-    EvmProviderState, EvmRpcClientState, HttpClientState, ProjectState, ProxiedHttpClientState,
-    SolRpcClientState, TradeTaskState,
+    EvmNonceManagerState, EvmProviderState, EvmRpcClientState, HttpClientState, JitoRegionsState,
+    JitoTipCacheState, ProjectState, ProxiedHttpClientState, SolChainCacheState,
+    SolEndpointStatsState, SolRpcClientState, SolSendRpcClientsState, SolTxConfirmerState,
+    TaskSchedulerState, TradeLedgerState, TradeTaskState,
 };
+use task::TaskScheduler;
 use tauri::{
     menu::{AboutMetadata, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconEvent},
@@ -18,18 +24,24 @@ use tauri_plugin_log::{
     Target, TargetKind,
 };
 
+mod agg;
 mod chain;
 mod commands;
 mod consts;
 mod contracts;
 mod error;
+mod evm;
 mod jito;
 #[allow(unused)]
 mod jup;
+mod ledger;
 mod one_inch;
 mod project;
+mod raydium;
+mod sol;
 mod state;
 mod task;
+mod tls;
 mod token;
 mod utils;
 mod wallet;
@@ -45,7 +57,6 @@ pub fn run() {
     let default_http_client = reqwest::ClientBuilder::default()
         .connect_timeout(Duration::from_secs(5))
         .read_timeout(Duration::from_secs(10))
-        .danger_accept_invalid_certs(true)
         .build()
         .unwrap();
 
@@ -71,9 +82,18 @@ pub fn run() {
         .plugin(tauri_plugin_os::init())
         .manage(ProjectState::new(None))
         .manage(TradeTaskState::new(HashMap::new()))
+        .manage(TradeLedgerState::new(None))
+        .manage::<TaskSchedulerState>(Arc::new(TaskScheduler::default()))
         .manage(SolRpcClientState::new(None))
+        .manage(SolTxConfirmerState::new(None))
+        .manage(SolChainCacheState::new(None))
+        .manage(SolSendRpcClientsState::new(vec![]))
+        .manage::<SolEndpointStatsState>(Arc::new(SolEndpointStatsBook::default()))
+        .manage::<JitoTipCacheState>(Arc::new(JitoTipCache::default()))
+        .manage::<JitoRegionsState>(Arc::new(JitoRegionBook::default()))
         .manage(EvmRpcClientState::new(None))
         .manage(EvmProviderState::new(None))
+        .manage::<EvmNonceManagerState>(Arc::new(EvmNonceManager::default()))
         .manage(ProxiedHttpClientState::new(vec![]))
         .manage(HttpClientState(default_http_client))
         .menu(|app_handle| {
@@ -229,6 +249,8 @@ fn setup_app(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
         main_window.set_focus()?;
     }
 
+    tauri::async_runtime::spawn(TaskScheduler::run(app.handle().clone()));
+
     Ok(())
 }
 
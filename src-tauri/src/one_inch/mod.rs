@@ -1,17 +1,34 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use alloy::{
     network::TransactionBuilder,
     primitives::{Address, Bytes, U256},
     rpc::types::TransactionRequest,
 };
-use serde::{Deserialize, Serialize};
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
 use crate::error::AppError;
 
 #[serde_as]
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteQueryParams {
+    pub src: Address,
+    pub dst: Address,
+
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: U256,
+    /// comma separated liquidity sources, all of them when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocols: Option<String>,
+    pub include_gas: bool,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SwapQueryParams {
     pub src: Address,
     pub dst: Address,
@@ -21,6 +38,51 @@ pub struct SwapQueryParams {
     pub from: Address,
     pub origin: Address,
     pub slippage: u16,
+    /// comma separated liquidity sources, all of them when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocols: Option<String>,
+    /// skip the balance and allowance checks of the api
+    pub disable_estimate: bool,
+    pub include_gas: bool,
+}
+
+#[allow(unused)]
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+    #[serde_as(as = "DisplayFromStr")]
+    pub dst_amount: U256,
+    /// only returned with `includeGas`
+    pub gas: Option<u128>,
+}
+
+/// Error body of the 1inch api
+#[allow(unused)]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiErrorResponse {
+    pub error: String,
+    pub description: String,
+    pub status_code: u16,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OneInchError {
+    #[error("1inch api key is invalid or missing")]
+    Unauthorized,
+    /// `retry_after` is from the `Retry-After` header when given in seconds
+    #[error("1inch api rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("1inch api error {}: {}", .0.status_code, .0.description)]
+    Api(ApiErrorResponse),
+    #[error("1inch request status not ok: {status}, body: {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("1inch request error: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("1inch query error: {0}")]
+    Query(#[from] serde_qs::Error),
 }
 
 #[allow(unused)]
@@ -35,7 +97,7 @@ pub struct SwapResponse {
 
 #[allow(unused)]
 #[serde_as]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapTxData {
     pub data: String,
@@ -48,39 +110,85 @@ pub struct SwapTxData {
     pub value: U256,
 }
 
+/// fees are left unset, they are chosen by the caller
 impl TryFrom<SwapTxData> for TransactionRequest {
     type Error = AppError;
 
     fn try_from(value: SwapTxData) -> Result<Self, Self::Error> {
-        let gas_price: u128 = value.gas_price.try_into()?;
         let input_bytes = Bytes::from_str(&value.data)?;
         let result = TransactionRequest::default()
             .from(value.from)
             .to(value.to)
             .value(value.value)
-            .with_input(input_bytes)
-            .with_gas_price(gas_price);
+            .with_input(input_bytes);
         Ok(result)
     }
 }
 
-pub async fn get_swap_data(
+async fn check_is_success(response: Response) -> Result<Response, OneInchError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    match status {
+        StatusCode::UNAUTHORIZED => Err(OneInchError::Unauthorized),
+        StatusCode::TOO_MANY_REQUESTS => {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            Err(OneInchError::RateLimited { retry_after })
+        }
+        _ => {
+            let body = response.text().await?;
+            match serde_json::from_str::<ApiErrorResponse>(&body) {
+                Ok(api_err) => Err(OneInchError::Api(api_err)),
+                Err(_) => Err(OneInchError::Status { status, body }),
+            }
+        }
+    }
+}
+
+async fn get<T: DeserializeOwned>(
     client: &reqwest::Client,
-    base_url: &str,
+    api_url: String,
     api_key: &str,
-    chain_id: u64,
-    query: SwapQueryParams,
-) -> Result<SwapResponse, AppError> {
-    let query_params = serde_qs::to_string(&query)?;
-    let api_url = format!("{base_url}/swap/v6.0/{chain_id}/swap?{query_params}");
+) -> Result<T, OneInchError> {
     let auth_header_value = format!("Bearer {api_key}");
-
     let resp = client
         .get(api_url)
         .header("Authorization", auth_header_value)
         .send()
         .await?;
-    let resp_data = resp.json::<SwapResponse>().await?;
+    let resp_data = check_is_success(resp).await?.json::<T>().await?;
 
     Ok(resp_data)
 }
+
+/// Out amount and gas of a swap without building its calldata
+pub async fn get_quote(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+    chain_id: u64,
+    query: QuoteQueryParams,
+) -> Result<QuoteResponse, OneInchError> {
+    let query_params = serde_qs::to_string(&query)?;
+    let api_url = format!("{base_url}/swap/v6.0/{chain_id}/quote?{query_params}");
+    get(client, api_url, api_key).await
+}
+
+pub async fn get_swap_data(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+    chain_id: u64,
+    query: SwapQueryParams,
+) -> Result<SwapResponse, OneInchError> {
+    let query_params = serde_qs::to_string(&query)?;
+    let api_url = format!("{base_url}/swap/v6.0/{chain_id}/swap?{query_params}");
+    get(client, api_url, api_key).await
+}
@@ -4,9 +4,13 @@ use std::{
 };
 
 use crate::{
+    agg::AggApiConfig,
     chain::Chain,
     commands::project::CreateProjectReq,
     error::AppError,
+    jito::JITO_BLOCK_ENGINE_URLS,
+    task::TaskSchedule,
+    tls::TlsConfig,
     wallet::{PrivateKey, WalletGrp},
 };
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
@@ -29,17 +33,163 @@ pub struct ProjectV1 {
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
-pub struct Project {
+pub struct ProjectV2 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub jito_url: Option<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV3 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub jito_url: Option<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV4 {
     pub id: String,
     pub name: String,
     pub chain: Chain,
     pub main_wallet: PrivateKey,
     pub rpc: String,
+    pub rpc_ws_url: Option<String>,
     pub jito_url: Option<String>,
     pub agg_api_url: String,
     pub agg_api_key: Option<String>,
     pub proxy_urls: Vec<String>,
     pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV5 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub rpc_ws_url: Option<String>,
+    /// extra rpc endpoints trade transactions are also sent to
+    pub send_rpc_urls: Vec<String>,
+    pub jito_url: Option<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV6 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub rpc_ws_url: Option<String>,
+    /// extra rpc endpoints trade transactions are also sent to
+    pub send_rpc_urls: Vec<String>,
+    pub jito_url: Option<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+    /// blocks on top of a receipt's block before an evm transaction is final,
+    /// chain default when not set
+    pub evm_confirmations: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV7 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub rpc_ws_url: Option<String>,
+    /// extra rpc endpoints trade transactions are also sent to
+    pub send_rpc_urls: Vec<String>,
+    pub jito_url: Option<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    /// api url and key per aggregator, `agg_api_url` and `agg_api_key`
+    /// are used for the chain default aggregator when it is not listed
+    pub agg_apis: Vec<AggApiConfig>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+    /// blocks on top of a receipt's block before an evm transaction is final,
+    /// chain default when not set
+    pub evm_confirmations: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ProjectV8 {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub rpc_ws_url: Option<String>,
+    /// extra rpc endpoints trade transactions are also sent to
+    pub send_rpc_urls: Vec<String>,
+    /// jito block engine regions, bundles go to the fastest reachable one
+    pub jito_urls: Vec<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    /// api url and key per aggregator, `agg_api_url` and `agg_api_key`
+    /// are used for the chain default aggregator when it is not listed
+    pub agg_apis: Vec<AggApiConfig>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+    /// blocks on top of a receipt's block before an evm transaction is final,
+    /// chain default when not set
+    pub evm_confirmations: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub chain: Chain,
+    pub main_wallet: PrivateKey,
+    pub rpc: String,
+    pub rpc_ws_url: Option<String>,
+    /// extra rpc endpoints trade transactions are also sent to
+    pub send_rpc_urls: Vec<String>,
+    /// jito block engine regions, bundles go to the fastest reachable one
+    pub jito_urls: Vec<String>,
+    pub agg_api_url: String,
+    pub agg_api_key: Option<String>,
+    /// api url and key per aggregator, `agg_api_url` and `agg_api_key`
+    /// are used for the chain default aggregator when it is not listed
+    pub agg_apis: Vec<AggApiConfig>,
+    pub proxy_urls: Vec<String>,
+    pub wallet_grps: Vec<WalletGrp>,
+    pub tls: TlsConfig,
+    /// blocks on top of a receipt's block before an evm transaction is final,
+    /// chain default when not set
+    pub evm_confirmations: Option<u64>,
+    /// start and stop times of the tasks
+    pub task_schedules: Vec<TaskSchedule>,
 }
 
 impl From<CreateProjectReq> for Project {
@@ -54,10 +204,15 @@ impl From<CreateProjectReq> for Project {
             name: value.name,
             chain: value.chain,
             rpc: value.rpc,
-            jito_url: value.jito_url,
+            rpc_ws_url: value.rpc_ws_url,
+            send_rpc_urls: value.send_rpc_urls,
+            jito_urls: value.jito_urls,
             agg_api_url: value.agg_api_url,
             agg_api_key: value.agg_api_key,
+            agg_apis: value.agg_apis,
             proxy_urls: value.proxy_urls,
+            tls: value.tls,
+            evm_confirmations: value.evm_confirmations,
             main_wallet,
             ..Default::default()
         }
@@ -66,9 +221,9 @@ impl From<CreateProjectReq> for Project {
 
 impl From<ProjectV1> for Project {
     fn from(value: ProjectV1) -> Self {
-        let jito_url = match value.chain {
-            Chain::Solana => Some("https://tokyo.mainnet.block-engine.jito.wtf".to_string()),
-            _ => None,
+        let jito_urls = match value.chain {
+            Chain::Solana => JITO_BLOCK_ENGINE_URLS.map(String::from).to_vec(),
+            _ => vec![],
         };
 
         Self {
@@ -76,12 +231,159 @@ impl From<ProjectV1> for Project {
             name: value.name,
             chain: value.chain,
             rpc: value.rpc,
-            jito_url,
+            jito_urls,
             agg_api_url: value.agg_api_url,
             agg_api_key: value.agg_api_key,
             proxy_urls: value.proxy_urls,
             main_wallet: value.main_wallet,
             wallet_grps: value.wallet_grps,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV2> for Project {
+    fn from(value: ProjectV2) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            rpc: value.rpc,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            proxy_urls: value.proxy_urls,
+            main_wallet: value.main_wallet,
+            wallet_grps: value.wallet_grps,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV3> for Project {
+    fn from(value: ProjectV3) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            rpc: value.rpc,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            proxy_urls: value.proxy_urls,
+            main_wallet: value.main_wallet,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV4> for Project {
+    fn from(value: ProjectV4) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            main_wallet: value.main_wallet,
+            rpc: value.rpc,
+            rpc_ws_url: value.rpc_ws_url,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            proxy_urls: value.proxy_urls,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV5> for Project {
+    fn from(value: ProjectV5) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            main_wallet: value.main_wallet,
+            rpc: value.rpc,
+            rpc_ws_url: value.rpc_ws_url,
+            send_rpc_urls: value.send_rpc_urls,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            proxy_urls: value.proxy_urls,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV6> for Project {
+    fn from(value: ProjectV6) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            main_wallet: value.main_wallet,
+            rpc: value.rpc,
+            rpc_ws_url: value.rpc_ws_url,
+            send_rpc_urls: value.send_rpc_urls,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            proxy_urls: value.proxy_urls,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            evm_confirmations: value.evm_confirmations,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV7> for Project {
+    fn from(value: ProjectV7) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            main_wallet: value.main_wallet,
+            rpc: value.rpc,
+            rpc_ws_url: value.rpc_ws_url,
+            send_rpc_urls: value.send_rpc_urls,
+            jito_urls: value.jito_url.into_iter().collect(),
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            agg_apis: value.agg_apis,
+            proxy_urls: value.proxy_urls,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            evm_confirmations: value.evm_confirmations,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ProjectV8> for Project {
+    fn from(value: ProjectV8) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            chain: value.chain,
+            main_wallet: value.main_wallet,
+            rpc: value.rpc,
+            rpc_ws_url: value.rpc_ws_url,
+            send_rpc_urls: value.send_rpc_urls,
+            jito_urls: value.jito_urls,
+            agg_api_url: value.agg_api_url,
+            agg_api_key: value.agg_api_key,
+            agg_apis: value.agg_apis,
+            proxy_urls: value.proxy_urls,
+            wallet_grps: value.wallet_grps,
+            tls: value.tls,
+            evm_confirmations: value.evm_confirmations,
+            ..Default::default()
         }
     }
 }
@@ -95,7 +397,21 @@ impl Project {
         // version 1
         // let contents = [vec![b'm', b't', 0u8, 1u8], contents].concat();
         // version 2
-        let contents = [vec![b'm', b't', 0u8, 2u8], contents].concat();
+        // let contents = [vec![b'm', b't', 0u8, 2u8], contents].concat();
+        // version 3
+        // let contents = [vec![b'm', b't', 0u8, 3u8], contents].concat();
+        // version 4
+        // let contents = [vec![b'm', b't', 0u8, 4u8], contents].concat();
+        // version 5
+        // let contents = [vec![b'm', b't', 0u8, 5u8], contents].concat();
+        // version 6
+        // let contents = [vec![b'm', b't', 0u8, 6u8], contents].concat();
+        // version 7
+        // let contents = [vec![b'm', b't', 0u8, 7u8], contents].concat();
+        // version 8
+        // let contents = [vec![b'm', b't', 0u8, 8u8], contents].concat();
+        // version 9
+        let contents = [vec![b'm', b't', 0u8, 9u8], contents].concat();
         tokio::fs::write(path.into(), contents).await?;
         Ok(())
     }
@@ -115,14 +431,50 @@ impl Project {
 
         let version = match &file_contents[..4] {
             [b'm', b't', 0u8, 1u8] => 1,
-            _ => 2,
+            [b'm', b't', 0u8, 2u8] => 2,
+            [b'm', b't', 0u8, 3u8] => 3,
+            [b'm', b't', 0u8, 4u8] => 4,
+            [b'm', b't', 0u8, 5u8] => 5,
+            [b'm', b't', 0u8, 6u8] => 6,
+            [b'm', b't', 0u8, 7u8] => 7,
+            [b'm', b't', 0u8, 8u8] => 8,
+            _ => 9,
         };
 
-        let proj = if version == 1 {
-            let proj_v1 = bincode::deserialize::<ProjectV1>(&decoded_contents)?;
-            Project::from(proj_v1)
-        } else {
-            bincode::deserialize::<Project>(&decoded_contents)?
+        let proj = match version {
+            1 => {
+                let proj_v1 = bincode::deserialize::<ProjectV1>(&decoded_contents)?;
+                Project::from(proj_v1)
+            }
+            2 => {
+                let proj_v2 = bincode::deserialize::<ProjectV2>(&decoded_contents)?;
+                Project::from(proj_v2)
+            }
+            3 => {
+                let proj_v3 = bincode::deserialize::<ProjectV3>(&decoded_contents)?;
+                Project::from(proj_v3)
+            }
+            4 => {
+                let proj_v4 = bincode::deserialize::<ProjectV4>(&decoded_contents)?;
+                Project::from(proj_v4)
+            }
+            5 => {
+                let proj_v5 = bincode::deserialize::<ProjectV5>(&decoded_contents)?;
+                Project::from(proj_v5)
+            }
+            6 => {
+                let proj_v6 = bincode::deserialize::<ProjectV6>(&decoded_contents)?;
+                Project::from(proj_v6)
+            }
+            7 => {
+                let proj_v7 = bincode::deserialize::<ProjectV7>(&decoded_contents)?;
+                Project::from(proj_v7)
+            }
+            8 => {
+                let proj_v8 = bincode::deserialize::<ProjectV8>(&decoded_contents)?;
+                Project::from(proj_v8)
+            }
+            _ => bincode::deserialize::<Project>(&decoded_contents)?,
         };
 
         Ok(proj)
@@ -0,0 +1,296 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{consts::RAYDIUM_AMM_V4_PROGRAM_ID, error::AppError};
+
+use super::{constant_product_out, read_pubkey, read_u64};
+
+/// size of an `AmmInfo` account
+pub const AMM_INFO_LEN: u64 = 752;
+pub const COIN_MINT_OFFSET: usize = 400;
+pub const PC_MINT_OFFSET: usize = 432;
+
+const SWAP_BASE_IN_TAG: u8 = 9;
+const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// `AmmStatus` values swaps are allowed in: initialized, swap only and waiting trade
+const SWAP_STATUSES: [u64; 3] = [1, 6, 7];
+
+/// Fields of an `AmmInfo` account needed to quote and swap
+#[derive(Debug, Clone)]
+pub struct AmmInfo {
+    pub status: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    /// pnl still held in the vaults, not part of the reserves
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub pool_open_time: u64,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+}
+
+impl AmmInfo {
+    pub fn unpack(data: &[u8]) -> Result<Self, AppError> {
+        if data.len() != AMM_INFO_LEN as usize {
+            return Err(AppError::new(format!(
+                "amm info size {} is not {AMM_INFO_LEN}",
+                data.len()
+            )));
+        }
+
+        Ok(Self {
+            status: read_u64(data, 0)?,
+            swap_fee_numerator: read_u64(data, 176)?,
+            swap_fee_denominator: read_u64(data, 184)?,
+            need_take_pnl_coin: read_u64(data, 192)?,
+            need_take_pnl_pc: read_u64(data, 200)?,
+            pool_open_time: read_u64(data, 224)?,
+            coin_vault: read_pubkey(data, 336)?,
+            pc_vault: read_pubkey(data, 368)?,
+            coin_mint: read_pubkey(data, COIN_MINT_OFFSET)?,
+            pc_mint: read_pubkey(data, PC_MINT_OFFSET)?,
+            open_orders: read_pubkey(data, 496)?,
+            market: read_pubkey(data, 528)?,
+            market_program: read_pubkey(data, 560)?,
+            target_orders: read_pubkey(data, 592)?,
+        })
+    }
+
+    pub fn is_swappable(&self, now: u64) -> bool {
+        SWAP_STATUSES.contains(&self.status) && self.pool_open_time <= now
+    }
+
+    /// `coin_to_pc` is the swap direction, vault amounts are read from the vault accounts
+    pub fn quote(
+        &self,
+        amount_in: u64,
+        coin_to_pc: bool,
+        coin_vault_amount: u64,
+        pc_vault_amount: u64,
+    ) -> u64 {
+        let coin_reserve = coin_vault_amount.saturating_sub(self.need_take_pnl_coin);
+        let pc_reserve = pc_vault_amount.saturating_sub(self.need_take_pnl_pc);
+        let (reserve_in, reserve_out) = if coin_to_pc {
+            (coin_reserve, pc_reserve)
+        } else {
+            (pc_reserve, coin_reserve)
+        };
+
+        constant_product_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            self.swap_fee_numerator,
+            self.swap_fee_denominator,
+        )
+    }
+}
+
+/// Accounts of the openbook market an amm v4 pool is bound to
+#[derive(Debug, Clone)]
+pub struct MarketInfo {
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+impl MarketInfo {
+    /// `data` is the raw market account, 5 bytes of head padding included
+    pub fn unpack(data: &[u8], market: &Pubkey, market_program: &Pubkey) -> Result<Self, AppError> {
+        let vault_signer_nonce = read_u64(data, 45)?;
+        let vault_signer = Pubkey::create_program_address(
+            &[market.as_ref(), &vault_signer_nonce.to_le_bytes()],
+            market_program,
+        )
+        .map_err(|_| AppError::new(format!("invalid vault signer nonce of market {market}")))?;
+
+        Ok(Self {
+            coin_vault: read_pubkey(data, 117)?,
+            pc_vault: read_pubkey(data, 165)?,
+            event_queue: read_pubkey(data, 253)?,
+            bids: read_pubkey(data, 285)?,
+            asks: read_pubkey(data, 317)?,
+            vault_signer,
+        })
+    }
+}
+
+pub fn amm_authority() -> Pubkey {
+    Pubkey::find_program_address(&[AMM_AUTHORITY_SEED], &RAYDIUM_AMM_V4_PROGRAM_ID).0
+}
+
+/// `SwapBaseIn` instruction, source and destination are token accounts of `user_owner`
+#[allow(clippy::too_many_arguments)]
+pub fn swap_base_in_ix(
+    amm_id: &Pubkey,
+    amm: &AmmInfo,
+    market: &MarketInfo,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    user_owner: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(17);
+    data.push(SWAP_BASE_IN_TAG);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    let accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(*amm_id, false),
+        AccountMeta::new_readonly(amm_authority(), false),
+        AccountMeta::new(amm.open_orders, false),
+        AccountMeta::new(amm.target_orders, false),
+        AccountMeta::new(amm.coin_vault, false),
+        AccountMeta::new(amm.pc_vault, false),
+        AccountMeta::new_readonly(amm.market_program, false),
+        AccountMeta::new(amm.market, false),
+        AccountMeta::new(market.bids, false),
+        AccountMeta::new(market.asks, false),
+        AccountMeta::new(market.event_queue, false),
+        AccountMeta::new(market.coin_vault, false),
+        AccountMeta::new(market.pc_vault, false),
+        AccountMeta::new_readonly(market.vault_signer, false),
+        AccountMeta::new(*user_source, false),
+        AccountMeta::new(*user_destination, false),
+        AccountMeta::new_readonly(*user_owner, true),
+    ];
+
+    Instruction {
+        program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::WSOL_MINT;
+
+    use super::*;
+
+    struct AmmInfoFixture {
+        data: Vec<u8>,
+        coin_vault: Pubkey,
+        pc_vault: Pubkey,
+        pc_mint: Pubkey,
+        open_orders: Pubkey,
+        market: Pubkey,
+        market_program: Pubkey,
+        target_orders: Pubkey,
+    }
+
+    /// `AmmInfo` account of a SOL-USDC pool with a 0.25% fee, laid out at the offsets
+    /// of the amm v4 program
+    fn amm_info_fixture() -> AmmInfoFixture {
+        let mut fixture = AmmInfoFixture {
+            data: vec![],
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+            pc_mint: Pubkey::new_unique(),
+            open_orders: Pubkey::new_unique(),
+            market: Pubkey::new_unique(),
+            market_program: Pubkey::new_unique(),
+            target_orders: Pubkey::new_unique(),
+        };
+        let coin_mint = WSOL_MINT;
+        let fields: [(usize, &[u8]); 14] = [
+            (0, &6u64.to_le_bytes()),
+            (176, &25u64.to_le_bytes()),
+            (184, &10_000u64.to_le_bytes()),
+            (192, &100_000_000_000u64.to_le_bytes()),
+            (200, &15_000_000_000u64.to_le_bytes()),
+            (224, &1_700_000_000u64.to_le_bytes()),
+            (336, fixture.coin_vault.as_ref()),
+            (368, fixture.pc_vault.as_ref()),
+            (COIN_MINT_OFFSET, coin_mint.as_ref()),
+            (PC_MINT_OFFSET, fixture.pc_mint.as_ref()),
+            (496, fixture.open_orders.as_ref()),
+            (528, fixture.market.as_ref()),
+            (560, fixture.market_program.as_ref()),
+            (592, fixture.target_orders.as_ref()),
+        ];
+        let mut data = vec![0u8; AMM_INFO_LEN as usize];
+        for (offset, bytes) in fields {
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        fixture.data = data;
+
+        fixture
+    }
+
+    #[test]
+    fn unpack_amm_info() {
+        let fixture = amm_info_fixture();
+
+        let amm = AmmInfo::unpack(&fixture.data).unwrap();
+
+        assert_eq!(amm.status, 6);
+        assert_eq!(amm.swap_fee_numerator, 25);
+        assert_eq!(amm.swap_fee_denominator, 10_000);
+        assert_eq!(amm.need_take_pnl_coin, 100_000_000_000);
+        assert_eq!(amm.need_take_pnl_pc, 15_000_000_000);
+        assert_eq!(amm.pool_open_time, 1_700_000_000);
+        assert_eq!(amm.coin_vault, fixture.coin_vault);
+        assert_eq!(amm.pc_vault, fixture.pc_vault);
+        assert_eq!(amm.coin_mint, WSOL_MINT);
+        assert_eq!(amm.pc_mint, fixture.pc_mint);
+        assert_eq!(amm.open_orders, fixture.open_orders);
+        assert_eq!(amm.market, fixture.market);
+        assert_eq!(amm.market_program, fixture.market_program);
+        assert_eq!(amm.target_orders, fixture.target_orders);
+        assert!(amm.is_swappable(1_700_000_000));
+        assert!(!amm.is_swappable(1_699_999_999));
+    }
+
+    #[test]
+    fn unpack_amm_info_of_wrong_size_is_error() {
+        let fixture = amm_info_fixture();
+
+        assert!(AmmInfo::unpack(&fixture.data[..AMM_INFO_LEN as usize - 1]).is_err());
+        assert!(AmmInfo::unpack(&[fixture.data, vec![0]].concat()).is_err());
+    }
+
+    #[test]
+    fn quote_excludes_pnl_from_reserves() {
+        let amm = AmmInfo::unpack(&amm_info_fixture().data).unwrap();
+        // 1000 SOL and 150k USDC in the vaults, 100 SOL and 15k USDC of them pnl
+        let coin_vault_amount = 1_000_000_000_000;
+        let pc_vault_amount = 150_000_000_000;
+
+        let out = amm.quote(1_000_000_000, true, coin_vault_amount, pc_vault_amount);
+
+        assert_eq!(out, 149_459_349);
+    }
+
+    #[test]
+    fn quote_both_directions() {
+        let amm = AmmInfo {
+            need_take_pnl_coin: 0,
+            need_take_pnl_pc: 0,
+            ..AmmInfo::unpack(&amm_info_fixture().data).unwrap()
+        };
+
+        assert_eq!(
+            amm.quote(1_000_000_000, true, 1_000_000_000_000, 150_000_000_000),
+            149_475_897
+        );
+        assert_eq!(
+            amm.quote(150_000_000, false, 1_000_000_000_000, 150_000_000_000),
+            996_505_985
+        );
+    }
+}
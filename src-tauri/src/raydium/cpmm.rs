@@ -0,0 +1,303 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{consts::RAYDIUM_CPMM_PROGRAM_ID, error::AppError};
+
+use super::{constant_product_out, read_pubkey, read_u64, read_u8};
+
+/// size of a `PoolState` account, anchor discriminator included
+pub const POOL_STATE_LEN: u64 = 637;
+pub const TOKEN_0_MINT_OFFSET: usize = 168;
+pub const TOKEN_1_MINT_OFFSET: usize = 200;
+
+/// trade fee rate of `AmmConfig` is in parts per million
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+const AMM_CONFIG_DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+const AUTH_SEED: &[u8] = b"vault_and_lp_mint_auth_seed";
+
+/// bit of `PoolState.status` set when swaps are disabled
+const SWAP_DISABLED_BIT: u8 = 1 << 2;
+
+/// Fields of a `PoolState` account needed to quote and swap
+#[derive(Debug, Clone)]
+pub struct PoolState {
+    pub amm_config: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub status: u8,
+    /// fees still held in the vaults, not part of the reserves
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub open_time: u64,
+}
+
+impl PoolState {
+    pub fn unpack(data: &[u8]) -> Result<Self, AppError> {
+        if data.len() != POOL_STATE_LEN as usize || data[..8] != POOL_STATE_DISCRIMINATOR {
+            return Err(AppError::new("account is not a cpmm pool state"));
+        }
+
+        Ok(Self {
+            amm_config: read_pubkey(data, 8)?,
+            token_0_vault: read_pubkey(data, 72)?,
+            token_1_vault: read_pubkey(data, 104)?,
+            token_0_mint: read_pubkey(data, TOKEN_0_MINT_OFFSET)?,
+            token_1_mint: read_pubkey(data, TOKEN_1_MINT_OFFSET)?,
+            token_0_program: read_pubkey(data, 232)?,
+            token_1_program: read_pubkey(data, 264)?,
+            observation_key: read_pubkey(data, 296)?,
+            status: read_u8(data, 329)?,
+            protocol_fees_token_0: read_u64(data, 341)?,
+            protocol_fees_token_1: read_u64(data, 349)?,
+            fund_fees_token_0: read_u64(data, 357)?,
+            fund_fees_token_1: read_u64(data, 365)?,
+            open_time: read_u64(data, 373)?,
+        })
+    }
+
+    pub fn is_swappable(&self, now: u64) -> bool {
+        self.status & SWAP_DISABLED_BIT == 0 && self.open_time <= now
+    }
+
+    /// `zero_to_one` is the swap direction, vault amounts are read from the vault accounts
+    pub fn quote(
+        &self,
+        amount_in: u64,
+        zero_to_one: bool,
+        trade_fee_rate: u64,
+        token_0_vault_amount: u64,
+        token_1_vault_amount: u64,
+    ) -> u64 {
+        let reserve_0 = token_0_vault_amount
+            .saturating_sub(self.protocol_fees_token_0)
+            .saturating_sub(self.fund_fees_token_0);
+        let reserve_1 = token_1_vault_amount
+            .saturating_sub(self.protocol_fees_token_1)
+            .saturating_sub(self.fund_fees_token_1);
+        let (reserve_in, reserve_out) = if zero_to_one {
+            (reserve_0, reserve_1)
+        } else {
+            (reserve_1, reserve_0)
+        };
+
+        constant_product_out(
+            amount_in,
+            reserve_in,
+            reserve_out,
+            trade_fee_rate,
+            FEE_RATE_DENOMINATOR,
+        )
+    }
+}
+
+/// `trade_fee_rate` of an `AmmConfig` account
+pub fn unpack_trade_fee_rate(data: &[u8]) -> Result<u64, AppError> {
+    if data.get(..8) != Some(&AMM_CONFIG_DISCRIMINATOR[..]) {
+        return Err(AppError::new("account is not a cpmm amm config"));
+    }
+    read_u64(data, 12)
+}
+
+pub fn authority() -> Pubkey {
+    Pubkey::find_program_address(&[AUTH_SEED], &RAYDIUM_CPMM_PROGRAM_ID).0
+}
+
+/// `swap_base_input` instruction, token accounts are owned by `payer`
+#[allow(clippy::too_many_arguments)]
+pub fn swap_base_input_ix(
+    pool_id: &Pubkey,
+    pool: &PoolState,
+    zero_to_one: bool,
+    payer: &Pubkey,
+    input_token_account: &Pubkey,
+    output_token_account: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&SWAP_BASE_INPUT_DISCRIMINATOR);
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    let (input_vault, output_vault, input_program, output_program, input_mint, output_mint) =
+        if zero_to_one {
+            (
+                pool.token_0_vault,
+                pool.token_1_vault,
+                pool.token_0_program,
+                pool.token_1_program,
+                pool.token_0_mint,
+                pool.token_1_mint,
+            )
+        } else {
+            (
+                pool.token_1_vault,
+                pool.token_0_vault,
+                pool.token_1_program,
+                pool.token_0_program,
+                pool.token_1_mint,
+                pool.token_0_mint,
+            )
+        };
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*payer, true),
+        AccountMeta::new_readonly(authority(), false),
+        AccountMeta::new_readonly(pool.amm_config, false),
+        AccountMeta::new(*pool_id, false),
+        AccountMeta::new(*input_token_account, false),
+        AccountMeta::new(*output_token_account, false),
+        AccountMeta::new(input_vault, false),
+        AccountMeta::new(output_vault, false),
+        AccountMeta::new_readonly(input_program, false),
+        AccountMeta::new_readonly(output_program, false),
+        AccountMeta::new_readonly(input_mint, false),
+        AccountMeta::new_readonly(output_mint, false),
+        AccountMeta::new(pool.observation_key, false),
+    ];
+
+    Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM_ID,
+        accounts,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consts::WSOL_MINT;
+
+    use super::*;
+
+    struct PoolStateFixture {
+        data: Vec<u8>,
+        amm_config: Pubkey,
+        token_0_vault: Pubkey,
+        token_1_vault: Pubkey,
+        token_1_mint: Pubkey,
+        observation_key: Pubkey,
+    }
+
+    /// `PoolState` account of a SOL-token pool, laid out at the offsets of the cpmm program
+    fn pool_state_fixture() -> PoolStateFixture {
+        let mut fixture = PoolStateFixture {
+            data: vec![],
+            amm_config: Pubkey::new_unique(),
+            token_0_vault: Pubkey::new_unique(),
+            token_1_vault: Pubkey::new_unique(),
+            token_1_mint: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+        };
+        let token_0_mint = WSOL_MINT;
+        let token_program = spl_token::id();
+        let fields: [(usize, &[u8]); 14] = [
+            (0, &POOL_STATE_DISCRIMINATOR),
+            (8, fixture.amm_config.as_ref()),
+            (72, fixture.token_0_vault.as_ref()),
+            (104, fixture.token_1_vault.as_ref()),
+            (TOKEN_0_MINT_OFFSET, token_0_mint.as_ref()),
+            (TOKEN_1_MINT_OFFSET, fixture.token_1_mint.as_ref()),
+            (232, token_program.as_ref()),
+            (264, token_program.as_ref()),
+            (296, fixture.observation_key.as_ref()),
+            (341, &1_000_000_000u64.to_le_bytes()),
+            (349, &4_000_000_000u64.to_le_bytes()),
+            (357, &500_000_000u64.to_le_bytes()),
+            (365, &0u64.to_le_bytes()),
+            (373, &1_720_000_000u64.to_le_bytes()),
+        ];
+        let mut data = vec![0u8; POOL_STATE_LEN as usize];
+        for (offset, bytes) in fields {
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+        fixture.data = data;
+
+        fixture
+    }
+
+    #[test]
+    fn unpack_pool_state() {
+        let fixture = pool_state_fixture();
+
+        let pool = PoolState::unpack(&fixture.data).unwrap();
+
+        assert_eq!(pool.amm_config, fixture.amm_config);
+        assert_eq!(pool.token_0_vault, fixture.token_0_vault);
+        assert_eq!(pool.token_1_vault, fixture.token_1_vault);
+        assert_eq!(pool.token_0_mint, WSOL_MINT);
+        assert_eq!(pool.token_1_mint, fixture.token_1_mint);
+        assert_eq!(pool.token_0_program, spl_token::id());
+        assert_eq!(pool.token_1_program, spl_token::id());
+        assert_eq!(pool.observation_key, fixture.observation_key);
+        assert_eq!(pool.status, 0);
+        assert_eq!(pool.protocol_fees_token_0, 1_000_000_000);
+        assert_eq!(pool.protocol_fees_token_1, 4_000_000_000);
+        assert_eq!(pool.fund_fees_token_0, 500_000_000);
+        assert_eq!(pool.fund_fees_token_1, 0);
+        assert_eq!(pool.open_time, 1_720_000_000);
+    }
+
+    #[test]
+    fn unpack_other_account_is_error() {
+        let mut data = pool_state_fixture().data;
+        data[..8].copy_from_slice(&AMM_CONFIG_DISCRIMINATOR);
+        assert!(PoolState::unpack(&data).is_err());
+
+        let data = pool_state_fixture().data;
+        assert!(PoolState::unpack(&data[..POOL_STATE_LEN as usize - 1]).is_err());
+    }
+
+    #[test]
+    fn swaps_disabled_by_status_bit() {
+        let mut data = pool_state_fixture().data;
+        let pool = PoolState::unpack(&data).unwrap();
+        assert!(pool.is_swappable(1_720_000_000));
+        assert!(!pool.is_swappable(1_719_999_999));
+
+        data[329] = SWAP_DISABLED_BIT;
+        let pool = PoolState::unpack(&data).unwrap();
+        assert!(!pool.is_swappable(1_720_000_000));
+    }
+
+    #[test]
+    fn quote_excludes_fees_from_reserves() {
+        let pool = PoolState::unpack(&pool_state_fixture().data).unwrap();
+        // 500 SOL and 2M tokens in the vaults, 0.25% trade fee
+        let token_0_vault_amount = 500_000_000_000;
+        let token_1_vault_amount = 2_000_000_000_000_000;
+
+        let out = pool.quote(
+            1_000_000_000,
+            true,
+            2_500,
+            token_0_vault_amount,
+            token_1_vault_amount,
+        );
+
+        assert_eq!(out, 3_994_005_996_026);
+    }
+
+    #[test]
+    fn unpack_amm_config_trade_fee_rate() {
+        let mut data = vec![0u8; 236];
+        data[..8].copy_from_slice(&AMM_CONFIG_DISCRIMINATOR);
+        data[12..20].copy_from_slice(&2_500u64.to_le_bytes());
+
+        assert_eq!(unpack_trade_fee_rate(&data).unwrap(), 2_500);
+
+        data[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+        assert!(unpack_trade_fee_rate(&data).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Account as TokenAccount;
+
+use crate::error::AppError;
+
+pub mod amm_v4;
+pub mod cpmm;
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, AppError> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(|| AppError::new(format!("account data too short for offset {offset}")))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, AppError> {
+    let bytes = data
+        .get(offset..offset + 8)
+        .ok_or_else(|| AppError::new(format!("account data too short for offset {offset}")))?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, AppError> {
+    let bytes = data
+        .get(offset..offset + 32)
+        .ok_or_else(|| AppError::new(format!("account data too short for offset {offset}")))?;
+    Ok(Pubkey::new_from_array(bytes.try_into().unwrap()))
+}
+
+/// Amount of a token account, token-2022 accounts with extensions included
+pub fn token_account_amount(data: &[u8]) -> Result<u64, AppError> {
+    let data = data
+        .get(..TokenAccount::LEN)
+        .ok_or(AppError::new("token account data too short"))?;
+    Ok(TokenAccount::unpack_from_slice(data)?.amount)
+}
+
+/// Out amount of a constant product swap, the fee is taken from the input
+pub fn constant_product_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> u64 {
+    if fee_denominator == 0 || reserve_in == 0 || reserve_out == 0 {
+        return 0;
+    }
+
+    let amount_in = amount_in as u128;
+    let fee = (amount_in * fee_numerator as u128).div_ceil(fee_denominator as u128);
+    let amount_in = amount_in.saturating_sub(fee);
+    let out = reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in);
+    out as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constant_product_out_takes_fee_from_input() {
+        // 0.3% fee, 997 of 1000 swapped against equal reserves
+        assert_eq!(constant_product_out(1_000, 10_000, 10_000, 3, 1_000), 906);
+        // 1 SOL into a 1000 SOL / 150k USDC pool at 0.25%
+        assert_eq!(
+            constant_product_out(
+                1_000_000_000,
+                1_000_000_000_000,
+                150_000_000_000,
+                25,
+                10_000
+            ),
+            149_475_897
+        );
+    }
+
+    #[test]
+    fn constant_product_out_rounds_fee_up() {
+        // fee of 1 rounds up to 1, nothing left to swap
+        assert_eq!(constant_product_out(1, 10_000, 10_000, 3, 1_000), 0);
+    }
+
+    #[test]
+    fn constant_product_out_of_empty_pool_is_zero() {
+        assert_eq!(constant_product_out(1_000, 0, 10_000, 3, 1_000), 0);
+        assert_eq!(constant_product_out(1_000, 10_000, 0, 3, 1_000), 0);
+        assert_eq!(constant_product_out(1_000, 10_000, 10_000, 3, 0), 0);
+    }
+
+    #[test]
+    fn constant_product_out_does_not_overflow() {
+        let out = constant_product_out(u64::MAX, u64::MAX, u64::MAX, 0, 1);
+        assert_eq!(out, u64::MAX / 2);
+    }
+
+    #[test]
+    fn read_past_end_is_error() {
+        let data = [0u8; 40];
+
+        assert!(read_u64(&data, 32).is_ok());
+        assert!(read_u64(&data, 33).is_err());
+        assert!(read_pubkey(&data, 8).is_ok());
+        assert!(read_pubkey(&data, 9).is_err());
+        assert!(read_u8(&data, 40).is_err());
+    }
+}
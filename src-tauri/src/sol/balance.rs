@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
+
+use crate::{consts::WSOL_MINT, error::AppError};
+
+/// a landed transaction may not be served by `getTransaction` right away
+const FETCH_TX_RETRIES: u32 = 5;
+const FETCH_TX_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Balance changes of a wallet made by a landed transaction, from its `getTransaction` meta
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolBalanceChanges {
+    /// lamports of the wallet, the fee and tips included
+    pub lamports: i128,
+    /// wrapped SOL in the token accounts the wallet owns
+    pub wsol: i128,
+    /// the token in the token accounts the wallet owns
+    pub token: i128,
+    /// base and priority fee in lamports
+    pub fee: u64,
+}
+
+impl SolBalanceChanges {
+    pub async fn fetch(
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        wallet: &Pubkey,
+        token_mint: &Pubkey,
+    ) -> Result<Self, AppError> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let mut retries = 0;
+        let tx = loop {
+            match rpc_client
+                .get_transaction_with_config(signature, config)
+                .await
+            {
+                Ok(tx) => break tx,
+                Err(_) if retries < FETCH_TX_RETRIES => {
+                    retries += 1;
+                    tokio::time::sleep(FETCH_TX_RETRY_DELAY).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
+        let meta = tx
+            .transaction
+            .meta
+            .ok_or_else(|| AppError::new(format!("transaction {signature} has no meta")))?;
+
+        // NOTE: the fee payer is the first account, which is the wallet
+        let lamports = match (meta.pre_balances.first(), meta.post_balances.first()) {
+            (Some(pre), Some(post)) => *post as i128 - *pre as i128,
+            _ => 0,
+        };
+        let pre_token_balances: Option<Vec<_>> = meta.pre_token_balances.into();
+        let post_token_balances: Option<Vec<_>> = meta.post_token_balances.into();
+        let pre_token_balances = pre_token_balances.unwrap_or_default();
+        let post_token_balances = post_token_balances.unwrap_or_default();
+        let token_change = |mint: &Pubkey| {
+            token_amount(&post_token_balances, wallet, mint)
+                - token_amount(&pre_token_balances, wallet, mint)
+        };
+
+        Ok(Self {
+            lamports,
+            wsol: token_change(&WSOL_MINT),
+            token: token_change(token_mint),
+            fee: meta.fee,
+        })
+    }
+}
+
+/// Sum of `mint` in the token accounts `owner` owns
+fn token_amount(balances: &[UiTransactionTokenBalance], owner: &Pubkey, mint: &Pubkey) -> i128 {
+    let owner = owner.to_string();
+    let mint = mint.to_string();
+    balances
+        .iter()
+        .filter(|balance| balance.mint == mint)
+        .filter(|balance| Option::<&String>::from(balance.owner.as_ref()) == Some(&owner))
+        .filter_map(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+        .sum()
+}
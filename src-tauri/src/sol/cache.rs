@@ -0,0 +1,131 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+
+use log::{debug, warn};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey};
+use tokio::sync::RwLock;
+
+use crate::{chain::SolRpcClientExt, error::AppError};
+
+const BLOCKHASH_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// cached blockhash older than this is fetched again instead of used
+const BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(10);
+const LOOKUP_TABLE_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy)]
+struct CachedBlockhash {
+    blockhash: Hash,
+    last_valid_block_height: u64,
+    fetched_at: Instant,
+}
+
+/// Project scoped cache of chain data every solana transaction needs,
+/// so workers don't multiply the rpc load
+pub struct SolChainCache {
+    rpc_client: Arc<RpcClient>,
+    blockhash: RwLock<Option<CachedBlockhash>>,
+    lookup_tables: RwLock<HashMap<Pubkey, (AddressLookupTableAccount, Instant)>>,
+}
+
+impl SolChainCache {
+    /// Create the cache and keep the blockhash fresh in background
+    /// until the returned cache is dropped
+    pub fn start(rpc_client: Arc<RpcClient>) -> Arc<Self> {
+        let cache = Arc::new(Self {
+            rpc_client,
+            blockhash: RwLock::new(None),
+            lookup_tables: RwLock::new(HashMap::new()),
+        });
+
+        let weak_cache = Arc::downgrade(&cache);
+        tauri::async_runtime::spawn(Self::refresh_blockhash_loop(weak_cache));
+
+        cache
+    }
+
+    async fn refresh_blockhash_loop(cache: Weak<Self>) {
+        loop {
+            let Some(cache) = cache.upgrade() else {
+                debug!("sol chain cache dropped, stop refreshing blockhash");
+                break;
+            };
+            if let Err(err) = cache.refresh_blockhash().await {
+                warn!("refresh blockhash error: {}", err.err_msg);
+            }
+            drop(cache);
+
+            tokio::time::sleep(BLOCKHASH_REFRESH_INTERVAL).await;
+        }
+    }
+
+    async fn refresh_blockhash(&self) -> Result<CachedBlockhash, AppError> {
+        let (blockhash, last_valid_block_height) = self
+            .rpc_client
+            .get_latest_blockhash_with_commitment(self.rpc_client.commitment())
+            .await?;
+        let cached = CachedBlockhash {
+            blockhash,
+            last_valid_block_height,
+            fetched_at: Instant::now(),
+        };
+
+        let mut guard = self.blockhash.write().await;
+        *guard = Some(cached);
+        drop(guard);
+
+        Ok(cached)
+    }
+
+    /// Latest blockhash and its last valid block height
+    pub async fn get_latest_blockhash(&self) -> Result<(Hash, u64), AppError> {
+        let guard = self.blockhash.read().await;
+        let cached = *guard;
+        drop(guard);
+
+        let cached = match cached {
+            Some(cached) if cached.fetched_at.elapsed() < BLOCKHASH_MAX_AGE => cached,
+            _ => self.refresh_blockhash().await?,
+        };
+
+        Ok((cached.blockhash, cached.last_valid_block_height))
+    }
+
+    pub async fn get_address_lookup_tables(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<Vec<AddressLookupTableAccount>, AppError> {
+        let mut result = vec![];
+        let mut missing = vec![];
+
+        let guard = self.lookup_tables.read().await;
+        for pubkey in pubkeys {
+            match guard.get(pubkey) {
+                Some((table, fetched_at)) if fetched_at.elapsed() < LOOKUP_TABLE_TTL => {
+                    result.push(table.clone());
+                }
+                _ => missing.push(*pubkey),
+            }
+        }
+        drop(guard);
+
+        if missing.is_empty() {
+            return Ok(result);
+        }
+
+        let fetched = self.rpc_client.get_address_lookup_tables(&missing).await?;
+        let now = Instant::now();
+        let mut guard = self.lookup_tables.write().await;
+        guard.retain(|_, (_, fetched_at)| fetched_at.elapsed() < LOOKUP_TABLE_TTL);
+        for table in &fetched {
+            guard.insert(table.key, (table.clone(), now));
+        }
+        drop(guard);
+
+        result.extend(fetched);
+        Ok(result)
+    }
+}
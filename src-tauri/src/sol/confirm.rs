@@ -0,0 +1,147 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use log::{debug, warn};
+use solana_client::{
+    nonblocking::{pubsub_client::PubsubClient, rpc_client::RpcClient},
+    rpc_config::RpcSignatureSubscribeConfig,
+    rpc_response::RpcSignatureResult,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, signature::Signature, transaction::TransactionResult,
+};
+
+use crate::error::AppError;
+
+// NOTE: max tx expire time 120s in solana_sdk
+pub const SOL_TX_CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+const SOL_TX_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Waits for solana transactions to be confirmed.
+///
+/// All workers of a project share one websocket connection, every in-flight
+/// transaction is a `signatureSubscribe` on it. Without a ws url, or when the
+/// subscription fails, it polls `getSignatureStatuses` instead.
+pub struct SolTxConfirmer {
+    rpc_client: Arc<RpcClient>,
+    pubsub_client: Option<Arc<PubsubClient>>,
+}
+
+impl SolTxConfirmer {
+    pub async fn connect(rpc_client: Arc<RpcClient>, ws_url: Option<&str>) -> Self {
+        let pubsub_client = match ws_url {
+            // NOTE: the project `TlsConfig` isn't applied, `PubsubClient` takes no tls connector
+            Some(ws_url) => match PubsubClient::new(ws_url).await {
+                Ok(client) => Some(Arc::new(client)),
+                Err(err) => {
+                    warn!("connect sol ws {ws_url} error: {err}, fallback to polling");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Self {
+            rpc_client,
+            pubsub_client,
+        }
+    }
+
+    /// Wait until the transaction is confirmed or `timeout` elapsed.
+    /// Returns `None` if the transaction was not found before the timeout.
+    pub async fn wait_for_status(
+        &self,
+        txid: &Signature,
+        timeout: Duration,
+    ) -> Result<Option<TransactionResult<()>>, AppError> {
+        let start_time = Instant::now();
+        if let Some(pubsub_client) = &self.pubsub_client {
+            match self.subscribe_status(pubsub_client, txid, timeout).await {
+                Ok(Some(status)) => return Ok(Some(status)),
+                Ok(None) => {}
+                Err(err) => warn!("subscribe transaction {txid} error: {}", err.err_msg),
+            }
+        }
+
+        // NOTE: the notification may be missed if the connection drops,
+        // poll for the rest of the time (at least once) to be sure
+        let remaining = timeout.saturating_sub(start_time.elapsed());
+        self.poll_status(txid, remaining).await
+    }
+
+    async fn subscribe_status(
+        &self,
+        pubsub_client: &PubsubClient,
+        txid: &Signature,
+        timeout: Duration,
+    ) -> Result<Option<TransactionResult<()>>, AppError> {
+        let config = RpcSignatureSubscribeConfig {
+            commitment: Some(self.rpc_client.commitment()),
+            enable_received_notification: Some(false),
+        };
+        let (mut notifications, unsubscribe) = pubsub_client
+            .signature_subscribe(txid, Some(config))
+            .await?;
+        debug!("subscribed transaction {txid}");
+
+        let notification = tokio::time::timeout(timeout, notifications.next()).await;
+        unsubscribe().await;
+
+        let result = match notification {
+            Ok(Some(resp)) => match resp.value {
+                RpcSignatureResult::ProcessedSignature(processed) => {
+                    Some(processed.err.map_or(Ok(()), Err))
+                }
+                RpcSignatureResult::ReceivedSignature(_) => None,
+            },
+            Ok(None) => {
+                warn!("sol ws subscription closed while confirming {txid}");
+                None
+            }
+            Err(_) => None,
+        };
+
+        Ok(result)
+    }
+
+    /// Status of a confirmed transaction, `None` if not found or not confirmed yet
+    pub async fn get_status(
+        &self,
+        txid: &Signature,
+    ) -> Result<Option<TransactionResult<()>>, AppError> {
+        debug!("confirming tranaction {} ...", txid);
+        let statuses = self
+            .rpc_client
+            .get_signature_statuses_with_history(&[*txid])
+            .await?
+            .value;
+
+        let status = statuses[0]
+            .clone()
+            .filter(|status| status.satisfies_commitment(CommitmentConfig::confirmed()))
+            .map(|status| status.status);
+
+        Ok(status)
+    }
+
+    async fn poll_status(
+        &self,
+        txid: &Signature,
+        timeout: Duration,
+    ) -> Result<Option<TransactionResult<()>>, AppError> {
+        let start_time = Instant::now();
+        loop {
+            if let Some(status) = self.get_status(txid).await? {
+                return Ok(Some(status));
+            }
+
+            if start_time.elapsed() >= timeout {
+                return Ok(None);
+            }
+            tokio::time::sleep(SOL_TX_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{debug, warn};
+use serde::Serialize;
+use solana_client::rpc_client::SerializableTransaction;
+use solana_sdk::{signature::Signature, transaction::VersionedTransaction};
+
+use crate::error::AppError;
+
+use super::{SolTxOutcome, SolTxSubmitter};
+
+pub struct SolEndpoint {
+    pub name: String,
+    pub submitter: Arc<dyn SolTxSubmitter + Send + Sync>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SolEndpointStats {
+    pub endpoint: String,
+    /// distinct transactions accepted by the endpoint
+    pub sent: u64,
+    /// submissions rejected by the endpoint, rebroadcasts included
+    pub rejected: u64,
+    /// accepted transactions which landed, success or failed, every endpoint which
+    /// accepted a transaction counts it, whose submission reached the leader is not known
+    pub accepted_landed: u64,
+    pub expired: u64,
+    /// transactions the endpoint accepted before any other endpoint
+    pub first_accepted: u64,
+    /// mean time the endpoint took to accept the first submission of a transaction
+    pub avg_accept_ms: Option<u64>,
+    #[serde(skip)]
+    accept_ms_total: u64,
+    #[serde(skip)]
+    accept_ms_cnt: u64,
+}
+
+impl SolEndpointStats {
+    fn record_accept_latency(&mut self, elapsed_ms: u64) {
+        self.accept_ms_total += elapsed_ms;
+        self.accept_ms_cnt += 1;
+        self.avg_accept_ms = Some(self.accept_ms_total / self.accept_ms_cnt);
+    }
+}
+
+/// Landing statistics of every endpoint transactions were sent to,
+/// kept for the opened project
+#[derive(Debug, Default)]
+pub struct SolEndpointStatsBook(Mutex<HashMap<String, SolEndpointStats>>);
+
+impl SolEndpointStatsBook {
+    fn update(&self, endpoint: &str, f: impl FnOnce(&mut SolEndpointStats)) {
+        let mut guard = self.0.lock().unwrap();
+        let stats = guard
+            .entry(endpoint.to_string())
+            .or_insert_with(|| SolEndpointStats {
+                endpoint: endpoint.to_string(),
+                ..Default::default()
+            });
+        f(stats);
+    }
+
+    pub fn snapshot(&self) -> Vec<SolEndpointStats> {
+        let guard = self.0.lock().unwrap();
+        let mut result: Vec<_> = guard.values().cloned().collect();
+        result.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        result
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+/// Submits the same signed transaction to all endpoints at once
+pub struct SolFanoutSubmitter {
+    endpoints: Vec<SolEndpoint>,
+    stats: Arc<SolEndpointStatsBook>,
+    /// endpoints which accepted each transaction, a signature is only counted once per endpoint
+    accepted: Mutex<HashMap<Signature, HashSet<String>>>,
+}
+
+impl SolFanoutSubmitter {
+    pub fn new(endpoints: Vec<SolEndpoint>, stats: Arc<SolEndpointStatsBook>) -> Self {
+        Self {
+            endpoints,
+            stats,
+            accepted: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl SolTxSubmitter for SolFanoutSubmitter {
+    async fn submit(
+        &self,
+        tx: &VersionedTransaction,
+        is_rebroadcast: bool,
+    ) -> Result<(), AppError> {
+        let txid = *tx.get_signature();
+        let results = join_all(self.endpoints.iter().map(|endpoint| async move {
+            let started_at = Instant::now();
+            let result = endpoint.submitter.submit(tx, is_rebroadcast).await;
+            (result, started_at.elapsed().as_millis() as u64)
+        }))
+        .await;
+
+        let mut last_err = None;
+        let mut accepted_cnt = 0;
+        let mut first_accepted: Option<(&str, u64)> = None;
+        for (endpoint, (result, elapsed_ms)) in self.endpoints.iter().zip(results) {
+            match result {
+                Ok(()) => {
+                    accepted_cnt += 1;
+                    let mut guard = self.accepted.lock().unwrap();
+                    let is_new = guard.entry(txid).or_default().insert(endpoint.name.clone());
+                    drop(guard);
+                    if is_new {
+                        self.stats.update(&endpoint.name, |stats| stats.sent += 1);
+                    }
+                    if !is_rebroadcast {
+                        self.stats.update(&endpoint.name, |stats| {
+                            stats.record_accept_latency(elapsed_ms)
+                        });
+                        if first_accepted.map_or(true, |(_, ms)| elapsed_ms < ms) {
+                            first_accepted = Some((&endpoint.name, elapsed_ms));
+                        }
+                    }
+                }
+                Err(err) => {
+                    debug!("endpoint {} reject {txid}: {}", endpoint.name, err.err_msg);
+                    self.stats
+                        .update(&endpoint.name, |stats| stats.rejected += 1);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        if let Some((endpoint, _)) = first_accepted {
+            self.stats
+                .update(endpoint, |stats| stats.first_accepted += 1);
+        }
+
+        if accepted_cnt == 0 {
+            warn!("transaction {txid} rejected by all endpoints");
+            return Err(
+                last_err.unwrap_or_else(|| AppError::new("no endpoint to send transaction"))
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Dropped only when every endpoint dropped it, rpc endpoints never do
+    async fn is_dropped(&self, txid: &Signature) -> bool {
+        let results = join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| endpoint.submitter.is_dropped(txid)),
+        )
+        .await;
+        !results.is_empty() && results.into_iter().all(|dropped| dropped)
+    }
+
+    fn record_outcome(&self, txid: &Signature, outcome: &SolTxOutcome) {
+        let accepted_by = self.accepted.lock().unwrap().remove(txid);
+        for endpoint in accepted_by.unwrap_or_default() {
+            self.stats.update(&endpoint, |stats| match outcome {
+                SolTxOutcome::Landed | SolTxOutcome::Failed(_) => stats.accepted_landed += 1,
+                SolTxOutcome::Expired | SolTxOutcome::Dropped => stats.expired += 1,
+            });
+        }
+    }
+}
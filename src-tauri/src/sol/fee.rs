@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::error::AppError;
+
+/// max accounts `getRecentPrioritizationFees` accepts
+const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
+/// How the compute unit price (micro lamports) of a trade is chosen
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum SolPriorityFeeStrategy {
+    /// always use the task `gas_price`
+    #[default]
+    Fixed,
+    /// percentile of `getRecentPrioritizationFees` over the writable accounts of the swap
+    Percentile { percentile: u8, max_price: u64 },
+    /// start from the task `gas_price`, raise it by `step_pct` after every dropped
+    /// transaction and go back after a transaction landed
+    Adaptive { step_pct: u32, max_price: u64 },
+}
+
+impl SolPriorityFeeStrategy {
+    /// `base_price` is the task `gas_price`, `dropped_cnt` the number of
+    /// transactions dropped in a row by the worker
+    pub async fn compute_unit_price(
+        &self,
+        rpc_client: &RpcClient,
+        ixs: &[Instruction],
+        base_price: u64,
+        dropped_cnt: u32,
+    ) -> Result<u64, AppError> {
+        let price = match *self {
+            Self::Fixed => base_price,
+            Self::Percentile {
+                percentile,
+                max_price,
+            } => {
+                let accounts = writable_accounts(ixs);
+                let fees = rpc_client.get_recent_prioritization_fees(&accounts).await?;
+                let fees: Vec<u64> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+                fee_percentile(fees, percentile).min(max_price)
+            }
+            Self::Adaptive {
+                step_pct,
+                max_price,
+            } => {
+                let mut price = base_price;
+                for _ in 0..dropped_cnt {
+                    price = price.saturating_mul(100 + step_pct as u64) / 100;
+                    if price >= max_price {
+                        break;
+                    }
+                }
+                price.min(max_price)
+            }
+        };
+
+        Ok(price)
+    }
+}
+
+fn writable_accounts(ixs: &[Instruction]) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = vec![];
+    for account in ixs.iter().flat_map(|ix| ix.accounts.iter()) {
+        if account.is_writable && !account.is_signer && !accounts.contains(&account.pubkey) {
+            accounts.push(account.pubkey);
+        }
+    }
+    accounts.truncate(MAX_PRIORITIZATION_FEE_ACCOUNTS);
+    accounts
+}
+
+fn fee_percentile(mut fees: Vec<u64>, percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    let percentile = percentile.min(100) as usize;
+    let idx = (fees.len() - 1) * percentile / 100;
+    fees[idx]
+}
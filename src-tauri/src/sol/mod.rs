@@ -0,0 +1,13 @@
+mod balance;
+mod cache;
+mod confirm;
+mod fanout;
+mod fee;
+mod sender;
+
+pub use balance::*;
+pub use cache::*;
+pub use confirm::*;
+pub use fanout::*;
+pub use fee::*;
+pub use sender::*;
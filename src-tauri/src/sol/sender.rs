@@ -0,0 +1,175 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use log::{debug, warn};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction,
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_sdk::{
+    signature::Signature,
+    transaction::{TransactionError, VersionedTransaction},
+};
+
+use crate::error::AppError;
+
+use super::{SolTxConfirmer, SOL_TX_CONFIRM_TIMEOUT};
+
+const SOL_TX_REBROADCAST_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Final state of a transaction sent by [`SolTxSender`]
+#[derive(Debug, Clone)]
+pub enum SolTxOutcome {
+    Landed,
+    Failed(TransactionError),
+    /// blockhash expired before the transaction landed, it can never land
+    Expired,
+    /// every endpoint it was submitted to gave up on it, like a failed jito bundle
+    Dropped,
+}
+
+/// Where a signed transaction is submitted to, rpc node or jito block engine
+#[async_trait]
+pub trait SolTxSubmitter {
+    /// `is_rebroadcast` is true when the same transaction was already submitted before
+    async fn submit(&self, tx: &VersionedTransaction, is_rebroadcast: bool)
+        -> Result<(), AppError>;
+
+    /// Whether the endpoint knows the submitted transaction will never land through it,
+    /// checked while waiting for the transaction
+    async fn is_dropped(&self, _txid: &Signature) -> bool {
+        false
+    }
+
+    /// Called once the final state of a submitted transaction is known
+    fn record_outcome(&self, _txid: &Signature, _outcome: &SolTxOutcome) {}
+}
+
+#[async_trait]
+impl SolTxSubmitter for RpcClient {
+    async fn submit(
+        &self,
+        tx: &VersionedTransaction,
+        is_rebroadcast: bool,
+    ) -> Result<(), AppError> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: is_rebroadcast,
+            preflight_commitment: Some(self.commitment().commitment),
+            max_retries: Some(0),
+            ..Default::default()
+        };
+        self.send_transaction_with_config(tx, config).await?;
+        Ok(())
+    }
+}
+
+/// Sends a signed transaction and rebroadcasts it until it is confirmed
+/// or the block height passed the blockhash's last valid block height
+pub struct SolTxSender {
+    rpc_client: Arc<RpcClient>,
+    confirmer: Arc<SolTxConfirmer>,
+}
+
+impl SolTxSender {
+    pub fn new(rpc_client: Arc<RpcClient>, confirmer: Arc<SolTxConfirmer>) -> Self {
+        Self {
+            rpc_client,
+            confirmer,
+        }
+    }
+
+    pub async fn send_and_confirm(
+        &self,
+        submitter: &(dyn SolTxSubmitter + Sync),
+        tx: &VersionedTransaction,
+        last_valid_block_height: u64,
+    ) -> Result<SolTxOutcome, AppError> {
+        let txid = tx.get_signature();
+        submitter.submit(tx, false).await?;
+
+        let outcome = self
+            .wait_for_outcome(submitter, tx, last_valid_block_height)
+            .await;
+        submitter.record_outcome(txid, &outcome);
+
+        Ok(outcome)
+    }
+
+    /// Rpc errors are logged and waited through, the transaction was sent and may still land
+    async fn wait_for_outcome(
+        &self,
+        submitter: &(dyn SolTxSubmitter + Sync),
+        tx: &VersionedTransaction,
+        last_valid_block_height: u64,
+    ) -> SolTxOutcome {
+        let txid = tx.get_signature();
+
+        let confirm = self.confirmer.wait_for_status(txid, SOL_TX_CONFIRM_TIMEOUT);
+        tokio::pin!(confirm);
+
+        let mut rebroadcast = tokio::time::interval(SOL_TX_REBROADCAST_INTERVAL);
+        // NOTE: first tick completes immediately, the transaction was just submitted
+        rebroadcast.tick().await;
+
+        loop {
+            tokio::select! {
+                status = &mut confirm => {
+                    match status {
+                        Ok(Some(Ok(()))) => return SolTxOutcome::Landed,
+                        Ok(Some(Err(err))) => return SolTxOutcome::Failed(err),
+                        // rpc block height may lag behind, keep checking until expired
+                        Ok(None) => break,
+                        Err(err) => {
+                            warn!("wait for transaction {txid} error: {}", err.err_msg);
+                            break;
+                        }
+                    }
+                }
+                _ = rebroadcast.tick() => {
+                    let block_height = match self.rpc_client.get_block_height().await {
+                        Ok(block_height) => block_height,
+                        Err(err) => {
+                            warn!("get block height error: {err}, keep waiting for {txid}");
+                            continue;
+                        }
+                    };
+                    if block_height > last_valid_block_height {
+                        break;
+                    }
+                    if submitter.is_dropped(txid).await {
+                        // NOTE: may have landed right before it was reported dropped
+                        match self.confirmer.get_status(txid).await {
+                            Ok(Some(Ok(()))) => return SolTxOutcome::Landed,
+                            Ok(Some(Err(err))) => return SolTxOutcome::Failed(err),
+                            Ok(None) => return SolTxOutcome::Dropped,
+                            Err(err) => {
+                                warn!("get transaction {txid} status error: {}", err.err_msg);
+                                continue;
+                            }
+                        }
+                    }
+                    debug!("rebroadcast transaction {txid}, block height {block_height}/{last_valid_block_height}");
+                    if let Err(err) = submitter.submit(tx, true).await {
+                        warn!("rebroadcast transaction {txid} error: {}", err.err_msg);
+                    }
+                }
+            }
+        }
+
+        loop {
+            match self.confirmer.get_status(txid).await {
+                Ok(Some(Ok(()))) => return SolTxOutcome::Landed,
+                Ok(Some(Err(err))) => return SolTxOutcome::Failed(err),
+                Ok(None) => match self.rpc_client.get_block_height().await {
+                    Ok(block_height) if block_height > last_valid_block_height => {
+                        return SolTxOutcome::Expired;
+                    }
+                    Ok(_) => {}
+                    Err(err) => warn!("get block height error: {err}, keep waiting for {txid}"),
+                },
+                Err(err) => warn!("get transaction {txid} status error: {}", err.err_msg),
+            }
+            tokio::time::sleep(SOL_TX_REBROADCAST_INTERVAL).await;
+        }
+    }
+}
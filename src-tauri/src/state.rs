@@ -8,7 +8,15 @@ use solana_client::nonblocking::rpc_client::RpcClient as SolRpcClient;
 use tauri::{AppHandle, Manager};
 use tokio::sync::{Mutex, RwLock};
 
-use crate::{error::AppError, project::Project, task::Task};
+use crate::{
+    error::AppError,
+    evm::{EvmNonceManager, EvmTxConfig, EvmTxSender},
+    jito::{JitoRegionBook, JitoTipCache},
+    ledger::TradeLedger,
+    project::Project,
+    sol::{SolChainCache, SolEndpointStatsBook, SolTxConfirmer, SolTxSender},
+    task::{Task, TaskScheduler},
+};
 
 #[derive(Debug)]
 pub struct ProjectStateValue {
@@ -31,15 +39,30 @@ pub type ProjectState = Mutex<Option<ProjectStateValue>>;
 pub type EvmRpcClientState = RwLock<Option<EvmRpcClient<Http<reqwest::Client>>>>;
 pub type EvmProviderState = RwLock<Option<EvmRpcProvider>>;
 pub type SolRpcClientState = RwLock<Option<Arc<SolRpcClient>>>;
+pub type SolTxConfirmerState = RwLock<Option<Arc<SolTxConfirmer>>>;
+pub type SolChainCacheState = RwLock<Option<Arc<SolChainCache>>>;
+/// extra rpc clients trade transactions are also sent to, (url, client)
+pub type SolSendRpcClientsState = RwLock<Vec<(String, Arc<SolRpcClient>)>>;
+pub type SolEndpointStatsState = Arc<SolEndpointStatsBook>;
+pub type JitoTipCacheState = Arc<JitoTipCache>;
+pub type JitoRegionsState = Arc<JitoRegionBook>;
+pub type EvmNonceManagerState = Arc<EvmNonceManager>;
 pub type TradeTaskState = RwLock<HashMap<String, Task>>;
+pub type TaskSchedulerState = Arc<TaskScheduler>;
+/// trade ledger of the open project
+pub type TradeLedgerState = RwLock<Option<Arc<TradeLedger>>>;
 pub type ProxiedHttpClientState = RwLock<Vec<ProxiedHttpClientValue>>;
 
 #[async_trait]
 pub trait AppHandleStateExt {
     async fn get_proxied_http_client(&self) -> Result<ProxiedHttpClientValue, AppError>;
     async fn read_sol_rpc_client(&self) -> Result<Arc<SolRpcClient>, AppError>;
+    async fn read_sol_tx_confirmer(&self) -> Result<Arc<SolTxConfirmer>, AppError>;
+    async fn read_sol_tx_sender(&self) -> Result<SolTxSender, AppError>;
+    async fn read_sol_chain_cache(&self) -> Result<Arc<SolChainCache>, AppError>;
     async fn read_evm_provider(&self) -> Result<EvmRpcProvider, AppError>;
     async fn read_evm_rpc_client(&self) -> Result<EvmRpcClient<Http<reqwest::Client>>, AppError>;
+    async fn read_evm_tx_sender(&self, config: EvmTxConfig) -> Result<EvmTxSender, AppError>;
 }
 
 #[async_trait]
@@ -76,6 +99,37 @@ impl AppHandleStateExt for AppHandle {
         Ok(rpc_client)
     }
 
+    async fn read_sol_tx_confirmer(&self) -> Result<Arc<SolTxConfirmer>, AppError> {
+        let confirmer_state = self.state::<SolTxConfirmerState>();
+        let guard = confirmer_state.read().await;
+        if guard.is_none() {
+            return Err(AppError::new("no sol tx confirmer found"));
+        }
+        let confirmer = guard.clone().unwrap();
+        drop(guard);
+
+        Ok(confirmer)
+    }
+
+    async fn read_sol_tx_sender(&self) -> Result<SolTxSender, AppError> {
+        let rpc_client = self.read_sol_rpc_client().await?;
+        let confirmer = self.read_sol_tx_confirmer().await?;
+
+        Ok(SolTxSender::new(rpc_client, confirmer))
+    }
+
+    async fn read_sol_chain_cache(&self) -> Result<Arc<SolChainCache>, AppError> {
+        let cache_state = self.state::<SolChainCacheState>();
+        let guard = cache_state.read().await;
+        if guard.is_none() {
+            return Err(AppError::new("no sol chain cache found"));
+        }
+        let cache = guard.clone().unwrap();
+        drop(guard);
+
+        Ok(cache)
+    }
+
     async fn read_evm_provider(&self) -> Result<EvmRpcProvider, AppError> {
         let rpc_state = self.state::<EvmProviderState>();
         let guard = rpc_state.read().await;
@@ -100,4 +154,25 @@ impl AppHandleStateExt for AppHandle {
 
         Ok(rpc_client)
     }
+
+    async fn read_evm_tx_sender(&self, config: EvmTxConfig) -> Result<EvmTxSender, AppError> {
+        let project_state = self.state::<ProjectState>();
+        let guard = project_state.lock().await;
+        let proj = guard
+            .as_ref()
+            .ok_or_else(|| AppError::new("No Project Open"))?;
+        let chain_config = proj
+            .project
+            .chain
+            .evm_chain_config()
+            .ok_or_else(|| AppError::new("project chain is not an evm chain"))?;
+        let confirmations = proj
+            .project
+            .evm_confirmations
+            .unwrap_or(chain_config.confirmations);
+        drop(guard);
+
+        let nonce_manager = self.state::<EvmNonceManagerState>().inner().clone();
+        Ok(EvmTxSender::new(nonce_manager, config, confirmations))
+    }
 }
@@ -1,21 +1,44 @@
 use std::collections::HashMap;
+use std::sync::atomic::AtomicU32;
+use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::Utc;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
+use crate::agg::{
+    AggApiConfig, AggRateLimits, AggRouteStep, AggregatorKind, JupiterRouteConfig,
+    OneInchRouteConfig, RaydiumPoolCache,
+};
 use crate::chain::Chain;
 use crate::commands::tasks::CreateTaskReq;
 use crate::error::AppError;
+use crate::evm::{EvmFeeMode, EvmTxConfig};
+use crate::jito::{JitoBundleCollector, JitoBundleState, JitoTipStrategy};
 use crate::project::Project;
+use crate::sol::SolPriorityFeeStrategy;
 use crate::token::TokenInfo;
+use crate::utils;
 use crate::wallet::PrivateKey;
 
+mod realized;
+mod schedule;
+mod stop;
 mod worker;
 
+pub use realized::*;
+pub use schedule::*;
+pub use stop::*;
 pub use worker::*;
 
+/// extra compute units on top of the simulated units consumed, in percent
+pub const DEFAULT_COMPUTE_UNIT_MARGIN_PCT: u32 = 10;
+
+/// a multi-wallet bundle which isn't full after this is sent with the swaps collected
+pub const JITO_BUNDLE_MAX_WAIT: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskState {
     Created,
@@ -44,21 +67,49 @@ pub struct Task {
     pub running_workers: u32,
     pub task_state: TaskState,
     pub chain: Chain,
-    pub agg_api_url: String,
-    pub agg_api_key: Option<String>,
+    /// primary aggregator first, then the fallbacks in order
+    pub aggregators: Vec<AggApiConfig>,
+    pub jupiter_route_config: JupiterRouteConfig,
+    pub one_inch_route_config: OneInchRouteConfig,
+    /// shared by the workers so all of them back off when an api is rate limited
+    #[serde(skip)]
+    pub agg_rate_limits: Arc<AggRateLimits>,
+    /// shared by the workers so the pools of the token are searched once
+    #[serde(skip)]
+    pub raydium_pools: Arc<RaydiumPoolCache>,
     pub token: TokenInfo,
     pub trade_mode: TradeMode,
     pub percetage: (u32, u32),
     pub slippage: u16,
     pub use_jito: bool,
-    pub jito_url: Option<String>,
+    pub jito_urls: Vec<String>,
+    pub jito_fallback_unit_price: Option<u32>,
+    /// swaps of different wallets sent in one bundle, at most 5
+    pub jito_bundle_size: Option<u8>,
+    #[serde(skip)]
+    pub jito_bundle_collector: Option<Arc<JitoBundleCollector>>,
+    pub jito_tip_strategy: JitoTipStrategy,
+    pub jito_max_tip: Option<u64>,
     pub gas_price: u32,
+    pub priority_fee_strategy: SolPriorityFeeStrategy,
+    pub compute_unit_margin_pct: u32,
+    pub evm_fee_mode: EvmFeeMode,
+    pub evm_tx_config: EvmTxConfig,
     pub interval_secs: u64,
+    pub stop_conditions: TaskStopConditions,
+    /// shared by the workers, the totals checked against the stop conditions
+    #[serde(skip)]
+    pub trade_stats: Arc<TaskTradeStats>,
+    /// why the task was stopped by a stop condition
+    pub stop_reason: Option<String>,
+    pub main_wallet_addr: String,
     pub wallet_states: HashMap<PrivateKey, bool>,
 }
 
 impl Task {
     pub fn create_from_req(req: &CreateTaskReq, project: &Project) -> Result<Self, AppError> {
+        req.jupiter_route_config.validate()?;
+
         let wallet_grp = project
             .wallet_grps
             .iter()
@@ -71,23 +122,62 @@ impl Task {
             .map(|pk| (pk.to_owned(), false))
             .collect();
 
+        let aggregator_kinds = if req.aggregators.is_empty() {
+            vec![AggregatorKind::default_for(wallet_grp.chain)]
+        } else {
+            req.aggregators.clone()
+        };
+        let aggregators = aggregator_kinds
+            .into_iter()
+            .map(|kind| AggApiConfig::resolve(project, kind))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let jito_bundle_collector = req
+            .jito_bundle_size
+            .filter(|size| req.use_jito && *size > 1)
+            .map(|size| {
+                Arc::new(JitoBundleCollector::new(
+                    size as usize,
+                    JITO_BUNDLE_MAX_WAIT,
+                ))
+            });
+        let main_wallet_addr = utils::pk_to_addr(wallet_grp.chain, &project.main_wallet)?;
+
         Ok(Self {
             id: wallet_grp.id.clone(),
             workers_cnt: req.workers_cnt,
             running_workers: 0,
             task_state: TaskState::Created,
             chain: wallet_grp.chain,
-            agg_api_url: project.agg_api_url.clone(),
-            agg_api_key: project.agg_api_key.clone(),
+            aggregators,
+            jupiter_route_config: req.jupiter_route_config.clone(),
+            one_inch_route_config: req.one_inch_route_config.clone(),
+            agg_rate_limits: Arc::default(),
+            raydium_pools: Arc::default(),
             wallet_states,
             token: req.token.clone(),
             trade_mode: req.trade_mode,
             percetage: req.percetage,
             slippage: req.slippage,
             use_jito: req.use_jito,
-            jito_url: project.jito_url.clone(),
+            jito_urls: project.jito_urls.clone(),
+            jito_fallback_unit_price: req.jito_fallback_unit_price,
+            jito_bundle_size: req.jito_bundle_size,
+            jito_bundle_collector,
+            jito_tip_strategy: req.jito_tip_strategy,
+            jito_max_tip: req.jito_max_tip,
             gas_price: req.gas_price,
+            priority_fee_strategy: req.priority_fee_strategy,
+            compute_unit_margin_pct: req
+                .compute_unit_margin_pct
+                .unwrap_or(DEFAULT_COMPUTE_UNIT_MARGIN_PCT),
+            evm_fee_mode: req.evm_fee_mode,
+            evm_tx_config: req.evm_tx_config,
             interval_secs: req.interval_secs,
+            stop_conditions: req.stop_conditions.clone(),
+            trade_stats: Arc::default(),
+            stop_reason: None,
+            main_wallet_addr,
         })
     }
 
@@ -103,15 +193,30 @@ impl Task {
                     id: idx,
                     app_handle: app_handle.clone(),
                     chain: self.chain,
-                    agg_api_url: self.agg_api_url.clone(),
-                    agg_api_key: self.agg_api_key.clone(),
+                    aggregators: self.aggregators.clone(),
+                    jupiter_route_config: self.jupiter_route_config.clone(),
+                    one_inch_route_config: self.one_inch_route_config.clone(),
+                    agg_rate_limits: self.agg_rate_limits.clone(),
+                    raydium_pools: self.raydium_pools.clone(),
                     token: self.token.clone(),
                     trade_mode: self.trade_mode,
                     percetage: self.percetage,
                     slippage: self.slippage,
                     gas_price: self.gas_price,
+                    priority_fee_strategy: self.priority_fee_strategy,
+                    compute_unit_margin_pct: self.compute_unit_margin_pct,
+                    evm_fee_mode: self.evm_fee_mode,
+                    evm_tx_config: self.evm_tx_config,
+                    dropped_cnt: AtomicU32::new(0),
                     use_jito: self.use_jito,
-                    jito_url: self.jito_url.clone(),
+                    jito_urls: self.jito_urls.clone(),
+                    jito_fallback_unit_price: self.jito_fallback_unit_price,
+                    jito_bundle_collector: self.jito_bundle_collector.clone(),
+                    jito_tip_strategy: self.jito_tip_strategy,
+                    jito_max_tip: self.jito_max_tip,
+                    stop_conditions: self.stop_conditions.clone(),
+                    trade_stats: self.trade_stats.clone(),
+                    main_wallet_addr: self.main_wallet_addr.clone(),
                 }
             })
             .collect()
@@ -144,16 +249,54 @@ impl Task {
 
 #[derive(Debug, Copy, Clone, Serialize)]
 pub enum TradeTaskEventType {
+    /// the task was started, only sent when it wasn't started from its window
+    Started,
     Executed,
+    /// the task was asked to stop, only sent when it wasn't stopped from its window,
+    /// `Stopped` follows once its workers exited
+    Stopping,
     Stopped,
 }
 
+/// Structured data of a trade attached to an event
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TradeEventDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txid: Option<String>,
+    /// compute unit price in micro lamports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority_fee: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_limit: Option<u32>,
+    /// lamports tipped to jito
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jito_tip: Option<u64>,
+    /// program logs of a failed simulation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logs: Option<Vec<String>>,
+    /// evm blocks on top of the receipt's block when reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u64>,
+    /// times the evm receipt's block was reorged out before it was final
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reorg_cnt: Option<u32>,
+    /// hops of the quote the swap was built from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route_plan: Option<Vec<AggRouteStep>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    /// state of the jito bundle once the transaction's outcome is known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_state: Option<JitoBundleState>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TradeTaskEventPayload {
     task_id: String,
     worker_id: Option<u32>,
     kind: TradeTaskEventType,
     msg: String,
+    detail: Option<TradeEventDetail>,
     ts: i64,
 }
 
@@ -168,6 +311,7 @@ impl TradeTaskEventPayload {
             worker_id: None,
             kind,
             msg: msg.into(),
+            detail: None,
             ts: Utc::now().timestamp_millis(),
         }
     }
@@ -182,7 +326,13 @@ impl TradeTaskEventPayload {
             worker_id: Some(worker.id),
             kind,
             msg: msg.into(),
+            detail: None,
             ts: Utc::now().timestamp_millis(),
         }
     }
+
+    pub fn with_detail(mut self, detail: TradeEventDetail) -> Self {
+        self.detail = Some(detail);
+        self
+    }
 }
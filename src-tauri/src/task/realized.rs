@@ -0,0 +1,88 @@
+use alloy::primitives::{I256, U256};
+
+use crate::{evm::EvmBalanceChanges, sol::SolBalanceChanges};
+
+use super::TradeDirection;
+
+/// What a landed swap actually traded, amounts are in the smallest unit
+#[derive(Debug, Clone, Copy)]
+pub struct RealizedSwap {
+    pub input: U256,
+    pub output: U256,
+    /// transaction fees and the jito tip in the native coin
+    pub fee: U256,
+}
+
+impl RealizedSwap {
+    /// The native side excludes the fee and the tip, but includes the rent of
+    /// token accounts the swap created
+    pub fn from_sol(direction: TradeDirection, changes: &SolBalanceChanges, tip: u64) -> Self {
+        let fee = changes.fee as i128 + tip as i128;
+        let native = changes.lamports + changes.wsol + fee;
+        let (input, output) = match direction {
+            TradeDirection::Buy => (-native, changes.token),
+            TradeDirection::Sell => (-changes.token, native),
+        };
+
+        Self {
+            input: U256::from(input.max(0) as u128),
+            output: U256::from(output.max(0) as u128),
+            fee: U256::from(fee as u128),
+        }
+    }
+
+    pub fn from_evm(direction: TradeDirection, changes: &EvmBalanceChanges) -> Self {
+        let (input, output) = match direction {
+            TradeDirection::Buy => (-changes.native, changes.token),
+            TradeDirection::Sell => (-changes.token, changes.native),
+        };
+        let to_amount = |change: I256| {
+            if change.is_negative() {
+                U256::ZERO
+            } else {
+                change.into_raw()
+            }
+        };
+
+        Self {
+            input: to_amount(input),
+            output: to_amount(output),
+            fee: changes.fee,
+        }
+    }
+
+    /// Native coin paid or received per token, `None` when no token was traded
+    pub fn effective_price(
+        &self,
+        direction: TradeDirection,
+        native_decimals: u8,
+        token_decimals: u8,
+    ) -> Option<f64> {
+        let (native, token) = match direction {
+            TradeDirection::Buy => (self.input, self.output),
+            TradeDirection::Sell => (self.output, self.input),
+        };
+        if token.is_zero() {
+            return None;
+        }
+        let native = to_ui_amount(native, native_decimals);
+        let token = to_ui_amount(token, token_decimals);
+
+        Some(native / token)
+    }
+
+    /// Basis points the output is below the quoted output, negative when it is above
+    pub fn slippage_bps(&self, quoted_output: U256) -> Option<f64> {
+        if quoted_output.is_zero() {
+            return None;
+        }
+        let quoted = quoted_output.saturating_to::<u128>() as f64;
+        let realized = self.output.saturating_to::<u128>() as f64;
+
+        Some((quoted - realized) / quoted * 10_000.0)
+    }
+}
+
+fn to_ui_amount(amount: U256, decimals: u8) -> f64 {
+    amount.saturating_to::<u128>() as f64 / 10f64.powi(decimals as i32)
+}
@@ -0,0 +1,229 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Weekday};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    commands::tasks::{create_trade_task, start_trade_task, stop_trade_task, CreateTaskReq},
+    error::AppError,
+    state::{ProjectState, TaskSchedulerState, TradeTaskState},
+    utils::AppHandleExt,
+};
+
+use super::{TaskState, TradeTaskEventPayload, TradeTaskEventType};
+
+/// schedules are checked this often
+const SCHEDULE_TICK: Duration = Duration::from_secs(1);
+
+/// When a task should be running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TaskScheduleKind {
+    /// start once at a unix timestamp in seconds, and stop at another one when set
+    Once { start_at: i64, stop_at: Option<i64> },
+    /// run in a window of the day in local time, the window spans midnight when
+    /// `stop` is before `start`, every day of the week when `weekdays` is empty
+    Daily {
+        start: NaiveTime,
+        stop: NaiveTime,
+        weekdays: Vec<Weekday>,
+    },
+    /// run `run_secs` out of every `every_secs` from a unix timestamp in seconds
+    Interval {
+        start_at: i64,
+        every_secs: u64,
+        run_secs: u64,
+    },
+}
+
+impl TaskScheduleKind {
+    pub fn validate(&self) -> Result<(), AppError> {
+        match self {
+            Self::Once { start_at, stop_at } => {
+                if stop_at.is_some_and(|stop_at| stop_at <= *start_at) {
+                    return Err(AppError::new("stop time must be after the start time"));
+                }
+            }
+            Self::Daily { start, stop, .. } => {
+                if start == stop {
+                    return Err(AppError::new("start and stop time of the day are the same"));
+                }
+            }
+            Self::Interval {
+                every_secs,
+                run_secs,
+                ..
+            } => {
+                if *every_secs == 0 || *run_secs == 0 {
+                    return Err(AppError::new("interval and run seconds must be positive"));
+                }
+                if run_secs > every_secs {
+                    return Err(AppError::new("run seconds are longer than the interval"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the task should be running at `now`
+    pub fn is_active(&self, now: DateTime<Local>) -> bool {
+        match self {
+            Self::Once { start_at, stop_at } => {
+                let ts = now.timestamp();
+                ts >= *start_at && stop_at.map_or(true, |stop_at| ts < stop_at)
+            }
+            Self::Daily {
+                start,
+                stop,
+                weekdays,
+            } => {
+                let on_day = |day: Weekday| weekdays.is_empty() || weekdays.contains(&day);
+                let time = now.time();
+                let today = now.weekday();
+                if start < stop {
+                    on_day(today) && time >= *start && time < *stop
+                } else {
+                    // the part after midnight belongs to the window started yesterday
+                    (on_day(today) && time >= *start) || (on_day(today.pred()) && time < *stop)
+                }
+            }
+            Self::Interval {
+                start_at,
+                every_secs,
+                run_secs,
+            } => {
+                let ts = now.timestamp();
+                ts >= *start_at && ((ts - start_at) as u64 % every_secs) < *run_secs
+            }
+        }
+    }
+}
+
+/// Schedule of a task, saved in the project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskSchedule {
+    /// id of the task, which is the id of its wallet group
+    pub task_id: String,
+    pub kind: TaskScheduleKind,
+    /// the task is created from it when the schedule starts it and it doesn't exist
+    pub task: CreateTaskReq,
+}
+
+/// Applies the transitions of the schedules to their tasks
+///
+/// Only the changes of a schedule's state are applied, so a task stopped by hand
+/// in its window stays stopped until the next window.
+#[derive(Debug, Default)]
+pub struct TaskScheduler {
+    /// last state applied per task, true when it was started
+    applied: Mutex<HashMap<String, bool>>,
+}
+
+impl TaskScheduler {
+    /// Forget the state applied to a task, like when its schedule is replaced
+    pub fn reset(&self, task_id: &str) {
+        self.applied.lock().unwrap().remove(task_id);
+    }
+
+    pub fn clear(&self) {
+        self.applied.lock().unwrap().clear();
+    }
+
+    /// Check the schedules of the open project until the app exits
+    pub async fn run(app_handle: AppHandle) {
+        let mut ticker = tokio::time::interval(SCHEDULE_TICK);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            let project_state = app_handle.state::<ProjectState>();
+            let guard = project_state.lock().await;
+            let schedules = guard
+                .as_ref()
+                .map(|proj| proj.project.task_schedules.clone())
+                .unwrap_or_default();
+            drop(guard);
+
+            let scheduler = app_handle.state::<TaskSchedulerState>().inner().clone();
+            let now = Local::now();
+            for schedule in schedules {
+                let active = schedule.kind.is_active(now);
+                if let Err(err) = scheduler.apply(&app_handle, &schedule, active).await {
+                    warn!(
+                        "apply schedule of task {} error: {}",
+                        schedule.task_id, err.err_msg
+                    );
+                }
+            }
+        }
+    }
+
+    async fn apply(
+        &self,
+        app_handle: &AppHandle,
+        schedule: &TaskSchedule,
+        active: bool,
+    ) -> Result<(), AppError> {
+        let task_id = schedule.task_id.as_str();
+        let applied = self.applied.lock().unwrap().get(task_id).copied();
+        match applied {
+            Some(applied) if applied == active => return Ok(()),
+            // an app opened out of a window leaves a task started by hand running
+            None if !active => {
+                self.applied
+                    .lock()
+                    .unwrap()
+                    .insert(task_id.to_string(), false);
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        let tasks_state = app_handle.state::<TradeTaskState>();
+        let guard = tasks_state.read().await;
+        let task_state = guard.get(task_id).map(|task| task.task_state);
+        drop(guard);
+        self.applied
+            .lock()
+            .unwrap()
+            .insert(task_id.to_string(), active);
+
+        let win_label = format!("task_{task_id}");
+        if active {
+            let task_state = match task_state {
+                Some(task_state) => task_state,
+                None => {
+                    debug!("creating task {task_id} by schedule");
+                    create_trade_task(schedule.task.clone(), app_handle.clone()).await?;
+                    TaskState::Created
+                }
+            };
+            if task_state != TaskState::Created && task_state != TaskState::Stopped {
+                return Ok(());
+            }
+            debug!("starting task {task_id} by schedule");
+            start_trade_task(task_id.to_string(), app_handle.clone()).await?;
+            let evt = TradeTaskEventPayload::new_task_event(
+                task_id,
+                TradeTaskEventType::Started,
+                format!("task {task_id} started by schedule"),
+            );
+            app_handle.emit_trade_task_evt(win_label, evt);
+        } else {
+            if task_state != Some(TaskState::Running) {
+                return Ok(());
+            }
+            debug!("stopping task {task_id} by schedule");
+            stop_trade_task(task_id.to_string(), app_handle.clone()).await?;
+            let evt = TradeTaskEventPayload::new_task_event(
+                task_id,
+                TradeTaskEventType::Stopping,
+                format!("task {task_id} stopping by schedule"),
+            );
+            app_handle.emit_trade_task_evt(win_label, evt);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::Chain;
+
+/// balances are checked at most this often by the workers of a task
+const BALANCE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Conditions which stop a running task, amounts are in the native coin of the chain
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskStopConditions {
+    /// trades which landed, failed ones included
+    pub max_trades: Option<u64>,
+    /// transaction fees and jito tips of every trade
+    pub max_fees: Option<f64>,
+    /// native side of every successful trade, the input of a buy
+    /// and the quoted output of a sell
+    pub max_notional: Option<f64>,
+    /// unix timestamp in seconds
+    pub end_at: Option<i64>,
+    pub min_main_wallet_balance: Option<f64>,
+    /// sum of the balances of the task's wallets
+    pub min_group_balance: Option<f64>,
+}
+
+impl TaskStopConditions {
+    pub fn has_balance_conditions(&self) -> bool {
+        self.min_main_wallet_balance.is_some() || self.min_group_balance.is_some()
+    }
+
+    /// Reason of the first condition hit by the trade totals or the clock
+    pub fn check_totals(&self, chain: Chain, totals: &TaskTradeTotals) -> Option<String> {
+        if let Some(max_trades) = self.max_trades {
+            if totals.trades >= max_trades {
+                return Some(format!(
+                    "{} trades reached the max {max_trades}",
+                    totals.trades
+                ));
+            }
+        }
+        if let Some(max_fees) = self.max_fees {
+            let fees = from_native_units(chain, totals.fees);
+            if fees >= max_fees {
+                return Some(format!("fees spent {fees} reached the max {max_fees}"));
+            }
+        }
+        if let Some(max_notional) = self.max_notional {
+            let notional = from_native_units(chain, totals.notional);
+            if notional >= max_notional {
+                return Some(format!(
+                    "traded notional {notional} reached the max {max_notional}"
+                ));
+            }
+        }
+        if let Some(end_at) = self.end_at {
+            if Utc::now().timestamp() >= end_at {
+                return Some("end time reached".to_string());
+            }
+        }
+
+        None
+    }
+
+    /// Balances are in the smallest unit, `None` when not fetched
+    pub fn check_balances(
+        &self,
+        chain: Chain,
+        main_wallet_bal: Option<u128>,
+        group_bal: Option<u128>,
+    ) -> Option<String> {
+        if let (Some(min_bal), Some(bal)) = (self.min_main_wallet_balance, main_wallet_bal) {
+            let bal = from_native_units(chain, bal);
+            if bal < min_bal {
+                return Some(format!(
+                    "main wallet balance {bal} is below the min {min_bal}"
+                ));
+            }
+        }
+        if let (Some(min_bal), Some(bal)) = (self.min_group_balance, group_bal) {
+            let bal = from_native_units(chain, bal);
+            if bal < min_bal {
+                return Some(format!(
+                    "wallet group balance {bal} is below the min {min_bal}"
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// Totals of a task's trades in the smallest unit of the native coin
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TaskTradeTotals {
+    pub trades: u64,
+    pub fees: u128,
+    pub notional: u128,
+}
+
+/// Trade totals shared by the workers of a task, kept across restarts of the task
+#[derive(Debug, Default)]
+pub struct TaskTradeStats {
+    totals: Mutex<TaskTradeTotals>,
+    balance_checked_at: Mutex<Option<Instant>>,
+}
+
+impl TaskTradeStats {
+    /// A landed trade, `notional` is 0 when it failed
+    pub fn record_trade(&self, fees: u128, notional: u128) {
+        let mut totals = self.totals.lock().unwrap();
+        totals.trades += 1;
+        totals.fees = totals.fees.saturating_add(fees);
+        totals.notional = totals.notional.saturating_add(notional);
+    }
+
+    /// Fees of a transaction which isn't a trade, like an approval
+    pub fn record_fees(&self, fees: u128) {
+        let mut totals = self.totals.lock().unwrap();
+        totals.fees = totals.fees.saturating_add(fees);
+    }
+
+    pub fn totals(&self) -> TaskTradeTotals {
+        self.totals.lock().unwrap().clone()
+    }
+
+    /// Whether balances weren't checked by any worker of the task recently,
+    /// the check time is updated when they weren't
+    pub fn should_check_balances(&self) -> bool {
+        let mut checked_at = self.balance_checked_at.lock().unwrap();
+        if checked_at.is_some_and(|it| it.elapsed() < BALANCE_CHECK_INTERVAL) {
+            return false;
+        }
+        *checked_at = Some(Instant::now());
+        true
+    }
+}
+
+fn from_native_units(chain: Chain, amount: u128) -> f64 {
+    amount as f64 / 10f64.powi(chain.native_decimals() as i32)
+}
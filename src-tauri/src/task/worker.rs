@@ -1,6 +1,10 @@
 use std::{
     str::FromStr,
-    time::{Duration, Instant},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
 use alloy::{
@@ -10,19 +14,21 @@ use alloy::{
         Address, U256,
     },
     providers::{Provider, ProviderBuilder},
-    rpc::types::TransactionRequest,
     signers::local::PrivateKeySigner,
 };
+use chrono::Utc;
+use futures::future::join_all;
 use log::{debug, warn};
 use rand::{thread_rng, Rng};
 use solana_client::rpc_client::SerializableTransaction;
 use solana_sdk::{
     compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
     message::{v0::Message, VersionedMessage},
     native_token::lamports_to_sol,
     program_pack::Pack,
     pubkey::Pubkey,
-    signature::Keypair,
+    signature::{Keypair, Signature},
     signer::Signer,
     system_instruction,
     transaction::VersionedTransaction,
@@ -30,21 +36,35 @@ use solana_sdk::{
 use tauri::{AppHandle, Manager};
 
 use crate::{
+    agg::{
+        AggApiConfig, AggRateLimits, AggRouteStep, AggSwapReq, JupiterRouteConfig,
+        OneInchRouteConfig, RaydiumPoolCache, SolSwap,
+    },
     chain::{Chain, SolRpcClientExt},
-    consts::{ONE_INCH_NATIVE_COIN_ADDR, WSOL_MINT},
+    consts::{ONE_INCH_NATIVE_COIN_ADDR, SOL_TX_BASE_FEE, WSOL_MINT},
     contracts::Erc20Contract,
     error::AppError,
-    jito::JitoRpcClient,
-    jup::{self, quote::QuoteRequest, swap::SwapRequest, transaction_config::TransactionConfig},
-    one_inch::{self, SwapQueryParams},
-    state::{AppHandleStateExt, TradeTaskState},
+    evm::{EvmBalanceChanges, EvmFeeMode, EvmTxConfig},
+    jito::{
+        JitoBundleCollector, JitoBundleEntry, JitoBundleSendError, JitoBundleSubmitter,
+        JitoRpcClient, JitoTipStrategy,
+    },
+    ledger::{TradeRecord, TradeStatus},
+    sol::{
+        SolBalanceChanges, SolEndpoint, SolFanoutSubmitter, SolPriorityFeeStrategy, SolTxOutcome,
+    },
+    state::{
+        AppHandleStateExt, JitoRegionsState, JitoTipCacheState, SolEndpointStatsState,
+        SolSendRpcClientsState, TradeLedgerState, TradeTaskState,
+    },
     token::TokenInfo,
-    utils::AppHandleExt,
+    utils::{self, AppHandleExt},
     wallet::PrivateKey,
 };
 
 use super::{
-    Task, TaskState, TradeDirection, TradeMode, TradeTaskEventPayload, TradeTaskEventType,
+    RealizedSwap, Task, TaskState, TaskStopConditions, TaskTradeStats, TradeDirection,
+    TradeEventDetail, TradeMode, TradeTaskEventPayload, TradeTaskEventType,
 };
 
 #[derive(Debug)]
@@ -54,15 +74,64 @@ pub struct Worker {
     pub app_handle: AppHandle,
     pub win_label: String,
     pub chain: Chain,
-    pub agg_api_url: String,
-    pub agg_api_key: Option<String>,
+    /// primary aggregator first, then the fallbacks in order
+    pub aggregators: Vec<AggApiConfig>,
+    pub jupiter_route_config: JupiterRouteConfig,
+    pub one_inch_route_config: OneInchRouteConfig,
+    pub agg_rate_limits: Arc<AggRateLimits>,
+    pub raydium_pools: Arc<RaydiumPoolCache>,
     pub token: TokenInfo,
     pub trade_mode: TradeMode,
     pub percetage: (u32, u32),
     pub slippage: u16,
     pub use_jito: bool,
-    pub jito_url: Option<String>,
+    /// block engine regions of the project
+    pub jito_urls: Vec<String>,
+    /// compute unit price in micro lamports to send through rpc with when every
+    /// jito region failed, no fallback when not set
+    pub jito_fallback_unit_price: Option<u32>,
+    /// shared by the workers of a task to bundle their swaps, `None` sends a bundle per swap
+    pub jito_bundle_collector: Option<Arc<JitoBundleCollector>>,
+    pub jito_tip_strategy: JitoTipStrategy,
+    /// lamports, caps the tip of every strategy
+    pub jito_max_tip: Option<u64>,
     pub gas_price: u32,
+    pub priority_fee_strategy: SolPriorityFeeStrategy,
+    pub compute_unit_margin_pct: u32,
+    pub evm_fee_mode: EvmFeeMode,
+    pub evm_tx_config: EvmTxConfig,
+    pub stop_conditions: TaskStopConditions,
+    pub trade_stats: Arc<TaskTradeStats>,
+    /// checked against the min main wallet balance stop condition
+    pub main_wallet_addr: String,
+    /// transactions dropped in a row, used by adaptive priority fee and escalating tips
+    pub dropped_cnt: AtomicU32,
+}
+
+const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 600_000;
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Why a solana swap was not sent or confirmed
+enum SolSendError {
+    /// every jito region rejected the bundle with rate limits or connection errors
+    /// before returning a bundle id, nothing was sent
+    JitoRejected(AppError),
+    Other(AppError),
+}
+
+impl From<AppError> for SolSendError {
+    fn from(err: AppError) -> Self {
+        Self::Other(err)
+    }
+}
+
+impl From<JitoBundleSendError> for SolSendError {
+    fn from(err: JitoBundleSendError) -> Self {
+        match err {
+            JitoBundleSendError::Rejected(err_msg) => Self::JitoRejected(AppError::new(err_msg)),
+            JitoBundleSendError::Failed(err_msg) => Self::Other(AppError::new(err_msg)),
+        }
+    }
 }
 
 impl Worker {
@@ -74,6 +143,15 @@ impl Worker {
         let evt = TradeTaskEventPayload::new_worker_event(self, kind, msg);
         self.app_handle.emit_trade_task_evt(&self.win_label, evt);
     }
+    fn send_worker_detail_to_win(
+        &self,
+        kind: TradeTaskEventType,
+        msg: impl Into<String>,
+        detail: TradeEventDetail,
+    ) {
+        let evt = TradeTaskEventPayload::new_worker_event(self, kind, msg).with_detail(detail);
+        self.app_handle.emit_trade_task_evt(&self.win_label, evt);
+    }
 
     pub fn start(self) {
         tauri::async_runtime::spawn(async move {
@@ -94,21 +172,39 @@ impl Worker {
                         let trade_interval_secs = task.interval_secs;
                         drop(guard);
 
+                        if let Some(reason) = self.stop_condition_hit().await {
+                            self.stop_for(reason).await;
+                            continue;
+                        }
+
                         let msg_kind = TradeTaskEventType::Executed;
                         if let Err(err) = self.execute().await {
                             self.send_worker_msg_to_win(msg_kind, err.err_msg);
                             self.send_worker_msg_to_win(TradeTaskEventType::Executed, "");
                         }
 
-                        if trade_interval_secs >= 5 {
+                        let mut wait = Duration::from_secs(trade_interval_secs);
+                        let kinds = self.aggregators.iter().map(|agg_api| agg_api.kind);
+                        if let Some(backoff) = self.agg_rate_limits.backoff(kinds) {
+                            if backoff > wait {
+                                wait = backoff;
+                                self.send_worker_msg_to_win(
+                                    msg_kind,
+                                    format!(
+                                        "all aggregators rate limited, back off {} seconds ......",
+                                        wait.as_secs()
+                                    ),
+                                );
+                            }
+                        }
+
+                        if wait.as_secs() >= 5 {
                             self.send_worker_msg_to_win(
                                 msg_kind,
-                                format!(
-                                    "waiting {trade_interval_secs} seconds for next trade ......"
-                                ),
+                                format!("waiting {} seconds for next trade ......", wait.as_secs()),
                             );
                         }
-                        tokio::time::sleep(Duration::from_secs(trade_interval_secs)).await;
+                        tokio::time::sleep(wait).await;
                     }
                     TaskState::Stopping => {
                         self.stop(task);
@@ -131,10 +227,14 @@ impl Worker {
         let selected_key = selected_key.unwrap();
 
         // NOTE: take and return must success, so don't consume Result here
+        let mut record = None;
         let execute_result = match self.chain {
-            Chain::Solana => self.sol_execute(&selected_key).await,
-            Chain::Base | Chain::Bsc => self.evm_execute(&selected_key).await,
+            Chain::Solana => self.sol_execute(&selected_key, &mut record).await,
+            Chain::Base | Chain::Bsc => self.evm_execute(&selected_key, &mut record).await,
         };
+        if let Some(record) = record {
+            self.write_trade_record(record, &execute_result).await;
+        }
 
         if execute_result.is_ok() {
             self.send_worker_msg_to_win(TradeTaskEventType::Executed, "");
@@ -175,7 +275,12 @@ impl Worker {
         thread_rng().gen_range(min..=max) as u64
     }
 
-    async fn sol_execute(&self, selected_key: &[u8]) -> Result<(), AppError> {
+    /// `record` is set once the trade is chosen and filled as it goes
+    async fn sol_execute(
+        &self,
+        selected_key: &[u8],
+        record: &mut Option<TradeRecord>,
+    ) -> Result<(), AppError> {
         let rpc_client = self.app_handle.read_sol_rpc_client().await?;
         let wallet_keypair = Keypair::from_bytes(selected_key)?;
         let wallet_pubkey = wallet_keypair.pubkey();
@@ -216,6 +321,15 @@ impl Worker {
         let msg_kind = TradeTaskEventType::Executed;
         let evt_msg = format!("choose account {wallet_pubkey} {msg}",);
         self.send_worker_msg_to_win(msg_kind, evt_msg);
+        let record = record.insert(TradeRecord::new(
+            &self.task_id,
+            self.id,
+            wallet_pubkey.to_string(),
+            trade_direction,
+            input_mint.to_string(),
+            output_mint.to_string(),
+            input_amount.to_string(),
+        ));
 
         if input_amount == 0 {
             return Err(AppError::new("input is 0, skip this trade ......"));
@@ -224,130 +338,401 @@ impl Worker {
         let proxied_http_client = self.app_handle.get_proxied_http_client().await?;
         let proxy_url = proxied_http_client.url;
         let proxied_http_client = proxied_http_client.client;
-        self.send_worker_msg_to_win(msg_kind, format!("use proxy: {} to request jup", proxy_url));
+        record.proxy = Some(proxy_url.clone());
+        let swap_req = AggSwapReq {
+            input_token: input_mint,
+            output_token: output_mint,
+            amount: U256::from(input_amount),
+            slippage: self.slippage,
+            user: wallet_pubkey,
+        };
+        let mut swap_result = Err(AppError::new("no aggregator configured"));
+        for agg_api in &self.aggregators {
+            let aggregator = agg_api.build_sol(
+                proxied_http_client.clone(),
+                rpc_client.clone(),
+                &self.jupiter_route_config,
+                self.agg_rate_limits.clone(),
+                self.raydium_pools.clone(),
+            )?;
+            let kind = aggregator.kind();
+            if let Some(remaining) = self.agg_rate_limits.remaining(kind) {
+                let err_msg = format!("{kind} rate limited for {}s", remaining.as_secs());
+                let evt_msg = format!("{err_msg}, try next aggregator");
+                self.send_worker_msg_to_win(msg_kind, evt_msg);
+                swap_result = Err(AppError::new(err_msg));
+                continue;
+            }
+            self.send_worker_msg_to_win(
+                msg_kind,
+                format!("use proxy: {proxy_url} to request {kind}"),
+            );
 
-        let quote_req = QuoteRequest {
-            amount: input_amount,
-            input_mint,
-            output_mint,
-            slippage_bps: self.slippage,
-            only_direct_routes: Some(true),
+            swap_result = aggregator.quote_swap(&swap_req).await;
+            match &swap_result {
+                Ok(_) => break,
+                Err(err) => {
+                    let evt_msg =
+                        format!("{kind} swap failed: {}, try next aggregator", err.err_msg);
+                    self.send_worker_msg_to_win(msg_kind, evt_msg);
+                }
+            }
+        }
+        let (quote, sol_swap) = swap_result?;
+        let native_notional = match trade_direction {
+            TradeDirection::Buy => input_amount as u128,
+            TradeDirection::Sell => quote.out_amount.saturating_to::<u128>(),
+        };
+        let route_plan = quote.route_plan();
+        record.aggregator = Some(quote.aggregator.to_string());
+        record.quoted_output = Some(quote.out_amount.to_string());
+        record.route_plan = route_plan.clone();
+        let evt_msg = format!(
+            "{} quote out amount: {}",
+            quote.aggregator, quote.out_amount
+        );
+        let detail = TradeEventDetail {
+            route_plan: route_plan.clone(),
             ..Default::default()
         };
-        debug!("jup quote req: {}", serde_qs::to_string(&quote_req)?);
-        let quote_response = jup::quote(&proxied_http_client, &self.agg_api_url, &quote_req)
-            .await
-            .map_err(|err| AppError::new(err.to_string()))?;
+        self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
 
-        let swap_req = SwapRequest {
-            quote_response,
-            user_public_key: wallet_pubkey,
-            config: TransactionConfig {
-                use_shared_accounts: false,
-                ..Default::default()
-            },
-        };
-        let swap_ixs_resp =
-            jup::swap_instructions(&proxied_http_client, &self.agg_api_url, &swap_req)
-                .await
-                .map_err(|err| AppError::new(err.to_string()))?;
-
-        let setup_ixs = swap_ixs_resp.setup_instructions;
-        let swap_ix = swap_ixs_resp.swap_instruction;
-        let cleanup_ix = swap_ixs_resp
-            .cleanup_instruction
-            .map(|ix| vec![ix])
-            .unwrap_or_default();
-        // let compute_units_ixs = swap_ixs_resp.compute_budget_instructions;
-        let addr_loopup_tb = rpc_client
-            .get_address_lookup_tables(&swap_ixs_resp.address_lookup_table_addresses)
+        let jito_regions = self.app_handle.state::<JitoRegionsState>();
+        let can_fallback = self.jito_fallback_unit_price.is_some();
+        let mut via_jito = self.use_jito;
+        if via_jito && can_fallback && !jito_regions.any_available(&self.jito_urls) {
+            via_jito = false;
+            let evt_msg = "every jito region failed recently, send through rpc with priority fee";
+            self.send_worker_msg_to_win(msg_kind, evt_msg);
+        }
+        loop {
+            let result = self
+                .sol_send_swap(
+                    &wallet_keypair,
+                    &sol_swap,
+                    route_plan.clone(),
+                    &proxied_http_client,
+                    native_notional,
+                    via_jito,
+                    record,
+                )
+                .await;
+            match result {
+                Ok(()) => break,
+                Err(SolSendError::JitoRejected(err)) if via_jito && can_fallback => {
+                    let evt_msg = format!(
+                        "every jito region rejected the bundle: {}, fall back to rpc with priority fee",
+                        err.err_msg
+                    );
+                    self.send_worker_msg_to_win(msg_kind, evt_msg);
+                    via_jito = false;
+                }
+                Err(SolSendError::JitoRejected(err) | SolSendError::Other(err)) => return Err(err),
+            }
+        }
+
+        if record.status == TradeStatus::Success {
+            let txid = record.txid.clone().unwrap_or_default();
+            let tip = record.tip.unwrap_or_default();
+            let realized = async {
+                let signature = Signature::from_str(&txid)?;
+                let changes =
+                    SolBalanceChanges::fetch(&rpc_client, &signature, &wallet_pubkey, &token_mint)
+                        .await?;
+                Ok::<_, AppError>(RealizedSwap::from_sol(trade_direction, &changes, tip))
+            }
+            .await;
+            self.report_realized(record, realized, quote.out_amount);
+        }
+
+        Ok(())
+    }
+
+    /// Simulate, sign and send the swap transaction and report its outcome, through the
+    /// jito block engines with a tip when `via_jito`, otherwise with a priority fee
+    async fn sol_send_swap(
+        &self,
+        wallet_keypair: &Keypair,
+        sol_swap: &SolSwap,
+        route_plan: Option<Vec<AggRouteStep>>,
+        proxied_http_client: &reqwest::Client,
+        native_notional: u128,
+        via_jito: bool,
+        record: &mut TradeRecord,
+    ) -> Result<(), SolSendError> {
+        let msg_kind = TradeTaskEventType::Executed;
+        let rpc_client = self.app_handle.read_sol_rpc_client().await?;
+        let wallet_pubkey = wallet_keypair.pubkey();
+
+        let chain_cache = self.app_handle.read_sol_chain_cache().await?;
+        let addr_loopup_tb = chain_cache
+            .get_address_lookup_tables(&sol_swap.address_lookup_table_addresses)
             .await?;
 
-        let unit_price = if self.use_jito {
+        let swap_ixs = &sol_swap.instructions;
+        let unit_price = if via_jito {
             0u64
         } else {
-            self.gas_price as u64
+            // NOTE: `gas_price` is the jito tip of jito tasks
+            let base_price = match self.jito_fallback_unit_price {
+                Some(fallback_price) if self.use_jito => fallback_price,
+                _ => self.gas_price,
+            };
+            let dropped_cnt = self.dropped_cnt.load(Ordering::Relaxed);
+            let unit_price = self
+                .priority_fee_strategy
+                .compute_unit_price(&rpc_client, swap_ixs, base_price as u64, dropped_cnt)
+                .await?;
+            let detail = TradeEventDetail {
+                priority_fee: Some(unit_price),
+                ..Default::default()
+            };
+            let evt_msg = format!("use priority fee {unit_price} micro lamports per compute unit");
+            self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            unit_price
         };
-        let compute_units_ixs = vec![
-            ComputeBudgetInstruction::set_compute_unit_limit(600_000),
-            ComputeBudgetInstruction::set_compute_unit_price(unit_price),
-        ];
-
-        let mut tx_ixs = [compute_units_ixs, setup_ixs, vec![swap_ix], cleanup_ix].concat();
-
-        if self.use_jito {
-            tx_ixs.push(system_instruction::transfer(
-                &wallet_pubkey,
-                &JitoRpcClient::get_tip_account(),
-                self.gas_price as u64,
-            ));
-        }
-
-        let recent_blockhash = rpc_client.get_latest_blockhash().await?;
-        let tx_msg =
-            Message::try_compile(&wallet_pubkey, &tx_ixs, &addr_loopup_tb, recent_blockhash)?;
-        let tx_msg = VersionedMessage::V0(tx_msg);
-        let tx = VersionedTransaction::try_new(tx_msg, &[&wallet_keypair])?;
-        let txid = tx.get_signature();
-        self.send_worker_msg_to_win(msg_kind, format!("created transaction {txid}"));
+        let jito_client = if via_jito {
+            if self.jito_urls.is_empty() {
+                return Err(AppError::new("jito block engine url not provide").into());
+            }
+            let jito_regions = self.app_handle.state::<JitoRegionsState>();
+            let base_urls = jito_regions
+                .ranked(proxied_http_client, &self.jito_urls)
+                .await;
+            debug!("jito regions fastest first: {base_urls:?}");
+            Some(Arc::new(JitoRpcClient::new(
+                proxied_http_client.clone(),
+                base_urls,
+                jito_regions.inner().clone(),
+            )))
+        } else {
+            None
+        };
+        let mut jito_tip = None;
+        if let Some(jito_client) = &jito_client {
+            let tip_cache = self.app_handle.state::<JitoTipCacheState>();
+            let tip_account = tip_cache.tip_account(jito_client).await;
+            let dropped_cnt = self.dropped_cnt.load(Ordering::Relaxed);
+            let tip = self
+                .jito_tip_strategy
+                .tip(
+                    &tip_cache,
+                    proxied_http_client,
+                    self.gas_price as u64,
+                    dropped_cnt,
+                    self.jito_max_tip,
+                )
+                .await?;
+            let detail = TradeEventDetail {
+                jito_tip: Some(tip),
+                ..Default::default()
+            };
+            let evt_msg = format!("use jito tip {} SOL", lamports_to_sol(tip));
+            self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            jito_tip = Some((tip_account, tip));
+        }
+        let build_tx = |unit_limit: u32, recent_blockhash: Hash, with_tip: bool| {
+            let compute_units_ixs = vec![
+                ComputeBudgetInstruction::set_compute_unit_limit(unit_limit),
+                ComputeBudgetInstruction::set_compute_unit_price(unit_price),
+            ];
+
+            let mut tx_ixs = [compute_units_ixs, swap_ixs.clone()].concat();
 
-        if self.use_jito {
-            if self.jito_url.is_none() {
-                return Err(AppError::new("jito api url not provide"));
+            if let Some((tip_account, tip)) = jito_tip.filter(|_| with_tip) {
+                tx_ixs.push(system_instruction::transfer(
+                    &wallet_pubkey,
+                    &tip_account,
+                    tip,
+                ));
             }
-            let jito_url = self.jito_url.clone().unwrap();
-            let jito_client = JitoRpcClient {
-                http_client: proxied_http_client.clone(),
-                base_url: jito_url,
+
+            let tx_msg =
+                Message::try_compile(&wallet_pubkey, &tx_ixs, &addr_loopup_tb, recent_blockhash)?;
+            let tx_msg = VersionedMessage::V0(tx_msg);
+            let tx = VersionedTransaction::try_new(tx_msg, &[wallet_keypair])?;
+            Ok::<_, AppError>(tx)
+        };
+
+        let (recent_blockhash, last_valid_block_height) =
+            chain_cache.get_latest_blockhash().await?;
+
+        let simulate_tx = build_tx(MAX_COMPUTE_UNIT_LIMIT, recent_blockhash, true)?;
+        let simulate_result = rpc_client.simulate_tx(&simulate_tx).await?;
+        if let Some(err) = simulate_result.err {
+            let detail = TradeEventDetail {
+                logs: simulate_result.logs,
+                ..Default::default()
             };
-            let tx_bytes = bincode::serialize(&tx)?;
-            let base58_tx = bs58::encode(tx_bytes).into_string();
-            jito_client.send_bundle(&[base58_tx]).await?;
-        } else {
-            rpc_client.send_transaction(&tx).await?;
+            let evt_msg = format!("simulate transaction failed, error is: {err}");
+            self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            return Err(AppError::new("simulate failed, skip this trade ......").into());
         }
 
-        let evt_msg = "transaction has been send, confirming now ...";
-        self.send_worker_msg_to_win(msg_kind, evt_msg);
+        let units_consumed = simulate_result
+            .units_consumed
+            .unwrap_or(DEFAULT_COMPUTE_UNIT_LIMIT as u64);
+        let margin_pct = self.compute_unit_margin_pct as u64;
+        let unit_limit =
+            (units_consumed * (100 + margin_pct) / 100).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32;
+        debug!("simulated units consumed: {units_consumed}, set compute unit limit: {unit_limit}");
 
-        let start_time = Instant::now();
-        loop {
-            tokio::time::sleep(Duration::from_secs(2)).await;
-            let results: Vec<_> = rpc_client
-                .get_signature_statuses_with_history(&[*txid])
-                .await?
-                .value;
-            let result = results[0].clone();
-            if result.is_none() {
-                if start_time.elapsed().as_secs() > 120 {
-                    let evt_msg = "transaction was dropped, please increase priority fee ...";
-                    self.send_worker_msg_to_win(msg_kind, evt_msg);
-                    break;
+        let sent_err = |err: AppError| match &jito_client {
+            Some(client) if client.is_rejected() => SolSendError::JitoRejected(err),
+            _ => SolSendError::Other(err),
+        };
+        let mut endpoints = vec![];
+        let collector = self.jito_bundle_collector.as_ref().filter(|_| via_jito);
+        let (tx, is_tipped) = match (collector, &jito_client) {
+            (Some(collector), Some(client)) => {
+                let entry = JitoBundleEntry {
+                    tx: build_tx(unit_limit, recent_blockhash, false)?,
+                    tipped_tx: build_tx(unit_limit, recent_blockhash, true)?,
+                };
+                let evt_msg = "waiting for swaps of other wallets to bundle ...";
+                self.send_worker_msg_to_win(msg_kind, evt_msg);
+                // NOTE: the worker which sent the bundle tells the others if it was rejected
+                let sent = collector.join(client, entry.clone()).await?;
+                let (tx, is_tipped) = sent
+                    .tx_of(&entry)
+                    .ok_or(AppError::new("transaction not found in the sent bundle"))?;
+                let evt_msg = format!(
+                    "bundle {} of {} wallets sent to {}",
+                    sent.bundle.id,
+                    sent.txs.len(),
+                    sent.bundle.region
+                );
+                self.send_worker_msg_to_win(msg_kind, evt_msg);
+
+                // NOTE: a bundled transaction sent alone through rpc would land without the others
+                let submitter = JitoBundleSubmitter::new(client.clone(), sent, is_tipped);
+                endpoints.push(SolEndpoint {
+                    name: "jito block engine".to_string(),
+                    submitter: Arc::new(submitter),
+                });
+                (tx, is_tipped)
+            }
+            _ => {
+                if let Some(client) = &jito_client {
+                    endpoints.push(SolEndpoint {
+                        name: "jito block engine".to_string(),
+                        submitter: client.clone(),
+                    });
                 } else {
-                    // still pendding status
-                    debug!("transaction {txid} still pendding...");
-                    continue;
+                    endpoints.push(SolEndpoint {
+                        name: "main rpc".to_string(),
+                        submitter: rpc_client.clone(),
+                    });
                 }
-            } else {
-                let status = result.unwrap();
-                match status.err {
-                    Some(err) => {
-                        let evt_msg = format!("transaction landed but failed, error is: {err}");
-                        self.send_worker_msg_to_win(msg_kind, evt_msg);
-                    }
-                    None => {
-                        let evt_msg = "transaction landed and successed !!!";
-                        self.send_worker_msg_to_win(msg_kind, evt_msg);
-                    }
+                let send_rpc_clients = self.app_handle.state::<SolSendRpcClientsState>();
+                for (url, client) in send_rpc_clients.read().await.iter() {
+                    endpoints.push(SolEndpoint {
+                        name: url.clone(),
+                        submitter: client.clone(),
+                    });
+                }
+                (build_tx(unit_limit, recent_blockhash, true)?, true)
+            }
+        };
+        let txid = tx.get_signature();
+        self.send_worker_msg_to_win(msg_kind, format!("created transaction {txid}"));
+        record.txid = Some(txid.to_string());
+
+        let endpoint_stats = self.app_handle.state::<SolEndpointStatsState>();
+        let submitter = SolFanoutSubmitter::new(endpoints, endpoint_stats.inner().clone());
+
+        let evt_msg = "sending transaction and confirming now ...";
+        self.send_worker_msg_to_win(msg_kind, evt_msg);
+
+        let sender = self.app_handle.read_sol_tx_sender().await?;
+        let outcome = sender
+            .send_and_confirm(&submitter, &tx, last_valid_block_height)
+            .await
+            .map_err(sent_err)?;
+
+        let mut bundle = None;
+        if let Some(jito_client) = jito_client {
+            bundle = match jito_client.refresh_bundle().await {
+                Ok(bundle) => bundle,
+                Err(err) => {
+                    warn!("refresh jito bundle error: {}", err.err_msg);
+                    jito_client.bundle()
                 }
-                break;
+            };
+        }
+        let detail = TradeEventDetail {
+            txid: Some(txid.to_string()),
+            priority_fee: Some(unit_price),
+            compute_unit_limit: Some(unit_limit),
+            jito_tip: jito_tip.filter(|_| is_tipped).map(|(_, tip)| tip),
+            route_plan,
+            bundle_id: bundle.as_ref().map(|bundle| bundle.id.clone()),
+            bundle_state: bundle.as_ref().map(|bundle| bundle.state),
+            ..Default::default()
+        };
+        let tx_fee = SOL_TX_BASE_FEE as u128 * tx.signatures.len() as u128
+            + (unit_price as u128 * unit_limit as u128).div_ceil(1_000_000);
+        let tip = jito_tip.filter(|_| is_tipped).map(|(_, tip)| tip);
+        let fees = tx_fee + tip.unwrap_or_default() as u128;
+        match outcome {
+            SolTxOutcome::Landed => self.trade_stats.record_trade(fees, native_notional),
+            SolTxOutcome::Failed(_) => self.trade_stats.record_trade(fees, 0),
+            SolTxOutcome::Expired | SolTxOutcome::Dropped => {}
+        }
+        record.tip = tip;
+        record.bundle_id = detail.bundle_id.clone();
+        record.bundle_state = detail.bundle_state;
+        record.status = match &outcome {
+            SolTxOutcome::Landed => TradeStatus::Success,
+            SolTxOutcome::Failed(_) => TradeStatus::Failed,
+            SolTxOutcome::Expired => TradeStatus::Expired,
+            SolTxOutcome::Dropped => TradeStatus::Dropped,
+        };
+        if let SolTxOutcome::Failed(err) = &outcome {
+            record.error = Some(err.to_string());
+        }
+        // NOTE: only a landed transaction is charged
+        if matches!(outcome, SolTxOutcome::Landed | SolTxOutcome::Failed(_)) {
+            record.fee = Some(tx_fee.to_string());
+        }
+        match outcome {
+            SolTxOutcome::Expired => {
+                self.dropped_cnt.fetch_add(1, Ordering::Relaxed);
+                let evt_msg = "transaction expired before landed, please increase priority fee ...";
+                self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            }
+            SolTxOutcome::Dropped => {
+                self.dropped_cnt.fetch_add(1, Ordering::Relaxed);
+                let evt_msg = match &bundle {
+                    Some(bundle) => format!(
+                        "bundle {} is {}, transaction won't land, please increase tip ...",
+                        bundle.id, bundle.state
+                    ),
+                    None => "transaction dropped before landed ...".to_string(),
+                };
+                self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            }
+            SolTxOutcome::Failed(err) => {
+                self.dropped_cnt.store(0, Ordering::Relaxed);
+                let evt_msg = format!("transaction landed but failed, error is: {err}");
+                self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+            }
+            SolTxOutcome::Landed => {
+                self.dropped_cnt.store(0, Ordering::Relaxed);
+                let evt_msg = "transaction landed and successed !!!";
+                self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
             }
         }
 
         Ok(())
     }
 
-    async fn evm_execute(&self, selected_key: &[u8]) -> Result<(), AppError> {
+    /// `record` is set once the trade is chosen and filled as it goes
+    async fn evm_execute(
+        &self,
+        selected_key: &[u8],
+        record: &mut Option<TradeRecord>,
+    ) -> Result<(), AppError> {
         let chain_config = self.chain.evm_chain_config().unwrap();
         let rpc_client = self.app_handle.read_evm_rpc_client().await?;
         let wallet_signer = PrivateKeySigner::from_slice(selected_key)?;
@@ -357,6 +742,10 @@ impl Worker {
             .with_recommended_fillers()
             .wallet(EthereumWallet::from(wallet_signer))
             .on_client(rpc_client);
+        let evm_tx_sender = self
+            .app_handle
+            .read_evm_tx_sender(self.evm_tx_config)
+            .await?;
 
         let token_address = Address::from_str(&self.token.addr)?;
         let balance_wei = rpc_provider.get_balance(wallet_address).await?;
@@ -390,68 +779,116 @@ impl Worker {
         let msg_kind = TradeTaskEventType::Executed;
         let evt_msg = format!("choose account {wallet_address} {msg}",);
         self.send_worker_msg_to_win(msg_kind, evt_msg);
+        let record = record.insert(TradeRecord::new(
+            &self.task_id,
+            self.id,
+            wallet_address.to_string(),
+            trade_direction,
+            input_token_addr.to_string(),
+            output_token_addr.to_string(),
+            input_amount.to_string(),
+        ));
 
         if input_amount.is_zero() {
             return Err(AppError::new("input amount too small, skip it..."));
         }
 
-        if input_token_addr == token_address {
-            let allowance = token_contract
-                .allowance(wallet_address, chain_config.one_inch_router_addr)
-                .call()
-                .await?
-                ._0;
-
-            if allowance < input_amount {
-                let evt_msg = "allowance not enough, adjust it ....";
-                self.send_worker_msg_to_win(msg_kind, evt_msg);
-
-                let receipt = token_contract
-                    .approve(chain_config.one_inch_router_addr, U256::MAX)
-                    .send()
-                    .await?
-                    .get_receipt()
-                    .await?;
-                if !receipt.status() {
-                    return Err(AppError::new("adjust allowance failed ...."));
-                }
-                let approve_txid = receipt.transaction_hash;
-                let evt_msg = format!("adjust allowance successed, tx: {approve_txid}");
-                self.send_worker_msg_to_win(msg_kind, evt_msg);
-            }
-        }
-
         let proxied_http_client = self.app_handle.get_proxied_http_client().await?;
-
         let proxy_url = proxied_http_client.url;
-        self.send_worker_msg_to_win(
-            msg_kind,
-            format!("use proxy: {} to request 1inch", proxy_url),
-        );
-
         let proxied_http_client = proxied_http_client.client;
-        let swap_query_params = SwapQueryParams {
-            src: input_token_addr,
-            dst: output_token_addr,
+        record.proxy = Some(proxy_url.clone());
+
+        let swap_req = AggSwapReq {
+            input_token: input_token_addr,
+            output_token: output_token_addr,
             amount: input_amount,
-            from: wallet_address,
-            origin: wallet_address,
             slippage: self.slippage,
+            user: wallet_address,
         };
+        let evm_provider = self.app_handle.read_evm_provider().await?;
+        let mut swap_result = Err(AppError::new("no aggregator configured"));
+        for agg_api in &self.aggregators {
+            let aggregator = agg_api.build_evm(
+                proxied_http_client.clone(),
+                evm_provider.clone(),
+                self.chain,
+                &self.one_inch_route_config,
+                self.agg_rate_limits.clone(),
+            )?;
+            let kind = aggregator.kind();
+            if let Some(remaining) = self.agg_rate_limits.remaining(kind) {
+                let err_msg = format!("{kind} rate limited for {}s", remaining.as_secs());
+                let evt_msg = format!("{err_msg}, try next aggregator");
+                self.send_worker_msg_to_win(msg_kind, evt_msg);
+                swap_result = Err(AppError::new(err_msg));
+                continue;
+            }
+            self.send_worker_msg_to_win(
+                msg_kind,
+                format!("use proxy: {proxy_url} to request {kind}"),
+            );
 
-        let api_key = self.agg_api_key.clone().unwrap_or_default();
-        let chain_id: u64 = chain_config.named_chain.into();
-        let resp = one_inch::get_swap_data(
-            &proxied_http_client,
-            &self.agg_api_url,
-            &api_key,
-            chain_id,
-            swap_query_params,
-        )
-        .await?;
+            swap_result = async {
+                let spender = aggregator
+                    .spender()
+                    .filter(|_| input_token_addr == token_address);
+                if let Some(spender) = spender {
+                    let allowance = token_contract
+                        .allowance(wallet_address, spender)
+                        .call()
+                        .await?
+                        ._0;
+
+                    if allowance < input_amount {
+                        let evt_msg = "allowance not enough, adjust it ....";
+                        self.send_worker_msg_to_win(msg_kind, evt_msg);
 
-        let total_fee = resp.tx.gas_price * U256::from(resp.tx.gas);
-        let total_need = total_fee + resp.tx.value;
+                        let approve_tx = token_contract
+                            .approve(spender, U256::MAX)
+                            .from(wallet_address)
+                            .into_transaction_request();
+                        let fees = self.evm_fee_mode.estimate(&rpc_provider).await?;
+                        let receipt = evm_tx_sender
+                            .send_and_wait(&rpc_provider, approve_tx, fees)
+                            .await?
+                            .receipt;
+                        let approve_fees =
+                            receipt.gas_used.saturating_mul(receipt.effective_gas_price);
+                        self.trade_stats.record_fees(approve_fees);
+                        if !receipt.status() {
+                            return Err(AppError::new("adjust allowance failed ...."));
+                        }
+                        let approve_txid = receipt.transaction_hash;
+                        let evt_msg = format!("adjust allowance successed, tx: {approve_txid}");
+                        self.send_worker_msg_to_win(msg_kind, evt_msg);
+                    }
+                }
+
+                aggregator.quote_swap(&swap_req).await
+            }
+            .await;
+            match &swap_result {
+                Ok(_) => break,
+                Err(err) => {
+                    let evt_msg =
+                        format!("{kind} swap failed: {}, try next aggregator", err.err_msg);
+                    self.send_worker_msg_to_win(msg_kind, evt_msg);
+                }
+            }
+        }
+        let (quote, evm_swap) = swap_result?;
+        record.aggregator = Some(quote.aggregator.to_string());
+        record.quoted_output = Some(quote.out_amount.to_string());
+        record.route_plan = quote.route_plan();
+        let evt_msg = format!(
+            "{} quote out amount: {}",
+            quote.aggregator, quote.out_amount
+        );
+        self.send_worker_msg_to_win(msg_kind, evt_msg);
+
+        let fees = self.evm_fee_mode.estimate(&rpc_provider).await?;
+        let total_fee = U256::from(fees.max_gas_price()) * U256::from(evm_swap.gas);
+        let total_need = total_fee + evm_swap.tx.value.unwrap_or_default();
         let balance_eth = format_ether(balance_wei);
         let total_need_eth = format_ether(total_need);
         if balance_wei < total_need {
@@ -461,22 +898,64 @@ impl Worker {
             )));
         }
 
-        let tx_req: TransactionRequest = resp.tx.try_into()?;
-        let receipt = rpc_provider
-            .send_transaction(tx_req)
-            .await?
-            .get_receipt()
+        let confirmed_tx = evm_tx_sender
+            .send_and_wait(&rpc_provider, evm_swap.tx, fees)
             .await?;
 
+        let receipt = confirmed_tx.receipt;
         let tx_hash = receipt.transaction_hash;
+        let tx_fees = receipt.gas_used.saturating_mul(receipt.effective_gas_price);
+        record.txid = Some(tx_hash.to_string());
+        record.fee = Some(tx_fees.to_string());
+        record.confirmations = Some(confirmed_tx.confirmations);
+        record.reorg_cnt = Some(confirmed_tx.reorg_cnt);
+        record.status = if receipt.status() {
+            TradeStatus::Success
+        } else {
+            TradeStatus::Failed
+        };
+        if receipt.status() {
+            let native_notional = match trade_direction {
+                TradeDirection::Buy => input_amount,
+                TradeDirection::Sell => quote.out_amount,
+            };
+            self.trade_stats
+                .record_trade(tx_fees, native_notional.saturating_to::<u128>());
+        } else {
+            self.trade_stats.record_trade(tx_fees, 0);
+        }
+        let detail = TradeEventDetail {
+            txid: Some(tx_hash.to_string()),
+            confirmations: Some(confirmed_tx.confirmations),
+            reorg_cnt: Some(confirmed_tx.reorg_cnt),
+            ..Default::default()
+        };
 
         if !receipt.status() {
+            let evt_msg = format!("swap transaction failed .... tx_id: {tx_hash}");
+            self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
             return Err(AppError::new(format!(
                 "swap transaction failed .... tx_id: {tx_hash}"
             )));
         }
 
-        self.send_worker_msg_to_win(msg_kind, format!("swap successed ... txhash: {tx_hash}"));
+        let evt_msg = format!(
+            "swap successed with {} confirmations ... txhash: {tx_hash}",
+            confirmed_tx.confirmations
+        );
+        self.send_worker_detail_to_win(msg_kind, evt_msg, detail);
+
+        let realized = EvmBalanceChanges::fetch(
+            &rpc_provider,
+            &receipt,
+            wallet_address,
+            token_address,
+            chain_config.wrapped_native_addr,
+            chain_config.is_op_stack,
+        )
+        .await
+        .map(|changes| RealizedSwap::from_evm(trade_direction, &changes));
+        self.report_realized(record, realized, quote.out_amount);
 
         Ok(())
     }
@@ -490,12 +969,192 @@ impl Worker {
         // if all worker stopped, mark task stopped
         if task.running_workers == 0 {
             task.task_state = TaskState::Stopped;
-            let evt_msg = format!("task {} stop successed", self.task_id);
+            let evt_msg = match &task.stop_reason {
+                Some(reason) => format!("task {} stop successed, {reason}", self.task_id),
+                None => format!("task {} stop successed", self.task_id),
+            };
             self.send_task_msg_to_win(TradeTaskEventType::Stopped, evt_msg);
             debug!("stopped task: {}", self.task_id);
         }
     }
 
+    /// Attach the realized amounts of a landed swap to its record and report them
+    fn report_realized(
+        &self,
+        record: &mut TradeRecord,
+        realized: Result<RealizedSwap, AppError>,
+        quoted_output: U256,
+    ) {
+        let msg_kind = TradeTaskEventType::Executed;
+        let realized = match realized {
+            Ok(realized) => realized,
+            Err(err) => {
+                let evt_msg = format!("fetch realized amounts failed: {}", err.err_msg);
+                self.send_worker_msg_to_win(msg_kind, evt_msg);
+                return;
+            }
+        };
+        let native_decimals = self.chain.native_decimals();
+        let token_decimals = self.token.decimals;
+        let effective_price =
+            realized.effective_price(record.direction, native_decimals, token_decimals);
+        let slippage_bps = realized.slippage_bps(quoted_output);
+        record.realized_input = Some(realized.input.to_string());
+        record.realized_output = Some(realized.output.to_string());
+        record.effective_price = effective_price;
+        record.slippage_bps = slippage_bps;
+        record.total_fee = Some(realized.fee.to_string());
+
+        let (input_decimals, output_decimals) = match record.direction {
+            TradeDirection::Buy => (native_decimals, token_decimals),
+            TradeDirection::Sell => (token_decimals, native_decimals),
+        };
+        let input_ui = format_units(realized.input, input_decimals).unwrap_or_default();
+        let output_ui = format_units(realized.output, output_decimals).unwrap_or_default();
+        let fee_ui = format_units(realized.fee, native_decimals).unwrap_or_default();
+        let mut evt_msg = format!("realized input {input_ui}, output {output_ui}, fee {fee_ui}");
+        if let Some(price) = effective_price {
+            evt_msg.push_str(&format!(", price {price}"));
+        }
+        if let Some(bps) = slippage_bps {
+            evt_msg.push_str(&format!(", slippage {bps:.2} bps"));
+        }
+        self.send_worker_msg_to_win(msg_kind, evt_msg);
+    }
+
+    /// Write the attempt to the ledger of the project when one is open
+    async fn write_trade_record(&self, mut record: TradeRecord, result: &Result<(), AppError>) {
+        record.finished_at = Utc::now().timestamp_millis();
+        if let Err(err) = result {
+            record.error.get_or_insert_with(|| err.err_msg.clone());
+        }
+        let ledger_state = self.app_handle.state::<TradeLedgerState>();
+        let Some(ledger) = ledger_state.read().await.clone() else {
+            return;
+        };
+        let inserted = tokio::task::spawn_blocking(move || ledger.insert(&record)).await;
+        if let Err(err) = inserted.map_err(AppError::from).and_then(|it| it) {
+            warn!(
+                "write trade record of task {} error: {}",
+                self.task_id, err.err_msg
+            );
+        }
+    }
+
+    /// Reason of the first stop condition of the task which is hit
+    async fn stop_condition_hit(&self) -> Option<String> {
+        let totals = self.trade_stats.totals();
+        if let Some(reason) = self.stop_conditions.check_totals(self.chain, &totals) {
+            return Some(reason);
+        }
+        if !self.stop_conditions.has_balance_conditions()
+            || !self.trade_stats.should_check_balances()
+        {
+            return None;
+        }
+
+        match self.fetch_stop_balances().await {
+            Ok((main_wallet_bal, group_bal)) => {
+                self.stop_conditions
+                    .check_balances(self.chain, main_wallet_bal, group_bal)
+            }
+            Err(err) => {
+                warn!(
+                    "check balances of task {} error: {}",
+                    self.task_id, err.err_msg
+                );
+                None
+            }
+        }
+    }
+
+    async fn fetch_stop_balances(&self) -> Result<(Option<u128>, Option<u128>), AppError> {
+        let main_wallet_bal = match self.stop_conditions.min_main_wallet_balance {
+            Some(_) => {
+                let addrs = [self.main_wallet_addr.clone()];
+                Some(self.native_balance(&addrs).await?)
+            }
+            None => None,
+        };
+        let group_bal = match self.stop_conditions.min_group_balance {
+            Some(_) => {
+                let addrs = self.wallet_addrs().await?;
+                Some(self.native_balance(&addrs).await?)
+            }
+            None => None,
+        };
+
+        Ok((main_wallet_bal, group_bal))
+    }
+
+    /// Addresses of every wallet of the task
+    async fn wallet_addrs(&self) -> Result<Vec<String>, AppError> {
+        let task_state = self.app_handle.state::<TradeTaskState>();
+        let guard = task_state.read().await;
+        let task = guard
+            .get(&self.task_id)
+            .ok_or_else(|| AppError::new(format!("no task {} found", self.task_id)))?;
+        task.wallet_states
+            .keys()
+            .map(|pk| utils::pk_to_addr(self.chain, pk))
+            .collect()
+    }
+
+    /// Sum of the native balances of the addresses in the smallest unit
+    async fn native_balance(&self, addrs: &[String]) -> Result<u128, AppError> {
+        let mut total = 0u128;
+        match self.chain {
+            Chain::Solana => {
+                let rpc_client = self.app_handle.read_sol_rpc_client().await?;
+                let pubkeys = addrs
+                    .iter()
+                    .map(|addr| Pubkey::from_str(addr))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // NOTE: `getMultipleAccounts` takes at most 100 accounts
+                for chunk in pubkeys.chunks(100) {
+                    let accounts = rpc_client.get_multiple_accounts(chunk).await?;
+                    for account in accounts.into_iter().flatten() {
+                        total += account.lamports as u128;
+                    }
+                }
+            }
+            Chain::Base | Chain::Bsc => {
+                let provider = self.app_handle.read_evm_provider().await?;
+                let provider = &provider;
+                let addresses = addrs
+                    .iter()
+                    .map(|addr| Address::from_str(addr))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let balances = join_all(
+                    addresses
+                        .into_iter()
+                        .map(|address| async move { provider.get_balance(address).await }),
+                )
+                .await;
+                for balance in balances {
+                    total = total.saturating_add(balance?.saturating_to::<u128>());
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Stop the task when it is running because a stop condition is hit
+    async fn stop_for(&self, reason: String) {
+        let tasks_state = self.app_handle.state::<TradeTaskState>();
+        let mut guard = tasks_state.write().await;
+        let Some(task) = guard.get_mut(&self.task_id) else {
+            return;
+        };
+        if task.task_state == TaskState::Running {
+            task.task_state = TaskState::Stopping;
+            let evt_msg = format!("task {} stopping, {reason}", self.task_id);
+            self.send_task_msg_to_win(TradeTaskEventType::Executed, evt_msg);
+            task.stop_reason = Some(reason);
+        }
+    }
+
     async fn take_private_key(&self) -> Result<Option<PrivateKey>, AppError> {
         let task_state = self.app_handle.state::<TradeTaskState>();
         let mut guard = task_state.write().await;
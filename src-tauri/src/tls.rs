@@ -0,0 +1,102 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Custom CA roots of the http rpc clients of a project, for self-hosted rpc endpoints
+///
+/// There is no certificate pinning, exclusive CA roots are the closest to it.
+/// Aggregator api and jito block engine clients, and the rpc websocket of
+/// transaction confirmations, always verify against the built-in root certificates.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TlsConfig {
+    /// PEM files of CA roots trusted in addition to the built-in root certificates
+    pub ca_cert_paths: Vec<String>,
+    /// PEM files of CA roots trusted instead of the built-in root certificates,
+    /// so rpc endpoints must present a certificate issued by one of them
+    pub exclusive_ca_cert_paths: Vec<String>,
+    /// Skip certificate verification, only for debugging against a trusted proxy
+    pub accept_invalid_certs: bool,
+}
+
+/// Certificates loaded from [`TlsConfig`], read once when the project is opened
+#[derive(Debug, Clone, Default)]
+pub struct LoadedTlsConfig {
+    ca_certs: Vec<Vec<u8>>,
+    exclusive_ca_certs: Vec<Vec<u8>>,
+    accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    pub async fn load(&self) -> Result<LoadedTlsConfig, AppError> {
+        let mut ca_certs = vec![];
+        for path in &self.ca_cert_paths {
+            let pem = tokio::fs::read(path)
+                .await
+                .map_err(|err| AppError::new(format!("read ca cert {path} error: {err}")))?;
+            ca_certs.push(pem);
+        }
+
+        let mut exclusive_ca_certs = vec![];
+        for path in &self.exclusive_ca_cert_paths {
+            let pem = tokio::fs::read(path)
+                .await
+                .map_err(|err| AppError::new(format!("read ca cert {path} error: {err}")))?;
+            exclusive_ca_certs.push(pem);
+        }
+
+        if self.accept_invalid_certs {
+            warn!("tls certificate verification is disabled, connections are open to MITM");
+        }
+
+        Ok(LoadedTlsConfig {
+            ca_certs,
+            exclusive_ca_certs,
+            accept_invalid_certs: self.accept_invalid_certs,
+        })
+    }
+}
+
+impl LoadedTlsConfig {
+    /// Only for the builders of rpc clients
+    pub fn apply(
+        &self,
+        builder: reqwest::ClientBuilder,
+    ) -> Result<reqwest::ClientBuilder, AppError> {
+        let mut builder = builder;
+        for pem in self.ca_certs.iter().chain(self.exclusive_ca_certs.iter()) {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+        }
+
+        if !self.exclusive_ca_certs.is_empty() {
+            builder = builder.tls_built_in_root_certs(false);
+        }
+
+        if self.accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder)
+    }
+
+    /// Same as [`LoadedTlsConfig::apply`], for the reqwest 0.11 client used by solana rpc
+    pub fn apply_11(
+        &self,
+        builder: reqwest_11::ClientBuilder,
+    ) -> Result<reqwest_11::ClientBuilder, AppError> {
+        let mut builder = builder;
+        for pem in self.ca_certs.iter().chain(self.exclusive_ca_certs.iter()) {
+            builder = builder.add_root_certificate(reqwest_11::Certificate::from_pem(pem)?);
+        }
+
+        if !self.exclusive_ca_certs.is_empty() {
+            builder = builder.tls_built_in_root_certs(false);
+        }
+
+        if self.accept_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder)
+    }
+}
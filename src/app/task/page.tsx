@@ -19,8 +19,9 @@ import {
 import { Event, UnlistenFn } from "@tauri-apps/api/event";
 import _ from "lodash";
 import dayjs from "dayjs";
+import TradeRecordsPanel from "@/components/task/TradeRecords";
 
-type TradeTaskEventType = "Executed" | "Stopped";
+type TradeTaskEventType = "Started" | "Executed" | "Stopping" | "Stopped";
 
 type TradeTaskEventPayload = {
   task_id: string;
@@ -107,6 +108,10 @@ export default function TaskPage() {
               if (data.payload.kind === "Stopped") {
                 setStopping(false);
                 setRunning(false);
+              } else if (data.payload.kind === "Stopping") {
+                setStopping(true);
+              } else if (data.payload.kind === "Started") {
+                setRunning(true);
               }
               return;
             }
@@ -195,6 +200,9 @@ export default function TaskPage() {
           isVertical={_.keys(workerLogs).length > 10}
           className="max-h-[calc(100vh-5rem)] overflow-y-auto scrollbar-hide"
         >
+          <Tab key="trades" title={<div className="pr-3">Trades</div>}>
+            <TradeRecordsPanel taskId={_walletGrpId} />
+          </Tab>
           {_.map(workerLogs, (logs, idx) => (
             <Tab
               key={idx}
@@ -1,3 +1,5 @@
+import dayjs from "dayjs";
+
 export function abbr(val: string, partLen: number) {
   return `${val.substring(0, partLen)}...${val.substring(val.length - partLen)}`;
 }
@@ -5,3 +7,12 @@ export function abbr(val: string, partLen: number) {
 export async function sleep(ms: number) {
   await new Promise((resolve) => setTimeout(() => resolve(0), ms));
 }
+
+// value of a datetime-local input from a unix timestamp in seconds, in local time
+export function toDatetimeInput(ts?: number) {
+  return ts === undefined ? "" : dayjs.unix(ts).format("YYYY-MM-DDTHH:mm");
+}
+
+export function fromDatetimeInput(val: string) {
+  return val ? dayjs(val).unix() : undefined;
+}
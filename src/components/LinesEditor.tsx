@@ -0,0 +1,35 @@
+import { Textarea } from "@nextui-org/react";
+import { useState } from "react";
+
+export default function LinesEditor({
+  value,
+  placeholder,
+  onChange,
+}: {
+  value: string[];
+  placeholder?: string;
+  onChange: (lines: string[]) => void;
+}) {
+  const [textContent, setTextContent] = useState(value.join("\n"));
+
+  const onTextAreaChange = (content: string) => {
+    setTextContent(content);
+
+    const lines = content
+      .split("\n")
+      .map((it) => it.trim())
+      .filter((it) => it.length > 0);
+
+    onChange(lines);
+  };
+
+  return (
+    <Textarea
+      minRows={2}
+      maxRows={5}
+      placeholder={placeholder}
+      value={textContent}
+      onValueChange={onTextAreaChange}
+    />
+  );
+}
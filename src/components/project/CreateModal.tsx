@@ -37,7 +37,7 @@ export default function CreateProjectModal({
   } = useForm<CreateProjectReq>({
     defaultValues: {
       chain: "Solana",
-      jito_url: "https://tokyo.mainnet.block-engine.jito.wtf",
+      jito_urls: JITO_BLOCK_ENGINE_URLS.map((item) => item.value),
       proxy_urls: [],
     },
   });
@@ -135,17 +135,29 @@ export default function CreateProjectModal({
                   </FormItem>
                   {selectedChain === "Solana" && (
                     <FormItem
-                      label={<div className={labelClassName}>Jito Api Url</div>}
-                      error={errors.jito_url}
+                      label={<div className={labelClassName}>Jito Regions</div>}
+                      error={errors.jito_urls}
                     >
-                      <Select
-                        aria-label="Jito Api Url"
-                        {...register("jito_url")}
-                      >
-                        {JITO_BLOCK_ENGINE_URLS.map((item) => (
-                          <SelectItem key={item.value}>{item.name}</SelectItem>
-                        ))}
-                      </Select>
+                      <Controller
+                        control={control}
+                        name="jito_urls"
+                        render={({ field: { value, onChange } }) => (
+                          <Select
+                            aria-label="Jito Regions"
+                            selectionMode="multiple"
+                            selectedKeys={value ?? []}
+                            onSelectionChange={(keys) =>
+                              onChange(Array.from(keys as Set<string>))
+                            }
+                          >
+                            {JITO_BLOCK_ENGINE_URLS.map((item) => (
+                              <SelectItem key={item.value}>
+                                {item.name}
+                              </SelectItem>
+                            ))}
+                          </Select>
+                        )}
+                      />
                     </FormItem>
                   )}
                   <FormItem
@@ -1,7 +1,12 @@
-import { JITO_BLOCK_ENGINE_URLS, VALID_URL_PATTERN } from "@/consts";
+import {
+  JITO_BLOCK_ENGINE_URLS,
+  VALID_URL_PATTERN,
+  VALID_WS_URL_PATTERN,
+} from "@/consts";
 import { ProjectResp, UpdateProjectReq, useUpdateProjectCmd } from "@/hooks";
 import {
   Button,
+  Input,
   Modal,
   ModalBody,
   ModalContent,
@@ -12,8 +17,10 @@ import {
 } from "@nextui-org/react";
 import { useMemo } from "react";
 import { Controller, useForm } from "react-hook-form";
+import { NumericFormat } from "react-number-format";
 import { toast } from "react-hot-toast";
 import FormItem from "../FormItem";
+import LinesEditor from "../LinesEditor";
 import ProxyEditor from "../ProxyEditor";
 import TextInput from "../TextInput";
 import { useProject } from "./Provider";
@@ -43,7 +50,10 @@ export default function EditProjectModal({
   } = useForm<UpdateProjectReq>({
     defaultValues: {
       rpc_url: project.rpc,
-      jito_url: project.jito_url,
+      rpc_ws_url: project.rpc_ws_url ?? "",
+      send_rpc_urls: project.send_rpc_urls,
+      evm_confirmations: project.evm_confirmations,
+      jito_urls: project.jito_urls,
       proxy_urls: project.proxy_urls,
       agg_api_url: project.agg_api_url,
       agg_api_key: project.agg_api_key,
@@ -115,17 +125,71 @@ export default function EditProjectModal({
                   </FormItem>
                   {project.chain === "Solana" && (
                     <FormItem
-                      label={<div className={labelClassName}>Jito Api Url</div>}
-                      error={errors.jito_url}
+                      label={<div className={labelClassName}>Rpc Ws Url</div>}
+                      error={errors.rpc_ws_url}
                     >
-                      <Select
-                        aria-label="Jito Api Url"
-                        {...register("jito_url")}
-                      >
-                        {JITO_BLOCK_ENGINE_URLS.map((item) => (
-                          <SelectItem key={item.value}>{item.name}</SelectItem>
-                        ))}
-                      </Select>
+                      <TextInput
+                        placeholder="Rpc Websocket Url, transactions are confirmed by polling when empty"
+                        {...register("rpc_ws_url", {
+                          pattern: {
+                            value: VALID_WS_URL_PATTERN,
+                            message: "Not a valid websocket url",
+                          },
+                        })}
+                      />
+                    </FormItem>
+                  )}
+                  {project.chain === "Solana" && (
+                    <FormItem
+                      label={
+                        <div className={labelClassName}>Send Rpc Urls</div>
+                      }
+                      error={errors.send_rpc_urls}
+                    >
+                      <Controller
+                        control={control}
+                        name="send_rpc_urls"
+                        rules={{
+                          validate: (urls) =>
+                            (urls ?? []).every((url) =>
+                              VALID_URL_PATTERN.test(url),
+                            ) || "Not a valid url",
+                        }}
+                        render={({ field: { value, onChange } }) => (
+                          <LinesEditor
+                            placeholder="Extra Rpc Urls transactions are also sent to, one per line"
+                            value={value ?? []}
+                            onChange={onChange}
+                          />
+                        )}
+                      />
+                    </FormItem>
+                  )}
+                  {project.chain === "Solana" && (
+                    <FormItem
+                      label={<div className={labelClassName}>Jito Regions</div>}
+                      error={errors.jito_urls}
+                    >
+                      <Controller
+                        control={control}
+                        name="jito_urls"
+                        render={({ field: { value, onChange } }) => (
+                          <Select
+                            aria-label="Jito Regions"
+                            selectionMode="multiple"
+                            selectedKeys={value ?? []}
+                            onSelectionChange={(keys) =>
+                              onChange(Array.from(keys as Set<string>))
+                            }
+                          >
+                            {JITO_BLOCK_ENGINE_URLS.map((item) => (
+                              <SelectItem key={item.value}>
+                                {item.name}
+                              </SelectItem>
+                            ))}
+                          </Select>
+                        )}
+                      />
                     </FormItem>
                   )}
 
@@ -164,6 +228,32 @@ export default function EditProjectModal({
                       />
                     </FormItem>
                   )}
+                  {["Base", "Bsc"].indexOf(project.chain) >= 0 && (
+                    <FormItem
+                      label={
+                        <div className={labelClassName}>Confirmations</div>
+                      }
+                      error={errors.evm_confirmations}
+                    >
+                      <Controller
+                        control={control}
+                        name="evm_confirmations"
+                        render={({ field }) => (
+                          <NumericFormat
+                            aria-label="Confirmations"
+                            placeholder="Blocks on top of a transaction before it is final"
+                            customInput={Input}
+                            decimalScale={0}
+                            allowNegative={false}
+                            value={field.value ?? ""}
+                            onValueChange={(v) =>
+                              field.onChange(v.floatValue ?? null)
+                            }
+                          />
+                        )}
+                      />
+                    </FormItem>
+                  )}
                   <Controller
                     control={control}
                     name="proxy_urls"
@@ -0,0 +1,44 @@
+import { useGetSolEndpointStatsCmd } from "@/hooks/chain";
+import { useEffect } from "react";
+import { MdRefresh } from "react-icons/md";
+import IconButton from "../IconButton";
+
+export default function SolEndpointStatsView() {
+  const { getSolEndpointStats, getting, endpointStats } =
+    useGetSolEndpointStatsCmd();
+
+  const refresh = () => {
+    getSolEndpointStats().catch(() => {});
+  };
+
+  useEffect(() => {
+    refresh();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  return (
+    <div className="flex flex-col gap-0.5">
+      <div className="flex items-center gap-1">
+        <div>Endpoints:</div>
+        <IconButton
+          tooltip={"Refresh Endpoint Stats"}
+          Icon={MdRefresh}
+          iconClassName={`${getting ? "animate-spin" : ""}`}
+          onClick={refresh}
+        />
+      </div>
+      {(endpointStats ?? []).length === 0 && (
+        <div className="text-sm text-gray-500">No transaction sent yet</div>
+      )}
+      {(endpointStats ?? []).map((stats) => (
+        <div key={stats.endpoint} className="text-sm">
+          {stats.endpoint}: sent {stats.sent}, rejected {stats.rejected},
+          accepted and landed {stats.accepted_landed}, expired {stats.expired},
+          first accepted {stats.first_accepted}
+          {typeof stats.avg_accept_ms === "number" &&
+            `, avg accept ${stats.avg_accept_ms}ms`}
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,44 @@
+import { useGetJitoRegionsCmd } from "@/hooks/chain";
+import { useEffect } from "react";
+import { MdRefresh } from "react-icons/md";
+import IconButton from "../IconButton";
+
+export default function JitoRegionsView() {
+  const { getJitoRegions, getting, jitoRegions } = useGetJitoRegionsCmd();
+
+  const refresh = () => {
+    getJitoRegions().catch(() => {});
+  };
+
+  useEffect(() => {
+    refresh();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  return (
+    <div className="flex flex-col gap-0.5">
+      <div className="flex items-center gap-1">
+        <div>Jito Region Stats:</div>
+        <IconButton
+          tooltip={"Refresh Jito Region Stats"}
+          Icon={MdRefresh}
+          iconClassName={`${getting ? "animate-spin" : ""}`}
+          onClick={refresh}
+        />
+      </div>
+      {(jitoRegions ?? []).length === 0 && (
+        <div className="text-sm text-gray-500">No region probed yet</div>
+      )}
+      {(jitoRegions ?? []).map((region) => (
+        <div key={region.url} className="text-sm">
+          {region.url}:{" "}
+          {typeof region.latency_ms === "number"
+            ? `latency ${region.latency_ms}ms`
+            : "probe failed"}
+          {typeof region.cooldown_secs === "number" &&
+            `, cooling down for ${region.cooldown_secs}s`}
+        </div>
+      ))}
+    </div>
+  );
+}
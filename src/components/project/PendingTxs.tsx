@@ -0,0 +1,98 @@
+import { abbr } from "@/app/utils";
+import {
+  useCancelEvmTxCmd,
+  useGetEvmPendingTxsCmd,
+  useSpeedUpEvmTxCmd,
+} from "@/hooks/chain";
+import { useEffect } from "react";
+import toast from "react-hot-toast";
+import { MdCancel, MdFastForward, MdRefresh } from "react-icons/md";
+import IconButton from "../IconButton";
+import { useProject } from "./Provider";
+
+export default function EvmPendingTxsView() {
+  const { project } = useProject();
+  const { getEvmPendingTxs, getting, pendingTxs } = useGetEvmPendingTxsCmd();
+  const { speedUpEvmTx, speedingUp } = useSpeedUpEvmTxCmd();
+  const { cancelEvmTx, canceling } = useCancelEvmTxCmd();
+
+  const refresh = () => {
+    getEvmPendingTxs().catch(() => {});
+  };
+
+  useEffect(() => {
+    refresh();
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  const pkOf = (addr: string) => {
+    const wallets: [string, string][] = [
+      [project.main_wallet, project.main_wallet_pk],
+      ...project.wallet_grps.flatMap((it) => it.addresses),
+    ];
+    return wallets.find(
+      ([it]) => it.toLowerCase() === addr.toLowerCase(),
+    )?.[1];
+  };
+
+  const replace = async (from: string, nonce: number, isCancel: boolean) => {
+    const from_pk = pkOf(from);
+    if (!from_pk) {
+      toast.error(`wallet ${from} is not in the project`);
+      return;
+    }
+    const action = isCancel ? "cancel" : "speed up";
+    try {
+      const req = { req: { from_pk, nonce } };
+      const txId = isCancel ? await cancelEvmTx(req) : await speedUpEvmTx(req);
+      toast.success(`${action} transaction sent: ${txId}`);
+    } catch (err) {
+      const e = err as { err_msg: string };
+      toast.error(`${action} transaction error: ${e.err_msg}`);
+    }
+    refresh();
+  };
+
+  return (
+    <div className="flex flex-col gap-0.5">
+      <div className="flex items-center gap-1">
+        <div>Pending Transactions:</div>
+        <IconButton
+          tooltip={"Refresh Pending Transactions"}
+          Icon={MdRefresh}
+          iconClassName={`${getting ? "animate-spin" : ""}`}
+          onClick={refresh}
+        />
+      </div>
+      {(pendingTxs ?? []).length === 0 && (
+        <div className="text-sm text-gray-500">No pending transaction</div>
+      )}
+      {(pendingTxs ?? []).map((tx) => (
+        <div
+          key={`${tx.from}-${tx.nonce}`}
+          className="flex items-center gap-1 text-sm"
+        >
+          <div>
+            {abbr(tx.from, 6)} nonce {tx.nonce}:{" "}
+            {abbr(tx.tx_hashes[tx.tx_hashes.length - 1] ?? "", 8)}
+            {tx.cancel_tx_hashes.length > 0 && ", canceling"}, sent at{" "}
+            {new Date(tx.sent_at * 1000).toLocaleTimeString()}
+          </div>
+          <IconButton
+            isDisabled={speedingUp || canceling}
+            tooltip={"Speed Up"}
+            Icon={MdFastForward}
+            onClick={() => replace(tx.from, tx.nonce, false)}
+          />
+          <IconButton
+            isDisabled={speedingUp || canceling}
+            isDanger
+            tooltip={"Cancel"}
+            Icon={MdCancel}
+            onClick={() => replace(tx.from, tx.nonce, true)}
+          />
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -10,6 +10,9 @@ import CopyButton from "../CopyButton";
 import IconButton from "../IconButton";
 import CreateWalletGrpModal from "../wallet/CreateWalletGrpModal";
 import EditProjectModal from "./EditModal";
+import SolEndpointStatsView from "./EndpointStats";
+import JitoRegionsView from "./JitoRegions";
+import EvmPendingTxsView from "./PendingTxs";
 import { useProject } from "./Provider";
 import MainWalletWithdrawModal from "./WithdrawModal";
 import { AiOutlineImport } from "react-icons/ai";
@@ -120,10 +123,21 @@ export default function ProjectPanel({
               <>
                 <div className="col-span-1"></div>
                 <div className="col-span-2">
-                  Jito Api Url: {project.jito_url}
+                  Jito Regions: {project.jito_urls.join(", ")}
+                </div>
+                <div className="col-span-3">
+                  <SolEndpointStatsView />
+                </div>
+                <div className="col-span-3">
+                  <JitoRegionsView />
                 </div>
               </>
             )}
+            {project.chain !== "Solana" && (
+              <div className="col-span-3">
+                <EvmPendingTxsView />
+              </div>
+            )}
           </div>
         </CardBody>
       </Card>
@@ -0,0 +1,57 @@
+import { AggregatorKind, Chain } from "@/hooks/project";
+import { Chip, Select, SelectItem } from "@nextui-org/react";
+
+const CHAIN_AGGREGATORS: Record<Chain, AggregatorKind[]> = {
+  Solana: ["Jupiter", "Raydium"],
+  Base: ["OneInch", "UniswapV2", "UniswapV3"],
+  Bsc: ["OneInch", "PancakeSwapV2", "PancakeSwapV3"],
+};
+
+// the first aggregator is tried first, then the fallbacks in order,
+// none selected uses the aggregator of the project
+export default function AggregatorsInput({
+  chain,
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  chain: Chain;
+  value: AggregatorKind[];
+  isDisabled?: boolean;
+  onChange: (aggregators: AggregatorKind[]) => void;
+}) {
+  const remaining = CHAIN_AGGREGATORS[chain].filter((x) => !value.includes(x));
+
+  return (
+    <div className="flex items-center gap-2">
+      {value.map((kind, i) => (
+        <Chip
+          key={kind}
+          variant="flat"
+          color={i === 0 ? "primary" : "default"}
+          isDisabled={isDisabled}
+          onClose={() => onChange(value.filter((x) => x !== kind))}
+        >
+          {`${i + 1}. ${kind}`}
+        </Chip>
+      ))}
+      {remaining.length > 0 && (
+        <Select
+          aria-label="Add Aggregator"
+          className="w-48"
+          placeholder={value.length === 0 ? "Project Default" : "Add Fallback"}
+          isDisabled={isDisabled}
+          selectedKeys={[]}
+          onChange={(e) =>
+            e.target.value &&
+            onChange([...value, e.target.value as AggregatorKind])
+          }
+        >
+          {remaining.map((kind) => (
+            <SelectItem key={kind}>{kind}</SelectItem>
+          ))}
+        </Select>
+      )}
+    </div>
+  );
+}
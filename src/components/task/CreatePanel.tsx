@@ -7,6 +7,8 @@ import {
   Input,
   Radio,
   RadioGroup,
+  Select,
+  SelectItem,
   Slider,
   Switch,
   Tooltip,
@@ -16,11 +18,26 @@ import { TauriEvent } from "@tauri-apps/api/event";
 import TokenInput from "../token/TokenInput";
 import FormItem from "../FormItem";
 import { Controller, useForm } from "react-hook-form";
-import { CreateTaskReq, useCreateTradeTaskCmd } from "@/hooks/tasks";
+import {
+  CreateTaskReq,
+  TaskScheduleKind,
+  useCreateTradeTaskCmd,
+  useGetTaskSchedulesCmd,
+  useRemoveTaskScheduleCmd,
+  useSetTaskScheduleCmd,
+} from "@/hooks/tasks";
 import { NumericFormat } from "react-number-format";
-import { useMemo } from "react";
+import { useEffect, useMemo, useState } from "react";
+import toast from "react-hot-toast";
 import { BsQuestionCircle } from "react-icons/bs";
 import { useProject } from "../project/Provider";
+import PriorityFeeStrategyInput from "./PriorityFeeStrategyInput";
+import EvmFeeModeInput from "./EvmFeeModeInput";
+import AggregatorsInput from "./AggregatorsInput";
+import JupiterRouteConfigInput from "./JupiterRouteConfigInput";
+import JitoTipStrategyInput from "./JitoTipStrategyInput";
+import StopConditionsInput from "./StopConditionsInput";
+import TaskScheduleInput, { defaultScheduleKind } from "./TaskScheduleInput";
 
 export type TaskStatus = "Stopped" | "Running";
 export type Task = {
@@ -39,7 +56,7 @@ export default function CreateTaskPanel({
   onTaskCreated: (task: Task) => void;
   onTaskRemoved: (id: string) => void;
 }) {
-  const { project } = useProject();
+  const { project, nativeCoinSymbol } = useProject();
   const labelClassName = "w-32 text-right";
 
   const {
@@ -58,10 +75,29 @@ export default function CreateTaskPanel({
       gas_price: walletGrp.chain === "Solana" ? 0.01 : 0.02,
       interval_secs: 1,
       workers_cnt: 2,
+      jito_tip_strategy: "Fixed",
+      priority_fee_strategy: "Fixed",
+      evm_fee_mode: { Eip1559: { reward_percentile: 50 } },
+      evm_tx_config: {
+        receipt_timeout_secs: 300,
+        replace_after_secs: 60,
+        replace_action: "SpeedUp",
+        fee_bump_pct: 15,
+      },
+      jupiter_route_config: {
+        only_direct_routes: true,
+        use_shared_accounts: false,
+        swap_mode: "ExactIn",
+      },
     },
   });
 
   const isUseJito = watch("use_jito");
+  const aggregators = watch("aggregators");
+  // jupiter is the aggregator of solana projects when none is selected
+  const isUseJupiter =
+    walletGrp.chain === "Solana" &&
+    (!aggregators?.length || aggregators.includes("Jupiter"));
 
   const [gasPriceLabel, gasPriceUnit] = useMemo(() => {
     if (walletGrp.chain === "Solana") {
@@ -80,17 +116,18 @@ export default function CreateTaskPanel({
   const { createTradeTask, creating, createTradeTaskErr } =
     useCreateTradeTaskCmd();
 
-  const onFormSubmit = async (data: CreateTaskReq) => {
-    data.wallet_grp_id = walletGrp.id;
-    data.percetage = data.percetage.map((x) => Math.round(x * 100)) as [
+  // form values are in the units shown, the request takes the smallest units
+  const toTaskReq = (data: CreateTaskReq): CreateTaskReq => {
+    const req = { ...data, wallet_grp_id: walletGrp.id };
+    req.percetage = data.percetage.map((x) => Math.round(x * 100)) as [
       number,
       number,
     ];
-    data.slippage =
+    req.slippage =
       walletGrp.chain === "Solana"
         ? Math.round(data.slippage * 10000)
         : Math.round(data.slippage * 100);
-    data.gas_price =
+    req.gas_price =
       walletGrp.chain === "Solana"
         ? data.use_jito
           ? // SOL
@@ -98,8 +135,73 @@ export default function CreateTaskPanel({
           : // to micro lamports
             Math.round(data.gas_price * 1e6)
         : Math.round(data.gas_price * 1e9);
-    data.workers_cnt = Number(data.workers_cnt);
-    data.interval_secs = Number(data.interval_secs);
+    if (data.jito_max_tip !== undefined) {
+      // SOL
+      req.jito_max_tip = Math.round(data.jito_max_tip * 1e9);
+    }
+    if (data.jito_fallback_unit_price !== undefined) {
+      // to micro lamports
+      req.jito_fallback_unit_price = Math.round(
+        data.jito_fallback_unit_price * 1e6,
+      );
+    }
+    req.workers_cnt = Number(data.workers_cnt);
+    req.interval_secs = Number(data.interval_secs);
+    return req;
+  };
+
+  const { getTaskSchedules, taskSchedules } = useGetTaskSchedulesCmd();
+  const { setTaskSchedule, setting, setTaskScheduleErr } =
+    useSetTaskScheduleCmd();
+  const { removeTaskSchedule, removing } = useRemoveTaskScheduleCmd();
+  const savedSchedule = taskSchedules?.find(
+    (it) => it.task_id === walletGrp.id,
+  );
+  const [scheduleKind, setScheduleKind] = useState<TaskScheduleKind>(
+    defaultScheduleKind("Daily"),
+  );
+
+  useEffect(() => {
+    getTaskSchedules().catch(() => {});
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, []);
+
+  useEffect(() => {
+    if (savedSchedule) {
+      setScheduleKind(savedSchedule.kind);
+    }
+  }, [savedSchedule]);
+
+  // the schedule creates the task from the form when it starts it
+  const onScheduleSave = async (formData: CreateTaskReq) => {
+    try {
+      await setTaskSchedule({
+        schedule: {
+          task_id: walletGrp.id,
+          kind: scheduleKind,
+          task: toTaskReq(formData),
+        },
+      });
+      toast.success("Task schedule saved");
+    } catch (e) {
+      // already use setTaskSchedule error state
+    }
+    getTaskSchedules().catch(() => {});
+  };
+
+  const onScheduleRemove = async () => {
+    try {
+      await removeTaskSchedule({ id: walletGrp.id });
+      toast.success("Task schedule removed");
+    } catch (e) {
+      const err = e as { err_msg: string };
+      toast.error(`remove task schedule error: ${err.err_msg}`);
+    }
+    getTaskSchedules().catch(() => {});
+  };
+
+  const onFormSubmit = async (formData: CreateTaskReq) => {
+    const data = toTaskReq(formData);
 
     const label = `task_${walletGrp.id}`;
     const searchParams = new URLSearchParams();
@@ -308,8 +410,251 @@ export default function CreateTaskPanel({
                   }}
                 />
               </FormItem>
+              {isUseJito && (
+                <>
+                  <FormItem
+                    label={<div className={labelClassName}>Tip Strategy</div>}
+                  >
+                    <Controller
+                      name="jito_tip_strategy"
+                      control={control}
+                      render={({ field }) => (
+                        <JitoTipStrategyInput
+                          isDisabled={task.status === "Running"}
+                          value={field.value ?? "Fixed"}
+                          onChange={field.onChange}
+                        />
+                      )}
+                    />
+                  </FormItem>
+                  <FormItem
+                    label={<div className={labelClassName}>Max Tip</div>}
+                  >
+                    <Controller
+                      name="jito_max_tip"
+                      control={control}
+                      render={({ field }) => (
+                        <NumericFormat
+                          aria-label="Max Tip"
+                          customInput={Input}
+                          decimalScale={9}
+                          placeholder="No Limit"
+                          endContent={
+                            <span className="text-default-400">SOL</span>
+                          }
+                          isDisabled={task.status === "Running"}
+                          value={field.value ?? ""}
+                          onValueChange={(v) => field.onChange(v.floatValue)}
+                        />
+                      )}
+                    />
+                  </FormItem>
+                  <FormItem
+                    label={<div className={labelClassName}>Rpc Fallback</div>}
+                  >
+                    <Controller
+                      name="jito_fallback_unit_price"
+                      control={control}
+                      render={({ field }) => (
+                        <NumericFormat
+                          aria-label="Fallback CU Price"
+                          customInput={Input}
+                          decimalScale={6}
+                          thousandSeparator
+                          placeholder="No Fallback"
+                          startContent={
+                            <span className="text-default-400">CU Price</span>
+                          }
+                          endContent={
+                            <span className="text-default-400">Lamports</span>
+                          }
+                          isDisabled={task.status === "Running"}
+                          value={field.value ?? ""}
+                          onValueChange={(v) => field.onChange(v.floatValue)}
+                        />
+                      )}
+                    />
+                  </FormItem>
+                  <FormItem
+                    label={<div className={labelClassName}>Bundle Size</div>}
+                  >
+                    <Controller
+                      name="jito_bundle_size"
+                      control={control}
+                      render={({ field }) => (
+                        <NumericFormat
+                          aria-label="Bundle Size"
+                          customInput={Input}
+                          decimalScale={0}
+                          allowNegative={false}
+                          // a jito bundle holds up to 5 transactions
+                          isAllowed={(v) => (v.floatValue ?? 0) <= 5}
+                          placeholder="1"
+                          endContent={
+                            <span className="text-default-400">
+                              Wallets per Bundle
+                            </span>
+                          }
+                          isDisabled={task.status === "Running"}
+                          value={field.value ?? ""}
+                          onValueChange={(v) => field.onChange(v.floatValue)}
+                        />
+                      )}
+                    />
+                  </FormItem>
+                </>
+              )}
+              <FormItem
+                label={<div className={labelClassName}>CU Price Strategy</div>}
+              >
+                <Controller
+                  name="priority_fee_strategy"
+                  control={control}
+                  render={({ field }) => (
+                    <PriorityFeeStrategyInput
+                      isDisabled={task.status === "Running"}
+                      value={field.value ?? "Fixed"}
+                      onChange={field.onChange}
+                    />
+                  )}
+                />
+              </FormItem>
+              <FormItem
+                label={<div className={labelClassName}>CU Margin</div>}
+              >
+                <Controller
+                  name="compute_unit_margin_pct"
+                  control={control}
+                  render={({ field }) => (
+                    <NumericFormat
+                      aria-label="Compute Unit Margin"
+                      customInput={Input}
+                      decimalScale={0}
+                      placeholder="10"
+                      endContent={
+                        <span className="text-default-400">
+                          % over simulated units
+                        </span>
+                      }
+                      isDisabled={task.status === "Running"}
+                      value={field.value ?? ""}
+                      onValueChange={(v) => field.onChange(v.floatValue)}
+                    />
+                  )}
+                />
+              </FormItem>
             </>
           )}
+          {walletGrp.chain !== "Solana" && (
+            <FormItem label={<div className={labelClassName}>Fee Mode</div>}>
+              <Controller
+                name="evm_fee_mode"
+                control={control}
+                render={({ field }) => (
+                  <EvmFeeModeInput
+                    isDisabled={task.status === "Running"}
+                    value={field.value ?? "Legacy"}
+                    onChange={field.onChange}
+                  />
+                )}
+              />
+            </FormItem>
+          )}
+          {walletGrp.chain !== "Solana" && (
+            <FormItem label={<div className={labelClassName}>Stuck Tx</div>}>
+              <div className="flex gap-2">
+                <Controller
+                  name="evm_tx_config.replace_after_secs"
+                  control={control}
+                  render={({ field }) => (
+                    <NumericFormat
+                      aria-label="Replace After"
+                      customInput={Input}
+                      decimalScale={0}
+                      placeholder="Never"
+                      startContent={
+                        <span className="text-default-400">After</span>
+                      }
+                      endContent={
+                        <span className="text-default-400">Seconds</span>
+                      }
+                      isDisabled={task.status === "Running"}
+                      value={field.value ?? ""}
+                      onValueChange={(v) =>
+                        field.onChange(v.floatValue ?? null)
+                      }
+                    />
+                  )}
+                />
+                <Controller
+                  name="evm_tx_config.replace_action"
+                  control={control}
+                  render={({ field }) => (
+                    <Select
+                      aria-label="Replace Action"
+                      className="w-40"
+                      isDisabled={task.status === "Running"}
+                      selectedKeys={[field.value]}
+                      onChange={(e) =>
+                        e.target.value && field.onChange(e.target.value)
+                      }
+                    >
+                      <SelectItem key="SpeedUp">Speed Up</SelectItem>
+                      <SelectItem key="Cancel">Cancel</SelectItem>
+                    </Select>
+                  )}
+                />
+                <Controller
+                  name="evm_tx_config.fee_bump_pct"
+                  control={control}
+                  render={({ field }) => (
+                    <NumericFormat
+                      aria-label="Fee Bump"
+                      customInput={Input}
+                      decimalScale={0}
+                      endContent={
+                        <span className="text-default-400">% Fee Bump</span>
+                      }
+                      isDisabled={task.status === "Running"}
+                      value={field.value}
+                      onValueChange={(v) => field.onChange(v.floatValue ?? 15)}
+                    />
+                  )}
+                />
+              </div>
+            </FormItem>
+          )}
+          <FormItem label={<div className={labelClassName}>Aggregators</div>}>
+            <Controller
+              name="aggregators"
+              control={control}
+              render={({ field }) => (
+                <AggregatorsInput
+                  chain={walletGrp.chain}
+                  isDisabled={task.status === "Running"}
+                  value={field.value ?? []}
+                  onChange={field.onChange}
+                />
+              )}
+            />
+          </FormItem>
+          {isUseJupiter && (
+            <FormItem
+              label={<div className={labelClassName}>Jupiter Route</div>}
+            >
+              <Controller
+                name="jupiter_route_config"
+                control={control}
+                render={({ field }) => (
+                  <JupiterRouteConfigInput
+                    isDisabled={task.status === "Running"}
+                    value={field.value!}
+                    onChange={field.onChange}
+                  />
+                )}
+              />
+            </FormItem>
+          )}
           <FormItem
             label={
               <div
@@ -379,6 +724,22 @@ export default function CreateTaskPanel({
               }}
             />
           </FormItem>
+          <FormItem
+            label={<div className={labelClassName}>Stop Conditions</div>}
+          >
+            <Controller
+              name="stop_conditions"
+              control={control}
+              render={({ field }) => (
+                <StopConditionsInput
+                  nativeSymbol={nativeCoinSymbol}
+                  isDisabled={task.status === "Running"}
+                  value={field.value ?? {}}
+                  onChange={field.onChange}
+                />
+              )}
+            />
+          </FormItem>
           {createTradeTaskErr && (
             <div className="text-danger">{createTradeTaskErr.err_msg} </div>
           )}
@@ -391,6 +752,37 @@ export default function CreateTaskPanel({
           >
             {task.status === "Stopped" ? "Start Task" : "Show Task Window"}
           </Button>
+          <FormItem label={<div className={labelClassName}>Schedule</div>}>
+            <TaskScheduleInput
+              isDisabled={setting}
+              value={scheduleKind}
+              onChange={setScheduleKind}
+            />
+          </FormItem>
+          {setTaskScheduleErr && (
+            <div className="text-danger">{setTaskScheduleErr.err_msg}</div>
+          )}
+          <div className="flex gap-2">
+            <Button
+              isDisabled={!isValid}
+              isLoading={setting}
+              className="flex-1"
+              color="secondary"
+              onClick={handleSubmit(onScheduleSave)}
+            >
+              {savedSchedule ? "Update Schedule" : "Save Schedule"}
+            </Button>
+            {savedSchedule && (
+              <Button
+                isLoading={removing}
+                color="danger"
+                variant="flat"
+                onClick={onScheduleRemove}
+              >
+                Remove Schedule
+              </Button>
+            )}
+          </div>
         </form>
       </CardBody>
     </Card>
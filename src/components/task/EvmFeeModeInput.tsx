@@ -0,0 +1,54 @@
+import { EvmFeeMode } from "@/hooks/chain";
+import { Input, Select, SelectItem } from "@nextui-org/react";
+import { NumericFormat } from "react-number-format";
+
+const DEFAULT_REWARD_PERCENTILE = 50;
+
+export default function EvmFeeModeInput({
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  value: EvmFeeMode;
+  isDisabled?: boolean;
+  onChange: (mode: EvmFeeMode) => void;
+}) {
+  return (
+    <div className="flex gap-2">
+      <Select
+        aria-label="Fee Mode"
+        className="w-40"
+        isDisabled={isDisabled}
+        selectedKeys={[value === "Legacy" ? "Legacy" : "Eip1559"]}
+        onChange={(e) => {
+          if (e.target.value === "Legacy") {
+            onChange("Legacy");
+          } else if (e.target.value === "Eip1559") {
+            onChange({
+              Eip1559: { reward_percentile: DEFAULT_REWARD_PERCENTILE },
+            });
+          }
+        }}
+      >
+        <SelectItem key="Eip1559">EIP-1559</SelectItem>
+        <SelectItem key="Legacy">Legacy</SelectItem>
+      </Select>
+      {value !== "Legacy" && (
+        <NumericFormat
+          aria-label="Priority Fee Percentile"
+          customInput={Input}
+          decimalScale={0}
+          isAllowed={(v) => (v.floatValue ?? 0) <= 100}
+          endContent={
+            <span className="text-default-400">Priority Fee Percentile</span>
+          }
+          isDisabled={isDisabled}
+          value={value.Eip1559.reward_percentile}
+          onValueChange={(v) =>
+            onChange({ Eip1559: { reward_percentile: v.floatValue ?? 0 } })
+          }
+        />
+      )}
+    </div>
+  );
+}
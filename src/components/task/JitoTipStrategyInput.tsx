@@ -0,0 +1,86 @@
+import { JitoTipStrategy } from "@/hooks/tasks";
+import { Input, Select, SelectItem } from "@nextui-org/react";
+import { NumericFormat } from "react-number-format";
+
+type StrategyKind = "Fixed" | "TipFloor" | "Escalating";
+
+// percentiles of the landed tips jito reports
+const TIP_FLOOR_PERCENTILES = [25, 50, 75, 95, 99];
+
+function kindOf(strategy: JitoTipStrategy): StrategyKind {
+  if (strategy === "Fixed") {
+    return "Fixed";
+  }
+  return "TipFloor" in strategy ? "TipFloor" : "Escalating";
+}
+
+export default function JitoTipStrategyInput({
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  value: JitoTipStrategy;
+  isDisabled?: boolean;
+  onChange: (strategy: JitoTipStrategy) => void;
+}) {
+  const onKindChange = (kind: StrategyKind) => {
+    if (kind === "TipFloor") {
+      onChange({ TipFloor: { percentile: 50 } });
+    } else if (kind === "Escalating") {
+      onChange({ Escalating: { step_pct: 20 } });
+    } else {
+      onChange("Fixed");
+    }
+  };
+
+  return (
+    <div className="flex gap-2">
+      <Select
+        aria-label="Tip Strategy"
+        className="w-40"
+        isDisabled={isDisabled}
+        selectedKeys={[kindOf(value)]}
+        onChange={(e) =>
+          e.target.value && onKindChange(e.target.value as StrategyKind)
+        }
+      >
+        <SelectItem key="Fixed">Fixed</SelectItem>
+        <SelectItem key="TipFloor">Tip Floor</SelectItem>
+        <SelectItem key="Escalating">Escalating</SelectItem>
+      </Select>
+      {value !== "Fixed" && "TipFloor" in value && (
+        <Select
+          aria-label="Tip Floor Percentile"
+          className="w-40"
+          isDisabled={isDisabled}
+          selectedKeys={[value.TipFloor.percentile.toString()]}
+          onChange={(e) =>
+            e.target.value &&
+            onChange({ TipFloor: { percentile: Number(e.target.value) } })
+          }
+        >
+          {TIP_FLOOR_PERCENTILES.map((percentile) => (
+            <SelectItem key={percentile.toString()}>
+              {`${percentile}th Percentile`}
+            </SelectItem>
+          ))}
+        </Select>
+      )}
+      {value !== "Fixed" && "Escalating" in value && (
+        <NumericFormat
+          aria-label="Tip Step"
+          customInput={Input}
+          decimalScale={0}
+          endContent={
+            <span className="text-default-400">% Step per Dropped Bundle</span>
+          }
+          isDisabled={isDisabled}
+          value={value.Escalating.step_pct}
+          onValueChange={(v) =>
+            onChange({ Escalating: { step_pct: v.floatValue ?? 0 } })
+          }
+        />
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,80 @@
+import { JupiterRouteConfig } from "@/hooks/tasks";
+import { Input, Switch } from "@nextui-org/react";
+import { NumericFormat } from "react-number-format";
+
+// dex labels like `Raydium` or `Orca V2`, separated by commas
+function parseDexes(text: string): string[] | undefined {
+  const dexes = text
+    .split(",")
+    .map((x) => x.trim())
+    .filter((x) => x.length > 0);
+  return dexes.length > 0 ? dexes : undefined;
+}
+
+export default function JupiterRouteConfigInput({
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  value: JupiterRouteConfig;
+  isDisabled?: boolean;
+  onChange: (config: JupiterRouteConfig) => void;
+}) {
+  return (
+    <div className="flex flex-col gap-2">
+      <div className="flex gap-4">
+        <Switch
+          size="sm"
+          isDisabled={isDisabled}
+          isSelected={value.only_direct_routes}
+          onValueChange={(v) => onChange({ ...value, only_direct_routes: v })}
+        >
+          Direct Routes Only
+        </Switch>
+        <Switch
+          size="sm"
+          isDisabled={isDisabled}
+          isSelected={value.use_shared_accounts}
+          onValueChange={(v) =>
+            onChange({ ...value, use_shared_accounts: v })
+          }
+        >
+          Shared Accounts
+        </Switch>
+      </div>
+      <div className="flex gap-2">
+        <Input
+          aria-label="Dexes"
+          placeholder="Any Dex"
+          startContent={<span className="text-default-400">Only</span>}
+          isDisabled={isDisabled}
+          defaultValue={value.dexes?.join(", ")}
+          onValueChange={(v) => onChange({ ...value, dexes: parseDexes(v) })}
+        />
+        <Input
+          aria-label="Excluded Dexes"
+          placeholder="None"
+          startContent={<span className="text-default-400">Exclude</span>}
+          isDisabled={isDisabled}
+          defaultValue={value.excluded_dexes?.join(", ")}
+          onValueChange={(v) =>
+            onChange({ ...value, excluded_dexes: parseDexes(v) })
+          }
+        />
+        <NumericFormat
+          aria-label="Max Accounts"
+          customInput={Input}
+          decimalScale={0}
+          placeholder="No Limit"
+          startContent={<span className="text-default-400">Max</span>}
+          endContent={<span className="text-default-400">Accounts</span>}
+          isDisabled={isDisabled}
+          value={value.max_accounts ?? ""}
+          onValueChange={(v) =>
+            onChange({ ...value, max_accounts: v.floatValue })
+          }
+        />
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,122 @@
+import { SolPriorityFeeStrategy } from "@/hooks/tasks";
+import { Input, Select, SelectItem } from "@nextui-org/react";
+import { NumericFormat } from "react-number-format";
+
+type StrategyKind = "Fixed" | "Percentile" | "Adaptive";
+
+const DEFAULT_MAX_PRICE = 1_000_000;
+
+function kindOf(strategy: SolPriorityFeeStrategy): StrategyKind {
+  if (strategy === "Fixed") {
+    return "Fixed";
+  }
+  return "Percentile" in strategy ? "Percentile" : "Adaptive";
+}
+
+// max price is in micro lamports, shown in lamports like the task CU price
+export default function PriorityFeeStrategyInput({
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  value: SolPriorityFeeStrategy;
+  isDisabled?: boolean;
+  onChange: (strategy: SolPriorityFeeStrategy) => void;
+}) {
+  const kind = kindOf(value);
+
+  const onKindChange = (newKind: StrategyKind) => {
+    if (newKind === "Percentile") {
+      onChange({
+        Percentile: { percentile: 75, max_price: DEFAULT_MAX_PRICE },
+      });
+    } else if (newKind === "Adaptive") {
+      onChange({ Adaptive: { step_pct: 20, max_price: DEFAULT_MAX_PRICE } });
+    } else {
+      onChange("Fixed");
+    }
+  };
+
+  const params =
+    value === "Fixed"
+      ? undefined
+      : "Percentile" in value
+        ? value.Percentile
+        : value.Adaptive;
+  const onMaxPriceChange = (lamports: number) => {
+    const max_price = Math.round(lamports * 1e6);
+    if (value === "Fixed") {
+      return;
+    }
+    if ("Percentile" in value) {
+      onChange({ Percentile: { ...value.Percentile, max_price } });
+    } else {
+      onChange({ Adaptive: { ...value.Adaptive, max_price } });
+    }
+  };
+
+  return (
+    <div className="flex gap-2">
+      <Select
+        aria-label="Priority Fee Strategy"
+        className="w-40"
+        isDisabled={isDisabled}
+        selectedKeys={[kind]}
+        onChange={(e) =>
+          e.target.value && onKindChange(e.target.value as StrategyKind)
+        }
+      >
+        <SelectItem key="Fixed">Fixed</SelectItem>
+        <SelectItem key="Percentile">Percentile</SelectItem>
+        <SelectItem key="Adaptive">Adaptive</SelectItem>
+      </Select>
+      {value !== "Fixed" && "Percentile" in value && (
+        <NumericFormat
+          aria-label="Fee Percentile"
+          customInput={Input}
+          decimalScale={0}
+          isAllowed={(v) => (v.floatValue ?? 0) <= 100}
+          endContent={<span className="text-default-400">Percentile</span>}
+          isDisabled={isDisabled}
+          value={value.Percentile.percentile}
+          onValueChange={(v) =>
+            onChange({
+              Percentile: {
+                ...value.Percentile,
+                percentile: v.floatValue ?? 0,
+              },
+            })
+          }
+        />
+      )}
+      {value !== "Fixed" && "Adaptive" in value && (
+        <NumericFormat
+          aria-label="Fee Step"
+          customInput={Input}
+          decimalScale={0}
+          endContent={<span className="text-default-400">% Step</span>}
+          isDisabled={isDisabled}
+          value={value.Adaptive.step_pct}
+          onValueChange={(v) =>
+            onChange({
+              Adaptive: { ...value.Adaptive, step_pct: v.floatValue ?? 0 },
+            })
+          }
+        />
+      )}
+      {params && (
+        <NumericFormat
+          aria-label="Max CU Price"
+          customInput={Input}
+          decimalScale={6}
+          thousandSeparator
+          startContent={<span className="text-default-400">Max</span>}
+          endContent={<span className="text-default-400">Lamports</span>}
+          isDisabled={isDisabled}
+          value={params.max_price / 1e6}
+          onValueChange={(v) => onMaxPriceChange(v.floatValue ?? 0)}
+        />
+      )}
+    </div>
+  );
+}
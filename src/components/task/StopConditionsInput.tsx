@@ -0,0 +1,67 @@
+import { fromDatetimeInput, toDatetimeInput } from "@/app/utils";
+import { TaskStopConditions } from "@/hooks/tasks";
+import { Input } from "@nextui-org/react";
+import { NumericFormat } from "react-number-format";
+
+// every condition is optional, amounts are in the native coin like the backend
+export default function StopConditionsInput({
+  value,
+  nativeSymbol,
+  isDisabled = false,
+  onChange,
+}: {
+  value: TaskStopConditions;
+  nativeSymbol: string;
+  isDisabled?: boolean;
+  onChange: (conditions: TaskStopConditions) => void;
+}) {
+  const amountInput = (
+    key: Exclude<keyof TaskStopConditions, "max_trades" | "end_at">,
+    label: string,
+  ) => (
+    <NumericFormat
+      aria-label={label}
+      customInput={Input}
+      decimalScale={9}
+      thousandSeparator
+      allowNegative={false}
+      placeholder="Not Set"
+      startContent={<span className="text-default-400">{label}</span>}
+      endContent={<span className="text-default-400">{nativeSymbol}</span>}
+      isDisabled={isDisabled}
+      value={value[key] ?? ""}
+      onValueChange={(v) => onChange({ ...value, [key]: v.floatValue })}
+    />
+  );
+
+  return (
+    <div className="grid grid-cols-2 gap-2">
+      <NumericFormat
+        aria-label="Max Trades"
+        customInput={Input}
+        decimalScale={0}
+        thousandSeparator
+        allowNegative={false}
+        placeholder="Not Set"
+        startContent={<span className="text-default-400">Max Trades</span>}
+        isDisabled={isDisabled}
+        value={value.max_trades ?? ""}
+        onValueChange={(v) => onChange({ ...value, max_trades: v.floatValue })}
+      />
+      <Input
+        aria-label="End At"
+        type="datetime-local"
+        startContent={<span className="text-default-400">End At</span>}
+        isDisabled={isDisabled}
+        value={toDatetimeInput(value.end_at)}
+        onValueChange={(v) =>
+          onChange({ ...value, end_at: fromDatetimeInput(v) })
+        }
+      />
+      {amountInput("max_fees", "Max Fees")}
+      {amountInput("max_notional", "Max Volume")}
+      {amountInput("min_main_wallet_balance", "Min Main Wallet")}
+      {amountInput("min_group_balance", "Min Group Balance")}
+    </div>
+  );
+}
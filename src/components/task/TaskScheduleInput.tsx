@@ -0,0 +1,207 @@
+import { fromDatetimeInput, toDatetimeInput } from "@/app/utils";
+import { TaskScheduleKind, Weekday } from "@/hooks/tasks";
+import {
+  Checkbox,
+  CheckboxGroup,
+  Input,
+  Select,
+  SelectItem,
+} from "@nextui-org/react";
+import dayjs from "dayjs";
+import { NumericFormat } from "react-number-format";
+
+type KindName = "Once" | "Daily" | "Interval";
+
+const WEEKDAYS: Weekday[] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+export function defaultScheduleKind(name: KindName): TaskScheduleKind {
+  const nextHour = dayjs().add(1, "hour").startOf("hour").unix();
+  if (name === "Daily") {
+    return { Daily: { start: "09:00:00", stop: "17:00:00", weekdays: [] } };
+  }
+  if (name === "Interval") {
+    return {
+      Interval: { start_at: nextHour, every_secs: 3600, run_secs: 600 },
+    };
+  }
+  return { Once: { start_at: nextHour } };
+}
+
+// times of day are edited as "HH:MM", the backend takes "HH:MM:SS"
+function toTimeInput(time: string) {
+  return time.substring(0, 5);
+}
+
+function fromTimeInput(val: string) {
+  return val.length === 5 ? `${val}:00` : val;
+}
+
+export default function TaskScheduleInput({
+  value,
+  isDisabled = false,
+  onChange,
+}: {
+  value: TaskScheduleKind;
+  isDisabled?: boolean;
+  onChange: (kind: TaskScheduleKind) => void;
+}) {
+  const kindName: KindName =
+    "Once" in value ? "Once" : "Daily" in value ? "Daily" : "Interval";
+
+  return (
+    <div className="flex flex-col gap-2">
+      <div className="flex gap-2">
+        <Select
+          aria-label="Schedule Kind"
+          className="w-40 shrink-0"
+          isDisabled={isDisabled}
+          selectedKeys={[kindName]}
+          onChange={(e) =>
+            e.target.value &&
+            onChange(defaultScheduleKind(e.target.value as KindName))
+          }
+        >
+          <SelectItem key="Once">Once</SelectItem>
+          <SelectItem key="Daily">Daily</SelectItem>
+          <SelectItem key="Interval">Interval</SelectItem>
+        </Select>
+        {"Once" in value && (
+          <>
+            <Input
+              aria-label="Start At"
+              type="datetime-local"
+              startContent={<span className="text-default-400">Start</span>}
+              isDisabled={isDisabled}
+              value={toDatetimeInput(value.Once.start_at)}
+              onValueChange={(v) =>
+                onChange({
+                  Once: {
+                    ...value.Once,
+                    start_at: fromDatetimeInput(v) ?? value.Once.start_at,
+                  },
+                })
+              }
+            />
+            <Input
+              aria-label="Stop At"
+              type="datetime-local"
+              startContent={<span className="text-default-400">Stop</span>}
+              isDisabled={isDisabled}
+              value={toDatetimeInput(value.Once.stop_at)}
+              onValueChange={(v) =>
+                onChange({
+                  Once: { ...value.Once, stop_at: fromDatetimeInput(v) },
+                })
+              }
+            />
+          </>
+        )}
+        {"Daily" in value && (
+          <>
+            <Input
+              aria-label="Start Time"
+              type="time"
+              startContent={<span className="text-default-400">Start</span>}
+              isDisabled={isDisabled}
+              value={toTimeInput(value.Daily.start)}
+              onValueChange={(v) =>
+                v &&
+                onChange({
+                  Daily: { ...value.Daily, start: fromTimeInput(v) },
+                })
+              }
+            />
+            <Input
+              aria-label="Stop Time"
+              type="time"
+              startContent={<span className="text-default-400">Stop</span>}
+              isDisabled={isDisabled}
+              value={toTimeInput(value.Daily.stop)}
+              onValueChange={(v) =>
+                v &&
+                onChange({
+                  Daily: { ...value.Daily, stop: fromTimeInput(v) },
+                })
+              }
+            />
+          </>
+        )}
+        {"Interval" in value && (
+          <>
+            <Input
+              aria-label="Start At"
+              type="datetime-local"
+              startContent={<span className="text-default-400">From</span>}
+              isDisabled={isDisabled}
+              value={toDatetimeInput(value.Interval.start_at)}
+              onValueChange={(v) =>
+                onChange({
+                  Interval: {
+                    ...value.Interval,
+                    start_at: fromDatetimeInput(v) ?? value.Interval.start_at,
+                  },
+                })
+              }
+            />
+            <NumericFormat
+              aria-label="Run Minutes"
+              customInput={Input}
+              decimalScale={0}
+              allowNegative={false}
+              startContent={<span className="text-default-400">Run</span>}
+              endContent={<span className="text-default-400">Minutes</span>}
+              isDisabled={isDisabled}
+              value={value.Interval.run_secs / 60}
+              onValueChange={(v) =>
+                onChange({
+                  Interval: {
+                    ...value.Interval,
+                    run_secs: (v.floatValue ?? 0) * 60,
+                  },
+                })
+              }
+            />
+            <NumericFormat
+              aria-label="Every Minutes"
+              customInput={Input}
+              decimalScale={0}
+              allowNegative={false}
+              startContent={<span className="text-default-400">Every</span>}
+              endContent={<span className="text-default-400">Minutes</span>}
+              isDisabled={isDisabled}
+              value={value.Interval.every_secs / 60}
+              onValueChange={(v) =>
+                onChange({
+                  Interval: {
+                    ...value.Interval,
+                    every_secs: (v.floatValue ?? 0) * 60,
+                  },
+                })
+              }
+            />
+          </>
+        )}
+      </div>
+      {"Daily" in value && (
+        <CheckboxGroup
+          aria-label="Weekdays"
+          orientation="horizontal"
+          description="Every day when none is checked"
+          isDisabled={isDisabled}
+          value={value.Daily.weekdays}
+          onValueChange={(weekdays) =>
+            onChange({
+              Daily: { ...value.Daily, weekdays: weekdays as Weekday[] },
+            })
+          }
+        >
+          {WEEKDAYS.map((day) => (
+            <Checkbox key={day} value={day}>
+              {day}
+            </Checkbox>
+          ))}
+        </CheckboxGroup>
+      )}
+    </div>
+  );
+}
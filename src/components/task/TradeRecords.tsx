@@ -0,0 +1,78 @@
+import { abbr } from "@/app/utils";
+import { useQueryTradeRecordsCmd } from "@/hooks/ledger";
+import { Card, CardBody, CardHeader, Pagination } from "@nextui-org/react";
+import dayjs from "dayjs";
+import { useEffect, useState } from "react";
+import { MdRefresh } from "react-icons/md";
+import IconButton from "../IconButton";
+
+const PAGE_SIZE = 50;
+
+export default function TradeRecordsPanel({ taskId }: { taskId: string }) {
+  const [page, setPage] = useState(0);
+  const {
+    queryTradeRecords,
+    querying,
+    tradeRecordPage,
+    queryTradeRecordsErr,
+  } = useQueryTradeRecordsCmd();
+
+  const refresh = (toPage: number) => {
+    setPage(toPage);
+    queryTradeRecords({
+      filter: { task_id: taskId, page: toPage, page_size: PAGE_SIZE },
+    }).catch(() => {});
+  };
+
+  useEffect(() => {
+    if (taskId) {
+      refresh(0);
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [taskId]);
+
+  const total = tradeRecordPage?.total ?? 0;
+  const totalPages = Math.ceil(total / PAGE_SIZE);
+
+  return (
+    <Card className="h-[calc(100vh-5rem)] max-h-[calc(100vh-5rem)]">
+      <CardHeader>
+        <div className="flex items-center gap-1">
+          <div className="font-bold">Trades ({total} items)</div>
+          <IconButton
+            tooltip={"Refresh Trades"}
+            Icon={MdRefresh}
+            iconClassName={`${querying ? "animate-spin" : ""}`}
+            onClick={() => refresh(page)}
+          />
+        </div>
+      </CardHeader>
+      <CardBody className="h-full">
+        {queryTradeRecordsErr && (
+          <div className="text-red-500">{queryTradeRecordsErr.err_msg}</div>
+        )}
+        <div className="flex flex-col gap-0.5 overflow-scroll">
+          {(tradeRecordPage?.records ?? []).map((record) => (
+            <pre key={record.id} className="text-sm">
+              {dayjs(record.started_at).format("YY-MM-DD HH:mm:ss")} Worker{" "}
+              {record.worker_id + 1} {record.direction} {record.status}: in{" "}
+              {record.realized_input ?? record.input_amount}, out{" "}
+              {record.realized_output ?? record.quoted_output ?? "-"}
+              {record.total_fee && `, fee ${record.total_fee}`}
+              {record.txid && `, tx ${abbr(record.txid, 8)}`}
+              {record.error && `, error: ${record.error}`}
+            </pre>
+          ))}
+        </div>
+        {totalPages > 1 && (
+          <Pagination
+            className="pt-2"
+            total={totalPages}
+            page={page + 1}
+            onChange={(toPage) => refresh(toPage - 1)}
+          />
+        )}
+      </CardBody>
+    </Card>
+  );
+}
@@ -5,6 +5,9 @@ export const BSC_TX_FAKE_BASE_FEE = 0.00003;
 export const VALID_URL_PATTERN =
   /^(https?:\/\/)([\da-z\.-]+\.[a-z\.]{2,6}|[\d\.]+)([\/:?=&#]{1}[\da-z\.-]+)*[\/\?]?$/i;
 
+export const VALID_WS_URL_PATTERN =
+  /^(wss?:\/\/)([\da-z\.-]+\.[a-z\.]{2,6}|[\d\.]+)([\/:?=&#]{1}[\da-z\.-]+)*[\/\?]?$/i;
+
 export const JITO_BLOCK_ENGINE_URLS = [
   {
     name: "Tokyo (https://tokyo.mainnet.block-engine.jito.wtf)",
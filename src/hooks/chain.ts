@@ -59,6 +59,7 @@ export type AirdropReq = {
   from_pk: string;
   addrs: string[];
   per_amount: number;
+  fee_mode?: EvmFeeMode;
 };
 
 export function useAirdropCmd() {
@@ -77,6 +78,8 @@ export function useAirdropCmd() {
   };
 }
 
+export type EvmFeeMode = "Legacy" | { Eip1559: { reward_percentile: number } };
+
 export type TransferNativeReq = {
   chain: Chain;
   from_pk: string;
@@ -85,6 +88,7 @@ export type TransferNativeReq = {
    * 'max' or float number
    */
   amount: string;
+  fee_mode?: EvmFeeMode;
 };
 
 export function useTransferNativeCmd() {
@@ -102,3 +106,120 @@ export function useTransferNativeCmd() {
     transferNativeError,
   };
 }
+
+export type SolEndpointStats = {
+  endpoint: string;
+  sent: number;
+  rejected: number;
+  accepted_landed: number;
+  expired: number;
+  first_accepted: number;
+  avg_accept_ms?: number;
+};
+
+export function useGetSolEndpointStatsCmd() {
+  const {
+    invokeFn: getSolEndpointStats,
+    invoking: getting,
+    result: endpointStats,
+    error: getSolEndpointStatsError,
+  } = useCmd<SolEndpointStats[], undefined>("get_sol_endpoint_stats", []);
+
+  return {
+    getSolEndpointStats,
+    getting,
+    endpointStats,
+    getSolEndpointStatsError,
+  };
+}
+
+export type JitoRegionStats = {
+  url: string;
+  latency_ms?: number;
+  cooldown_secs?: number;
+};
+
+export function useGetJitoRegionsCmd() {
+  const {
+    invokeFn: getJitoRegions,
+    invoking: getting,
+    result: jitoRegions,
+    error: getJitoRegionsError,
+  } = useCmd<JitoRegionStats[], undefined>("get_jito_regions", []);
+
+  return {
+    getJitoRegions,
+    getting,
+    jitoRegions,
+    getJitoRegionsError,
+  };
+}
+
+export type EvmFees =
+  | { Legacy: { gas_price: number } }
+  | {
+      Eip1559: { max_fee_per_gas: number; max_priority_fee_per_gas: number };
+    };
+
+export type EvmPendingTx = {
+  from: string;
+  nonce: number;
+  tx_hashes: string[];
+  fees: EvmFees;
+  cancel_tx_hashes: string[];
+  sent_at: number;
+};
+
+export function useGetEvmPendingTxsCmd() {
+  const {
+    invokeFn: getEvmPendingTxs,
+    invoking: getting,
+    result: pendingTxs,
+    error: getEvmPendingTxsError,
+  } = useCmd<EvmPendingTx[], undefined>("get_evm_pending_txs", []);
+
+  return {
+    getEvmPendingTxs,
+    getting,
+    pendingTxs,
+    getEvmPendingTxsError,
+  };
+}
+
+export type ReplaceEvmTxReq = {
+  from_pk: string;
+  nonce: number;
+  fee_mode?: EvmFeeMode;
+};
+
+export function useSpeedUpEvmTxCmd() {
+  const {
+    invokeFn: speedUpEvmTx,
+    invoking: speedingUp,
+    result: txId,
+    error: speedUpEvmTxError,
+  } = useCmd<string, { req: ReplaceEvmTxReq }>("speed_up_evm_tx");
+
+  return {
+    speedUpEvmTx,
+    speedingUp,
+    txId,
+    speedUpEvmTxError,
+  };
+}
+
+export function useCancelEvmTxCmd() {
+  const {
+    invokeFn: cancelEvmTx,
+    invoking: canceling,
+    result: txId,
+    error: cancelEvmTxError,
+  } = useCmd<string, { req: ReplaceEvmTxReq }>("cancel_evm_tx");
+
+  return {
+    cancelEvmTx,
+    canceling,
+    txId,
+    cancelEvmTxError,
+  };
+}
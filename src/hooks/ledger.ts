@@ -0,0 +1,83 @@
+import { useCmd } from ".";
+import { TradeDirection } from "./tasks";
+
+export type TradeStatus = "Success" | "Failed" | "Dropped" | "Expired" | "Error";
+
+export type TradeRouteStep = {
+  label: string;
+  amm_key: string;
+  input_token: string;
+  output_token: string;
+  percent: number;
+};
+
+// amounts are integer strings in the smallest unit, timestamps are unix milliseconds
+export type TradeRecord = {
+  id?: number;
+  task_id: string;
+  worker_id: number;
+  wallet: string;
+  direction: TradeDirection;
+  input_mint: string;
+  output_mint: string;
+  input_amount: string;
+  quoted_output?: string;
+  realized_input?: string;
+  realized_output?: string;
+  effective_price?: number;
+  slippage_bps?: number;
+  fee?: string;
+  total_fee?: string;
+  tip?: number;
+  txid?: string;
+  proxy?: string;
+  aggregator?: string;
+  route_plan?: TradeRouteStep[];
+  bundle_id?: string;
+  bundle_state?: "Invalid" | "Pending" | "Failed" | "Landed";
+  confirmations?: number;
+  reorg_cnt?: number;
+  status: TradeStatus;
+  error?: string;
+  started_at: number;
+  finished_at: number;
+};
+
+export type TradeRecordFilter = {
+  task_id?: string;
+  worker_id?: number;
+  wallet?: string;
+  direction?: TradeDirection;
+  status?: TradeStatus;
+  txid?: string;
+  from?: number;
+  to?: number;
+  page?: number;
+  page_size?: number;
+};
+
+export type TradeRecordPage = {
+  total: number;
+  page: number;
+  page_size: number;
+  records: TradeRecord[];
+};
+
+export function useQueryTradeRecordsCmd() {
+  const {
+    invokeFn: queryTradeRecords,
+    invoking: querying,
+    result: tradeRecordPage,
+    error: queryTradeRecordsErr,
+  } = useCmd<TradeRecordPage | null, { filter: TradeRecordFilter }>(
+    "query_trade_records",
+    null,
+  );
+
+  return {
+    queryTradeRecords,
+    querying,
+    tradeRecordPage,
+    queryTradeRecordsErr,
+  };
+}
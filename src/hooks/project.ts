@@ -2,15 +2,41 @@ import { useCmd } from ".";
 
 export type Chain = "Solana" | "Base" | "Bsc";
 
+export type TlsConfig = {
+  ca_cert_paths: string[];
+  exclusive_ca_cert_paths: string[];
+  accept_invalid_certs: boolean;
+};
+
+export type AggregatorKind =
+  | "Jupiter"
+  | "OneInch"
+  | "Raydium"
+  | "UniswapV2"
+  | "UniswapV3"
+  | "PancakeSwapV2"
+  | "PancakeSwapV3";
+
+export type AggApiConfig = {
+  kind: AggregatorKind;
+  api_url: string;
+  api_key?: string;
+};
+
 export type CreateProjectReq = {
   id: string;
   name: string;
   chain: Chain;
   rpc: string;
-  jito_url?: string;
+  rpc_ws_url?: string;
+  send_rpc_urls?: string[];
+  jito_urls?: string[];
   agg_api_url: string;
   agg_api_key?: string;
+  agg_apis?: AggApiConfig[];
   proxy_urls: string[];
+  tls?: TlsConfig;
+  evm_confirmations?: number;
   save_path: string;
 };
 
@@ -42,10 +68,15 @@ export type ProjectResp = {
   main_wallet: string;
   main_wallet_pk: string;
   rpc: string;
-  jito_url?: string;
+  rpc_ws_url?: string;
+  send_rpc_urls: string[];
+  jito_urls: string[];
   agg_api_url: string;
   agg_api_key?: string;
+  agg_apis: AggApiConfig[];
   proxy_urls: string[];
+  tls: TlsConfig;
+  evm_confirmations?: number;
   wallet_grps: WalletGrpResp[];
 };
 
@@ -53,9 +84,15 @@ export type UpdateProjectReq = {
   main_wallet_pk: string;
   proxy_urls: string[];
   rpc_url: string;
-  jito_url?: string;
+  rpc_ws_url?: string;
+  send_rpc_urls?: string[];
+  jito_urls?: string[];
   agg_api_url: string;
   agg_api_key?: string;
+  agg_apis?: AggApiConfig[];
+  tls?: TlsConfig;
+  // null removes the saved confirmations, the chain default is used
+  evm_confirmations?: number | null;
 };
 
 export function useOpenProjectCmd() {
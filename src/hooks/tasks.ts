@@ -1,8 +1,54 @@
 import { useCmd } from ".";
-import { TokenInfo } from "./chain";
+import { EvmFeeMode, TokenInfo } from "./chain";
+import { AggregatorKind } from "./project";
 
 export type TradeMode = "Both" | "BuyOnly" | "SellOnly";
 
+export type TradeDirection = "Buy" | "Sell";
+
+export type SolPriorityFeeStrategy =
+  | "Fixed"
+  | { Percentile: { percentile: number; max_price: number } }
+  | { Adaptive: { step_pct: number; max_price: number } };
+
+export type JitoTipStrategy =
+  | "Fixed"
+  | { TipFloor: { percentile: number } }
+  | { Escalating: { step_pct: number } };
+
+export type EvmTxConfig = {
+  receipt_timeout_secs: number;
+  // null never replaces, the default when left out
+  replace_after_secs?: number | null;
+  replace_action: "SpeedUp" | "Cancel";
+  fee_bump_pct: number;
+};
+
+export type JupiterRouteConfig = {
+  only_direct_routes: boolean;
+  use_shared_accounts: boolean;
+  dexes?: string[];
+  excluded_dexes?: string[];
+  max_accounts?: number;
+  swap_mode: "ExactIn";
+};
+
+export type OneInchRouteConfig = {
+  protocols?: string[];
+  disable_estimate: boolean;
+  include_gas: boolean;
+};
+
+// amounts are in the native coin of the chain, end_at is a unix timestamp in seconds
+export type TaskStopConditions = {
+  max_trades?: number;
+  max_fees?: number;
+  max_notional?: number;
+  end_at?: number;
+  min_main_wallet_balance?: number;
+  min_group_balance?: number;
+};
+
 export type CreateTaskReq = {
   workers_cnt: number;
   wallet_grp_id: string;
@@ -12,7 +58,19 @@ export type CreateTaskReq = {
   slippage: number;
   use_jito: boolean;
   gas_price: number;
+  jito_tip_strategy?: JitoTipStrategy;
+  jito_max_tip?: number;
+  jito_fallback_unit_price?: number;
+  jito_bundle_size?: number;
+  priority_fee_strategy?: SolPriorityFeeStrategy;
+  compute_unit_margin_pct?: number;
+  evm_fee_mode?: EvmFeeMode;
+  evm_tx_config?: EvmTxConfig;
+  aggregators?: AggregatorKind[];
+  jupiter_route_config?: JupiterRouteConfig;
+  one_inch_route_config?: OneInchRouteConfig;
   interval_secs: number;
+  stop_conditions?: TaskStopConditions;
 };
 
 export function useCreateTradeTaskCmd() {
@@ -70,3 +128,62 @@ export function useRemoveTradeTaskCmd() {
     removeTradeTaskErr,
   };
 }
+
+export type Weekday = "Mon" | "Tue" | "Wed" | "Thu" | "Fri" | "Sat" | "Sun";
+
+// times of day are "HH:MM:SS" in local time, timestamps are unix seconds
+export type TaskScheduleKind =
+  | { Once: { start_at: number; stop_at?: number } }
+  | { Daily: { start: string; stop: string; weekdays: Weekday[] } }
+  | { Interval: { start_at: number; every_secs: number; run_secs: number } };
+
+// `task` creates the task when the schedule starts it and it doesn't exist
+export type TaskSchedule = {
+  task_id: string;
+  kind: TaskScheduleKind;
+  task: CreateTaskReq;
+};
+
+export function useGetTaskSchedulesCmd() {
+  const {
+    invokeFn: getTaskSchedules,
+    invoking: getting,
+    result: taskSchedules,
+    error: getTaskSchedulesErr,
+  } = useCmd<TaskSchedule[], undefined>("get_task_schedules", []);
+
+  return {
+    getTaskSchedules,
+    getting,
+    taskSchedules,
+    getTaskSchedulesErr,
+  };
+}
+
+export function useSetTaskScheduleCmd() {
+  const {
+    invokeFn: setTaskSchedule,
+    invoking: setting,
+    error: setTaskScheduleErr,
+  } = useCmd<null, { schedule: TaskSchedule }>("set_task_schedule", null);
+
+  return {
+    setTaskSchedule,
+    setting,
+    setTaskScheduleErr,
+  };
+}
+
+export function useRemoveTaskScheduleCmd() {
+  const {
+    invokeFn: removeTaskSchedule,
+    invoking: removing,
+    error: removeTaskScheduleErr,
+  } = useCmd<null, { id: string }>("remove_task_schedule", null);
+
+  return {
+    removeTaskSchedule,
+    removing,
+    removeTaskScheduleErr,
+  };
+}
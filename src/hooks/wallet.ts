@@ -1,4 +1,5 @@
 import { Chain, useCmd } from ".";
+import { EvmFeeMode } from "./chain";
 
 export type CreateWalletGrpReq = {
   id: string;
@@ -81,6 +82,7 @@ export type WalletGrpWithdrawReq = {
   chain: Chain;
   from_pk: string;
   addr: string;
+  fee_mode?: EvmFeeMode;
 };
 
 export function useWalletWithdrawCmd() {